@@ -42,6 +42,7 @@ fn params() -> IndexParams {
         max_connections: 16,
         ef_construction: 100,
         ef_search: 50,
+        ..IndexParams::default()
     }
 }
 
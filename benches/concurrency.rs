@@ -191,6 +191,7 @@ fn params() -> IndexParams {
         max_connections: 16,
         ef_construction: 100,
         ef_search: EF,
+        ..IndexParams::default()
     }
 }
 
@@ -163,6 +163,7 @@ fn bench_chronomind(
         max_connections: 16,
         ef_construction: 100,
         ef_search: 50,
+        ..IndexParams::default()
     };
     let metric = Arc::new(CosineDistance::new());
     let build = |threads: usize| -> (Arc<dyn VectorIndex>, Duration) {
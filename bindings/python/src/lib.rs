@@ -51,6 +51,7 @@ impl Index {
             max_connections,
             ef_construction,
             ef_search,
+            ..IndexParams::default()
         };
         let metric = Arc::new(CosineDistance::new());
         Index {
@@ -49,6 +49,7 @@ fuzz_target!(|ops: Vec<Op>| {
             max_connections: 4,
             ef_construction: 16,
             ef_search: 16,
+            ..IndexParams::default()
         },
         Arc::new(CosineDistance::new()),
         0xF022,
@@ -0,0 +1,226 @@
+//! Incremental backups: a directory of segments, each holding only what
+//! changed since the one before.
+//!
+//! [`save_snapshot`](crate::save_snapshot) rewrites the whole store on
+//! every save. [`backup_incremental`] instead writes a new segment file
+//! with just the memories written since the store's previous incremental
+//! backup — inserted, removed, decayed, accessed, relinked — and
+//! [`restore_from_dir`] replays every segment in order. The first segment
+//! written into a directory is always a full one, so a directory restores
+//! on its own whatever the store backed up elsewhere before.
+//!
+//! Segment format (version 1): 7-byte magic `CHRONOS`, one format-version
+//! byte, a little-endian CRC32 of the body, then a bincode body holding
+//! the configuration, the changed memories, and the removed ids. Segments
+//! are named `<sequence>.segment` with an eight-digit, zero-padded
+//! sequence number, and each is written atomically like a snapshot, so a
+//! crash mid-backup leaves the directory as it was.
+
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::metric::{CosineDistance, DistanceMetric};
+use crate::store::ChronoMind;
+use crate::types::Memory;
+
+const MAGIC: &[u8; 7] = b"CHRONOS";
+const FORMAT_VERSION: u8 = 1;
+const EXTENSION: &str = "segment";
+
+#[derive(Serialize, Deserialize)]
+struct SegmentBody {
+    config: Config,
+    /// Current state of every memory written since the previous segment,
+    /// in id order.
+    memories: Vec<Memory>,
+    /// Ids removed since the previous segment, in id order.
+    removed: Vec<String>,
+}
+
+/// Write the memories changed since `store`'s previous incremental backup
+/// to a new segment in `dir`, creating the directory if needed, and
+/// return how many memories and removals the segment records.
+///
+/// When nothing changed no segment is written and `0` is returned. A
+/// directory without segments gets a full one: every memory in the store.
+///
+/// The set of changes is tracked per store, not per directory: backing
+/// one store up into two directories alternately leaves each with gaps.
+/// A backup that fails keeps its changes for the next one.
+#[instrument(skip(store))]
+pub fn backup_incremental(store: &ChronoMind, dir: &Path) -> Result<usize> {
+    fs::create_dir_all(dir)?;
+    let segments = list_segments(dir)?;
+    // Consume the marks before reading any state, so that a concurrent
+    // write is either in this segment or marked for the next.
+    let changed = store.take_changed();
+
+    let (mut memories, mut removed) = (Vec::new(), Vec::new());
+    if segments.is_empty() {
+        memories = store.snapshot();
+    } else {
+        if changed.is_empty() {
+            return Ok(0);
+        }
+        for id in changed {
+            match store.get(&id) {
+                Some(memory) => memories.push(memory),
+                None => removed.push(id),
+            }
+        }
+    }
+    memories.sort_by(|a, b| a.vector.id.cmp(&b.vector.id));
+    removed.sort();
+
+    let sequence = segments.last().map_or(0, |(n, _)| n + 1);
+    let path = dir.join(format!("{sequence:08}.{EXTENSION}"));
+    let body = SegmentBody {
+        config: store.config().clone(),
+        memories,
+        removed,
+    };
+    if let Err(e) = write_segment(&body, dir, &path) {
+        // Nothing was written: the next backup must still carry these.
+        let ids = body.memories.iter().map(|m| &m.vector.id);
+        for id in ids.chain(&body.removed) {
+            store.mark_changed(id);
+        }
+        return Err(e);
+    }
+
+    let written = body.memories.len() + body.removed.len();
+    info!(
+        memories = body.memories.len(),
+        removed = body.removed.len(),
+        ?path,
+        "backup segment written"
+    );
+    Ok(written)
+}
+
+/// Rebuild a store from the segments [`backup_incremental`] wrote to
+/// `dir`, replaying them in sequence order.
+///
+/// Every segment's checksum is verified before it is applied. The store
+/// takes the configuration of the newest segment and uses cosine
+/// distance; stores built with a custom metric must be restored with
+/// [`restore_from_dir_with_metric`].
+pub fn restore_from_dir(dir: &Path) -> Result<ChronoMind> {
+    restore_from_dir_with_metric(dir, Arc::new(CosineDistance::new()))
+}
+
+/// Rebuild a store from backup segments, indexing with `metric`.
+///
+/// Like snapshots, segments do not record the metric, so pass the same
+/// one the backed-up store was created with.
+#[instrument(skip(metric), fields(metric = metric.name()))]
+pub fn restore_from_dir_with_metric(
+    dir: &Path,
+    metric: Arc<dyn DistanceMetric>,
+) -> Result<ChronoMind> {
+    let segments = list_segments(dir)?;
+    let Some((_, newest)) = segments.last() else {
+        return Err(Error::InvalidSnapshot(format!(
+            "no backup segments in {}",
+            dir.display()
+        )));
+    };
+    let config = read_segment(newest)?.config;
+    let store = ChronoMind::with_metric(config, metric)?;
+    for (_, path) in &segments {
+        let body = read_segment(path)?;
+        for id in &body.removed {
+            store.remove(id);
+        }
+        for memory in body.memories {
+            // A segment records whole memories; replacing in place would
+            // merge back links the store had since dropped.
+            store.remove(&memory.vector.id);
+            store.insert(memory)?;
+        }
+    }
+    store.take_changed();
+
+    info!(
+        memories = store.len(),
+        segments = segments.len(),
+        ?dir,
+        "backup restored"
+    );
+    Ok(store)
+}
+
+/// Atomically write `body` as the segment at `path`, in `dir`.
+fn write_segment(body: &SegmentBody, dir: &Path, path: &Path) -> Result<()> {
+    let encoded = bincode::serialize(body)?;
+    let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+    temp.write_all(MAGIC)?;
+    temp.write_all(&[FORMAT_VERSION])?;
+    temp.write_all(&crc32fast::hash(&encoded).to_le_bytes())?;
+    temp.write_all(&encoded)?;
+    temp.flush()?;
+    temp.as_file().sync_all()?;
+    // Never overwrite: two backups racing for one sequence number must not
+    // silently drop either's changes.
+    temp.persist_noclobber(path)
+        .map_err(|e| Error::Io(e.error))?;
+    Ok(())
+}
+
+/// The segment files in `dir` with their sequence numbers, in order.
+fn list_segments(dir: &Path) -> Result<Vec<(u64, PathBuf)>> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(EXTENSION) {
+            continue;
+        }
+        let sequence = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse::<u64>().ok());
+        if let Some(sequence) = sequence {
+            segments.push((sequence, path));
+        }
+    }
+    segments.sort_by_key(|(sequence, _)| *sequence);
+    Ok(segments)
+}
+
+fn read_segment(path: &Path) -> Result<SegmentBody> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header).map_err(|_| {
+        Error::InvalidSnapshot(format!("{} is too short to be a segment", path.display()))
+    })?;
+    if &header[..7] != MAGIC {
+        return Err(Error::InvalidSnapshot(format!(
+            "bad magic bytes: {} is not a ChronoMind backup segment",
+            path.display()
+        )));
+    }
+    if header[7] != FORMAT_VERSION {
+        return Err(Error::InvalidSnapshot(format!(
+            "unsupported backup segment version {} (supported: {FORMAT_VERSION})",
+            header[7]
+        )));
+    }
+    let expected = u32::from_le_bytes(header[8..].try_into().expect("4 bytes"));
+    let mut encoded = Vec::new();
+    reader.read_to_end(&mut encoded)?;
+    let actual = crc32fast::hash(&encoded);
+    if actual != expected {
+        return Err(Error::InvalidSnapshot(format!(
+            "{}: body checksum mismatch (expected {expected:08x}, got {actual:08x})",
+            path.display()
+        )));
+    }
+    Ok(bincode::deserialize(&encoded)?)
+}
@@ -1,5 +1,7 @@
 //! Store and index configuration.
 
+use std::path::Path;
+
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
@@ -8,6 +10,7 @@ use crate::error::{Error, Result};
 ///
 /// These follow the standard HNSW nomenclature from Malkov & Yashunin (2018).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct IndexParams {
     /// Number of bidirectional links created per node and layer (`M`).
     ///
@@ -22,6 +25,32 @@ pub struct IndexParams {
     /// Searches always explore at least this many candidates before temporal
     /// reranking; raising it trades latency for recall.
     pub ef_search: usize,
+
+    /// Upper bound on the graph nodes one search may evaluate, across all
+    /// layers. `None` (the default) lets a search run to convergence.
+    ///
+    /// On a poorly connected graph a search can touch a large fraction of
+    /// the index; a budget bounds that tail latency at a recall cost. When
+    /// the budget runs out the search returns the best candidates found so
+    /// far (see [`ChronoMind::search_with_stats`](crate::ChronoMind::search_with_stats)).
+    /// Index construction is never budgeted, and only the lock-free index
+    /// (the one the store uses) honors the setting. Exhaustive scans of
+    /// small stores (see [`Config::brute_force_threshold`]) are not
+    /// budgeted either.
+    pub max_nodes_visited: Option<usize>,
+
+    /// Seed for HNSW layer assignment and for the
+    /// [`Random`](EntryStrategy::Random) entry strategy's draws. `None`
+    /// (the default) draws one from entropy.
+    ///
+    /// These are the only random decisions in the crate, so with a fixed
+    /// seed and single-threaded use, identical operations build identical
+    /// graphs and give identical search results.
+    pub seed: Option<u64>,
+
+    /// Which node a search starts its descent from. Only the lock-free
+    /// index (the one the store uses) honors the setting.
+    pub entry_strategy: EntryStrategy,
 }
 
 impl Default for IndexParams {
@@ -30,16 +59,110 @@ impl Default for IndexParams {
             max_connections: 16,
             ef_construction: 200,
             ef_search: 50,
+            max_nodes_visited: None,
+            seed: None,
+            entry_strategy: EntryStrategy::default(),
         }
     }
 }
 
+/// Where an HNSW search enters the graph (see
+/// [`IndexParams::entry_strategy`]).
+///
+/// Inserts always link in from the highest-layer node; the strategy only
+/// changes where searches start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntryStrategy {
+    /// The live node with the highest top layer, descending through every
+    /// layer: standard HNSW. The index tracks this node as nodes are
+    /// inserted, and removing it moves the entry point to the highest
+    /// surviving node, at the cost of one scan of the index.
+    #[default]
+    HighestLayer,
+    /// The most recently inserted node, descending from its own top
+    /// layer. Starts searches in the newest region of the graph, which
+    /// suits query streams that follow the insert stream.
+    MostRecent,
+    /// A uniformly random node, descending from its own top layer.
+    /// Spreads load on the upper layers' few nodes at some recall cost.
+    /// Draws come from the index's [`seed`](IndexParams::seed), so a fixed
+    /// seed repeats the same sequence of entry points.
+    Random,
+}
+
+/// Score thresholds that bucket search results into qualitative bands, as
+/// returned by [`ChronoMind::search_banded`](crate::ChronoMind::search_banded).
+///
+/// Scores are lower-is-better (see
+/// [`ChronoMind::search`](crate::ChronoMind::search)), so each threshold is
+/// the inclusive upper bound of its band: a score of exactly `good` is
+/// `"good"`, anything above `fair` is `"weak"`. Thresholds must be finite
+/// and non-decreasing.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreBands {
+    /// Upper bound of the `"excellent"` band.
+    pub excellent: f32,
+    /// Upper bound of the `"good"` band.
+    pub good: f32,
+    /// Upper bound of the `"fair"` band.
+    pub fair: f32,
+}
+
+impl Default for ScoreBands {
+    fn default() -> Self {
+        Self {
+            excellent: 0.1,
+            good: 0.25,
+            fair: 0.5,
+        }
+    }
+}
+
+impl ScoreBands {
+    /// The band a score falls into: `"excellent"`, `"good"`, `"fair"`, or
+    /// `"weak"`.
+    pub fn band(&self, score: f32) -> &'static str {
+        if score <= self.excellent {
+            "excellent"
+        } else if score <= self.good {
+            "good"
+        } else if score <= self.fair {
+            "fair"
+        } else {
+            "weak"
+        }
+    }
+}
+
+/// How a store keeps the vector of each memory record.
+///
+/// The HNSW index always keeps its own full-precision copy for traversal;
+/// this controls the record copy that [`get`](crate::ChronoMind::get),
+/// context scans, and snapshots read. Lower precisions make those reads
+/// lossy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Precision {
+    /// Full `f32` components.
+    #[default]
+    F32,
+    /// IEEE half-precision components: half the size, about three
+    /// significant decimal digits. Distances are computed in `f32` after
+    /// widening.
+    F16,
+    /// One byte per component, scalar-quantized per vector (see
+    /// [`QuantizedVector`](crate::quantization::QuantizedVector)).
+    Int8,
+}
+
 /// Configuration for a [`ChronoMind`](crate::ChronoMind) store.
 ///
-/// Construct with [`Config::default`] and adjust fields, or use
-/// [`Config::builder`] for a fluent interface. All configurations are
-/// validated when the store is created.
+/// Construct with [`Config::default`] and adjust fields, use
+/// [`Config::builder`] for a fluent interface, or load a file with
+/// [`Config::from_toml_path`] or [`Config::from_yaml_path`]. All
+/// configurations are validated when the store is created.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     /// Dimensionality every stored vector must have.
     pub dimensions: usize,
@@ -59,15 +182,50 @@ pub struct Config {
     /// See [`ChronoMind::search`](crate::ChronoMind::search) for the exact formula.
     pub temporal_weight: f32,
 
-    /// Cosine similarity above which two memories are considered duplicates
-    /// by [`consolidate`](crate::ChronoMind::consolidate), in `(0.0, 1.0)`.
+    /// Similarity above which two memories are considered duplicates by
+    /// [`consolidate`](crate::ChronoMind::consolidate), in `(0.0, 1.0)`.
+    ///
+    /// Measured by the store metric's
+    /// [`similarity`](crate::DistanceMetric::similarity): the cosine under
+    /// the default metric, `1 / (1 + distance)` under Euclidean.
     pub similarity_threshold: f32,
 
     /// Maximum number of relationship links kept per memory.
     pub max_relationships: usize,
 
+    /// Nearest neighbors each memory is compared with by
+    /// [`consolidate`](crate::ChronoMind::consolidate). Must be at least 1.
+    pub consolidation_neighbors: usize,
+
+    /// Whether [`consolidate`](crate::ChronoMind::consolidate) redirects
+    /// links to each absorbed memory to the memory that absorbed it,
+    /// dropping the self-links and duplicates that creates — merging two
+    /// memories that link each other then breaks that cycle. Off by
+    /// default, which leaves such links dangling.
+    pub consolidation_relinks: bool,
+
     /// HNSW index parameters.
     pub index: IndexParams,
+
+    /// Thresholds for [`search_banded`](crate::ChronoMind::search_banded).
+    pub score_bands: ScoreBands,
+
+    /// Storage precision of record vectors.
+    pub precision: Precision,
+
+    /// Stores holding fewer memories than this are searched by an exact
+    /// scan of every record instead of the HNSW graph: at that size the
+    /// scan is cheap and its results are exact. `0` always uses the graph.
+    /// The scan is parallel with the `rayon` feature.
+    pub brute_force_threshold: usize,
+
+    /// Over-fetch factor for temporal reranking: searches draw
+    /// `max(ef_search, rerank_overfetch * k)` geometric candidates from the
+    /// index before [`search`](crate::ChronoMind::search) reranks them.
+    /// A high [`temporal_weight`](Self::temporal_weight) can promote fresh
+    /// memories from far down the geometric order, and only candidates in
+    /// that pool can be promoted. Must be at least 1.
+    pub rerank_overfetch: usize,
 }
 
 impl Default for Config {
@@ -79,7 +237,13 @@ impl Default for Config {
             temporal_weight: 0.3,
             similarity_threshold: 0.95,
             max_relationships: 50,
+            consolidation_neighbors: 10,
+            consolidation_relinks: false,
             index: IndexParams::default(),
+            score_bands: ScoreBands::default(),
+            precision: Precision::default(),
+            brute_force_threshold: 1_000,
+            rerank_overfetch: 4,
         }
     }
 }
@@ -92,6 +256,39 @@ impl Config {
         }
     }
 
+    /// Load and validate a configuration from a TOML file.
+    ///
+    /// Fields missing from the file take their defaults, so a file only
+    /// needs the settings it changes:
+    ///
+    /// ```toml
+    /// dimensions = 384
+    /// temporal_weight = 0.5
+    ///
+    /// [index]
+    /// ef_search = 100
+    /// ```
+    ///
+    /// Parse errors and out-of-range values are reported as
+    /// [`Error::Config`].
+    pub fn from_toml_path(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let config: Self =
+            toml::from_str(&text).map_err(|e| Error::Config(format!("{}: {e}", path.display())))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load and validate a configuration from a YAML file. Behaves like
+    /// [`from_toml_path`](Self::from_toml_path).
+    pub fn from_yaml_path(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let config: Self = serde_yaml::from_str(&text)
+            .map_err(|e| Error::Config(format!("{}: {e}", path.display())))?;
+        config.validate()?;
+        Ok(config)
+    }
+
     /// Validate the configuration, returning a descriptive error for the
     /// first violated constraint.
     pub fn validate(&self) -> Result<()> {
@@ -124,6 +321,16 @@ impl Config {
                 "max_relationships must be greater than 0".into(),
             ));
         }
+        if self.consolidation_neighbors == 0 {
+            return Err(Error::Config(
+                "consolidation_neighbors must be greater than 0".into(),
+            ));
+        }
+        if self.rerank_overfetch == 0 {
+            return Err(Error::Config(
+                "rerank_overfetch must be greater than 0".into(),
+            ));
+        }
         if self.index.max_connections < 2 {
             return Err(Error::Config(
                 "index.max_connections must be at least 2".into(),
@@ -139,6 +346,22 @@ impl Config {
                 "index.ef_search must be greater than 0".into(),
             ));
         }
+        if self.index.max_nodes_visited == Some(0) {
+            return Err(Error::Config(
+                "index.max_nodes_visited must be greater than 0 when set".into(),
+            ));
+        }
+        let bands = &self.score_bands;
+        if ![bands.excellent, bands.good, bands.fair]
+            .iter()
+            .all(|t| t.is_finite())
+            || bands.excellent > bands.good
+            || bands.good > bands.fair
+        {
+            return Err(Error::Config(
+                "score_bands must be finite and non-decreasing".into(),
+            ));
+        }
         Ok(())
     }
 }
@@ -186,12 +409,48 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set how many neighbors consolidation compares each memory with.
+    pub fn consolidation_neighbors(mut self, neighbors: usize) -> Self {
+        self.config.consolidation_neighbors = neighbors;
+        self
+    }
+
+    /// Set whether consolidation redirects links to absorbed memories.
+    pub fn consolidation_relinks(mut self, relinks: bool) -> Self {
+        self.config.consolidation_relinks = relinks;
+        self
+    }
+
     /// Set the HNSW index parameters.
     pub fn index(mut self, index: IndexParams) -> Self {
         self.config.index = index;
         self
     }
 
+    /// Set the search score bands.
+    pub fn score_bands(mut self, bands: ScoreBands) -> Self {
+        self.config.score_bands = bands;
+        self
+    }
+
+    /// Set the storage precision of record vectors.
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.config.precision = precision;
+        self
+    }
+
+    /// Set the store size below which searches scan every record.
+    pub fn brute_force_threshold(mut self, threshold: usize) -> Self {
+        self.config.brute_force_threshold = threshold;
+        self
+    }
+
+    /// Set how many candidates per result searches draw before reranking.
+    pub fn rerank_overfetch(mut self, factor: usize) -> Self {
+        self.config.rerank_overfetch = factor;
+        self
+    }
+
     /// Validate and produce the configuration.
     pub fn build(self) -> Result<Config> {
         self.config.validate()?;
@@ -220,8 +479,78 @@ mod tests {
         assert_eq!(config.max_memories, 10_000);
     }
 
+    #[test]
+    fn builder_defaults_fields_it_does_not_set() {
+        let config = Config::builder()
+            .dimensions(64)
+            .temporal_weight(0.7)
+            .build()
+            .unwrap();
+        let expected = Config {
+            dimensions: 64,
+            temporal_weight: 0.7,
+            ..Config::default()
+        };
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn builder_validates_on_build() {
+        assert!(matches!(
+            Config::builder().dimensions(0).build(),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn band_bounds_are_inclusive() {
+        let bands = ScoreBands {
+            excellent: 0.1,
+            good: 0.2,
+            fair: 0.3,
+        };
+        assert_eq!(bands.band(0.0), "excellent");
+        assert_eq!(bands.band(0.1), "excellent");
+        assert_eq!(bands.band(0.15), "good");
+        assert_eq!(bands.band(0.2), "good");
+        assert_eq!(bands.band(0.3), "fair");
+        assert_eq!(bands.band(0.31), "weak");
+    }
+
     type Mutation = Box<dyn Fn(&mut Config)>;
 
+    #[test]
+    fn boundary_values_are_accepted() {
+        let cases: Vec<Mutation> = vec![
+            Box::new(|c| c.dimensions = 1),
+            Box::new(|c| c.max_memories = 1),
+            Box::new(|c| c.temporal_weight = 0.0),
+            Box::new(|c| c.temporal_weight = 1.0),
+            Box::new(|c| c.max_relationships = 1),
+            Box::new(|c| c.consolidation_neighbors = 1),
+            Box::new(|c| c.consolidation_relinks = true),
+            Box::new(|c| c.rerank_overfetch = 1),
+            Box::new(|c| {
+                c.index.max_connections = 2;
+                c.index.ef_construction = 2;
+            }),
+            Box::new(|c| c.index.ef_search = 1),
+            Box::new(|c| c.index.max_nodes_visited = Some(1)),
+            Box::new(|c| {
+                c.score_bands = ScoreBands {
+                    excellent: 0.2,
+                    good: 0.2,
+                    fair: 0.2,
+                }
+            }),
+        ];
+        for (i, mutate) in cases.iter().enumerate() {
+            let mut config = Config::default();
+            mutate(&mut config);
+            assert!(config.validate().is_ok(), "case {i} should be accepted");
+        }
+    }
+
     #[test]
     fn invalid_configs_are_rejected() {
         let cases: Vec<Mutation> = vec![
@@ -234,9 +563,14 @@ mod tests {
             Box::new(|c| c.similarity_threshold = 0.0),
             Box::new(|c| c.similarity_threshold = 1.0),
             Box::new(|c| c.max_relationships = 0),
+            Box::new(|c| c.consolidation_neighbors = 0),
+            Box::new(|c| c.rerank_overfetch = 0),
             Box::new(|c| c.index.max_connections = 1),
             Box::new(|c| c.index.ef_construction = 1),
             Box::new(|c| c.index.ef_search = 0),
+            Box::new(|c| c.index.max_nodes_visited = Some(0)),
+            Box::new(|c| c.score_bands.good = f32::NAN),
+            Box::new(|c| c.score_bands.excellent = 0.9),
         ];
         for (i, mutate) in cases.iter().enumerate() {
             let mut config = Config::default();
@@ -18,6 +18,18 @@ pub enum Error {
         expected: usize,
     },
 
+    /// A query vector's dimensionality does not match the store it searches.
+    ///
+    /// Distinct from [`InvalidDimensions`](Self::InvalidDimensions), which
+    /// reports a vector being stored or fitted with the wrong length.
+    #[error("query has {query} dimensions but the store's vectors have {expected}")]
+    QueryDimensionMismatch {
+        /// Dimensions of the query.
+        query: usize,
+        /// Dimensions the store was configured for.
+        expected: usize,
+    },
+
     /// A vector contains invalid data (empty, NaN, or infinite components).
     #[error("invalid vector data: {0}")]
     InvalidVector(String),
@@ -26,6 +38,10 @@ pub enum Error {
     #[error("invalid importance value {0}: must be within [0.0, 1.0]")]
     InvalidImportance(f32),
 
+    /// A search or maintenance parameter is outside its valid range.
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+
     /// No memory exists with the given id.
     #[error("memory not found: {0}")]
     NotFound(String),
@@ -42,6 +58,10 @@ pub enum Error {
     )]
     IndexFull(usize),
 
+    /// The store was opened read-only and the operation would mutate it.
+    #[error("store is read-only")]
+    ReadOnly,
+
     /// The configuration failed validation.
     #[error("invalid configuration: {0}")]
     Config(String),
@@ -57,6 +77,12 @@ pub enum Error {
     /// A snapshot file is not a ChronoMind snapshot or uses an unsupported format version.
     #[error("invalid snapshot: {0}")]
     InvalidSnapshot(String),
+
+    /// A SQLite operation of a [`SqliteStore`](crate::sqlite::SqliteStore)
+    /// failed.
+    #[cfg(feature = "sqlite")]
+    #[error("database error: {0}")]
+    Database(#[from] rusqlite::Error),
 }
 
 impl From<serde_json::Error> for Error {
@@ -0,0 +1,48 @@
+//! Index quality evaluation.
+//!
+//! HNSW trades exactness for speed; how much it gives up depends on the
+//! data and on [`IndexParams`](crate::IndexParams). The functions here
+//! measure that against an exhaustive scan, so tuning can be checked on
+//! the store's own contents rather than guessed.
+
+use std::collections::HashSet;
+
+use crate::error::{Error, Result};
+use crate::store::ChronoMind;
+
+/// Mean recall@k of the store's index over `queries`.
+///
+/// For each query, the `k` nearest memories the index finds — the
+/// candidates [`ChronoMind::search`] ranks, before temporal scoring — are
+/// compared with the true `k` nearest under the store's metric, found by
+/// scanning every record. Recall is the fraction of the true neighbors the
+/// index found, averaged over queries; `1.0` means the index is exact on
+/// this workload. A query against a store with fewer than `k` memories is
+/// scored against all of them.
+///
+/// The scan reads record vectors, so on a store with a lossy
+/// [`Precision`](crate::Precision) the reference is itself approximate.
+/// Costs one search plus `O(n)` distance computations per query.
+pub fn recall_at_k(store: &ChronoMind, queries: &[Vec<f32>], k: usize) -> Result<f64> {
+    if k == 0 {
+        return Err(Error::InvalidArgument("k must be greater than 0".into()));
+    }
+    if queries.is_empty() {
+        return Err(Error::InvalidArgument(
+            "recall needs at least one query".into(),
+        ));
+    }
+    let mut total = 0.0;
+    for query in queries {
+        let exact = store.exact_neighbors(query, k)?;
+        if exact.is_empty() {
+            // Nothing to find, nothing missed.
+            total += 1.0;
+            continue;
+        }
+        let found: HashSet<String> = store.approximate_neighbors(query, k)?.into_iter().collect();
+        let hits = exact.iter().filter(|id| found.contains(*id)).count();
+        total += hits as f64 / exact.len() as f64;
+    }
+    Ok(total / queries.len() as f64)
+}
@@ -0,0 +1,234 @@
+//! C ABI over the core store, for embedding from Python (cffi/ctypes), Go
+//! (cgo), and other languages with a C FFI. Enabled by the `ffi` feature.
+//!
+//! Build a shared library with
+//!
+//! ```text
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! and declare, in C:
+//!
+//! ```c
+//! typedef struct ChronoMind ChronoMind;
+//!
+//! int32_t chrono_mind_init(size_t dimensions, ChronoMind **out);
+//! int32_t chrono_mind_save(ChronoMind *store, const char *id,
+//!                          const float *vector, size_t len);
+//! int32_t chrono_mind_search(const ChronoMind *store, const float *query,
+//!                            size_t len, size_t k, char **out_ids,
+//!                            float *out_scores, size_t *out_count);
+//! void chrono_mind_free_string(char *id);
+//! void chrono_mind_free(ChronoMind *store);
+//! ```
+//!
+//! A store is an opaque handle owned by the caller from `chrono_mind_init`
+//! until `chrono_mind_free`. Every fallible function returns a status code
+//! (`CHRONO_MIND_OK` on success, a negative value otherwise; see the
+//! constants below) and writes results only through out-pointers, only on
+//! success. Panics never cross the boundary: they are caught and reported
+//! as [`CHRONO_MIND_PANIC`].
+//!
+//! The handle is as thread-safe as [`ChronoMind`]: `save` and `search` may
+//! be called concurrently from any number of threads. `free` must not race
+//! any other call on the same handle.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::store::ChronoMind;
+use crate::types::{Memory, Vector};
+
+/// Success.
+pub const CHRONO_MIND_OK: i32 = 0;
+/// A required pointer argument was null.
+pub const CHRONO_MIND_NULL_POINTER: i32 = -1;
+/// An id was not valid UTF-8.
+pub const CHRONO_MIND_INVALID_UTF8: i32 = -2;
+/// A vector or query had the wrong number of dimensions.
+pub const CHRONO_MIND_DIMENSION_MISMATCH: i32 = -3;
+/// A vector contained NaN or infinite components, or was empty.
+pub const CHRONO_MIND_INVALID_VECTOR: i32 = -4;
+/// An argument was out of range (including an invalid configuration).
+pub const CHRONO_MIND_INVALID_ARGUMENT: i32 = -5;
+/// The store is at its configured capacity.
+pub const CHRONO_MIND_CAPACITY_EXCEEDED: i32 = -6;
+/// Any other store error.
+pub const CHRONO_MIND_ERROR: i32 = -99;
+/// A panic was caught at the boundary.
+pub const CHRONO_MIND_PANIC: i32 = -100;
+
+fn status(error: &Error) -> i32 {
+    match error {
+        Error::InvalidDimensions { .. } | Error::QueryDimensionMismatch { .. } => {
+            CHRONO_MIND_DIMENSION_MISMATCH
+        }
+        Error::InvalidVector(_) => CHRONO_MIND_INVALID_VECTOR,
+        Error::InvalidArgument(_) | Error::InvalidImportance(_) | Error::Config(_) => {
+            CHRONO_MIND_INVALID_ARGUMENT
+        }
+        Error::CapacityExceeded(_) | Error::IndexFull(_) => CHRONO_MIND_CAPACITY_EXCEEDED,
+        _ => CHRONO_MIND_ERROR,
+    }
+}
+
+/// Run `body`, turning a panic into [`CHRONO_MIND_PANIC`].
+fn guarded(body: impl FnOnce() -> i32) -> i32 {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(CHRONO_MIND_PANIC)
+}
+
+/// Create a store for `dimensions`-dimensional vectors with the default
+/// configuration, writing its handle to `*out`.
+///
+/// # Safety
+///
+/// `out` must be null or valid for a pointer-sized write.
+#[no_mangle]
+pub unsafe extern "C" fn chrono_mind_init(dimensions: usize, out: *mut *mut ChronoMind) -> i32 {
+    guarded(|| {
+        if out.is_null() {
+            return CHRONO_MIND_NULL_POINTER;
+        }
+        let config = Config {
+            dimensions,
+            ..Config::default()
+        };
+        match ChronoMind::new(config) {
+            Ok(store) => {
+                // SAFETY: `out` is non-null and writable per the contract.
+                unsafe { *out = Box::into_raw(Box::new(store)) };
+                CHRONO_MIND_OK
+            }
+            Err(e) => status(&e),
+        }
+    })
+}
+
+/// Insert (or replace) the memory `id` with the `len` floats at `vector`.
+///
+/// # Safety
+///
+/// `store` must be null or a live handle from [`chrono_mind_init`]; `id`
+/// must be null or a NUL-terminated string; `vector` must be null or
+/// valid for reads of `len` floats.
+#[no_mangle]
+pub unsafe extern "C" fn chrono_mind_save(
+    store: *mut ChronoMind,
+    id: *const c_char,
+    vector: *const f32,
+    len: usize,
+) -> i32 {
+    guarded(|| {
+        if store.is_null() || id.is_null() || vector.is_null() {
+            return CHRONO_MIND_NULL_POINTER;
+        }
+        // SAFETY: all three pointers are non-null and valid per the contract.
+        let (store, id, data) = unsafe {
+            (
+                &*store,
+                CStr::from_ptr(id),
+                std::slice::from_raw_parts(vector, len),
+            )
+        };
+        let Ok(id) = id.to_str() else {
+            return CHRONO_MIND_INVALID_UTF8;
+        };
+        match store.insert(Memory::from_vector(Vector::new(id, data.to_vec()))) {
+            Ok(()) => CHRONO_MIND_OK,
+            Err(e) => status(&e),
+        }
+    })
+}
+
+/// Search for the `k` memories most relevant to the `len` floats at
+/// `query`, as [`ChronoMind::search`] does.
+///
+/// Writes up to `k` results, best first: ids to `out_ids` and scores to
+/// `out_scores`, then the number written to `*out_count`. Each id is a
+/// new string the caller owns and must release with
+/// [`chrono_mind_free_string`].
+///
+/// # Safety
+///
+/// `store` must be null or a live handle from [`chrono_mind_init`];
+/// `query` must be null or valid for reads of `len` floats; `out_ids` and
+/// `out_scores` must be null or valid for writes of `k` elements;
+/// `out_count` must be null or valid for one write.
+#[no_mangle]
+pub unsafe extern "C" fn chrono_mind_search(
+    store: *const ChronoMind,
+    query: *const f32,
+    len: usize,
+    k: usize,
+    out_ids: *mut *mut c_char,
+    out_scores: *mut f32,
+    out_count: *mut usize,
+) -> i32 {
+    guarded(|| {
+        if store.is_null()
+            || query.is_null()
+            || out_ids.is_null()
+            || out_scores.is_null()
+            || out_count.is_null()
+        {
+            return CHRONO_MIND_NULL_POINTER;
+        }
+        // SAFETY: non-null and valid per the contract.
+        let (store, query) = unsafe { (&*store, std::slice::from_raw_parts(query, len)) };
+        let results = match store.search(query, k) {
+            Ok(results) => results,
+            Err(e) => return status(&e),
+        };
+        // Ids containing NUL cannot become C strings; check them all
+        // before handing out any allocation.
+        let Ok(ids) = results
+            .iter()
+            .map(|(memory, _)| CString::new(memory.vector.id.as_str()))
+            .collect::<Result<Vec<CString>, _>>()
+        else {
+            return CHRONO_MIND_INVALID_UTF8;
+        };
+        for (i, (id, (_, score))) in ids.into_iter().zip(&results).enumerate() {
+            // SAFETY: `i < results.len() <= k`, within the caller's buffers.
+            unsafe {
+                *out_ids.add(i) = id.into_raw();
+                *out_scores.add(i) = *score;
+            }
+        }
+        // SAFETY: non-null and writable per the contract.
+        unsafe { *out_count = results.len() };
+        CHRONO_MIND_OK
+    })
+}
+
+/// Release an id returned by [`chrono_mind_search`]. A null `id` is a
+/// no-op.
+///
+/// # Safety
+///
+/// `id` must be null or a string from [`chrono_mind_search`] not already
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn chrono_mind_free_string(id: *mut c_char) {
+    if !id.is_null() {
+        // SAFETY: `id` came from `CString::into_raw` and is freed once.
+        drop(unsafe { CString::from_raw(id) });
+    }
+}
+
+/// Destroy a store and release everything it holds. A null `store` is a
+/// no-op.
+///
+/// # Safety
+///
+/// `store` must be null or a handle from [`chrono_mind_init`] not already
+/// freed, with no other call on it in progress or to come.
+#[no_mangle]
+pub unsafe extern "C" fn chrono_mind_free(store: *mut ChronoMind) {
+    if !store.is_null() {
+        // SAFETY: `store` came from `Box::into_raw` and is freed once.
+        drop(unsafe { Box::from_raw(store) });
+    }
+}
@@ -0,0 +1,226 @@
+//! Exact nearest-neighbor search by linear scan.
+//!
+//! No graph, no approximation: every search compares the query with every
+//! live vector and returns the true nearest under the
+//! index's metric. That makes it the trustworthy reference the HNSW
+//! indexes are measured against, and a perfectly good index for stores
+//! small enough that a scan beats graph traversal. With the `rayon`
+//! feature the scan runs on rayon's thread pool.
+//!
+//! Deletion frees the vector immediately — there is no graph for a
+//! tombstone to keep routing — though handles, as with every index here,
+//! are never reused.
+
+use std::sync::{Arc, RwLock};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::{top_k_by, TotalF32, VectorIndex};
+use crate::metric::DistanceMetric;
+
+struct Inner {
+    /// Preprocessed vectors by handle; `None` once removed.
+    slots: Vec<Option<Box<[f32]>>>,
+    live: usize,
+}
+
+/// An exact, scan-based index. See the module docs.
+pub struct BruteForceIndex {
+    metric: Arc<dyn DistanceMetric>,
+    inner: RwLock<Inner>,
+}
+
+impl BruteForceIndex {
+    /// Create an empty index comparing vectors under `metric`.
+    pub fn new(metric: Arc<dyn DistanceMetric>) -> Self {
+        Self {
+            metric,
+            inner: RwLock::new(Inner {
+                slots: Vec::new(),
+                live: 0,
+            }),
+        }
+    }
+
+    /// The `ef` nearest live vectors among the handles `accept` passes.
+    fn scan(
+        &self,
+        query: &[f32],
+        ef: usize,
+        accept: impl Fn(u32) -> bool + Sync,
+    ) -> Vec<(u32, f32)> {
+        let query = self.metric.preprocess(query);
+        let inner = self.inner.read().expect("index lock poisoned");
+        #[cfg(feature = "rayon")]
+        let slots = inner.slots.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let slots = inner.slots.iter();
+        let scored: Vec<(TotalF32, u32)> = slots
+            .enumerate()
+            .filter_map(|(handle, slot)| {
+                let handle = handle as u32;
+                let vector = slot.as_deref().filter(|_| accept(handle))?;
+                let distance = self.metric.distance_prepared(&query, vector);
+                Some((TotalF32(distance), handle))
+            })
+            .collect();
+        top_k_by(scored, ef, Ord::cmp)
+            .into_iter()
+            .map(|(d, id)| (id, d.0))
+            .collect()
+    }
+}
+
+impl VectorIndex for BruteForceIndex {
+    fn insert(&self, vector: &[f32]) -> Option<u32> {
+        let prepared = self.metric.preprocess(vector).into_boxed_slice();
+        let mut inner = self.inner.write().expect("index lock poisoned");
+        let handle = u32::try_from(inner.slots.len()).ok()?;
+        inner.slots.push(Some(prepared));
+        inner.live += 1;
+        Some(handle)
+    }
+
+    fn remove(&self, id: u32) -> bool {
+        let mut inner = self.inner.write().expect("index lock poisoned");
+        match inner.slots.get_mut(id as usize).and_then(Option::take) {
+            Some(_) => {
+                inner.live -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn search(&self, query: &[f32], ef: usize) -> Vec<(u32, f32)> {
+        self.scan(query, ef, |_| true)
+    }
+
+    /// One scan, skipping rejected handles before computing their
+    /// distance.
+    fn search_filtered(
+        &self,
+        query: &[f32],
+        ef: usize,
+        filter: &(dyn Fn(u32) -> bool + Sync),
+    ) -> Vec<(u32, f32)> {
+        self.scan(query, ef, filter)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.read().expect("index lock poisoned").live
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+    use crate::metric::{CosineDistance, DistanceMetric, EuclideanDistance};
+
+    fn index() -> BruteForceIndex {
+        BruteForceIndex::new(Arc::new(CosineDistance::new()))
+    }
+
+    #[test]
+    fn empty_index_returns_nothing() {
+        let idx = index();
+        assert!(idx.search(&[1.0, 0.0], 10).is_empty());
+        assert!(idx.is_empty());
+    }
+
+    #[test]
+    fn single_vector_is_found() {
+        let idx = index();
+        let id = idx.insert(&[1.0, 0.0]).unwrap();
+        let results = idx.search(&[1.0, 0.0], 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, id);
+        assert!(results[0].1 < 1e-6);
+    }
+
+    #[test]
+    fn nearest_is_ranked_first() {
+        let idx = index();
+        let near = idx.insert(&[1.0, 0.05]).unwrap();
+        let _far = idx.insert(&[0.0, 1.0]).unwrap();
+        let _mid = idx.insert(&[0.5, 0.5]).unwrap();
+
+        let results = idx.search(&[1.0, 0.0], 3);
+        assert_eq!(results[0].0, near);
+        assert!(results.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(idx.search(&[1.0, 0.0], 2).len(), 2);
+    }
+
+    #[test]
+    fn removed_vectors_disappear_and_handles_are_not_reused() {
+        let idx = index();
+        let a = idx.insert(&[1.0, 0.0]).unwrap();
+        let b = idx.insert(&[0.9, 0.1]).unwrap();
+
+        assert!(idx.remove(a));
+        assert!(!idx.remove(a), "double remove reports false");
+        assert!(!idx.remove(99), "unknown handles report false");
+        assert_eq!(idx.len(), 1);
+
+        let results = idx.search(&[1.0, 0.0], 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, b);
+        let c = idx.insert(&[1.0, 0.0]).unwrap();
+        assert!(c != a && c != b);
+    }
+
+    #[test]
+    fn top_k_matches_a_full_sort() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let metric = CosineDistance::new();
+        let idx = index();
+        let n = if cfg!(miri) { 200 } else { 10_000 };
+        let vectors: Vec<Vec<f32>> = (0..n)
+            .map(|_| (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect())
+            .collect();
+        for v in &vectors {
+            idx.insert(v).unwrap();
+        }
+        let query: Vec<f32> = (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect();
+
+        let mut sorted: Vec<(u32, f32)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i as u32, metric.distance(&query, v)))
+            .collect();
+        sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let got = idx.search(&query, 5);
+        let ids: Vec<u32> = got.iter().map(|&(id, _)| id).collect();
+        let expected: Vec<u32> = sorted[..5].iter().map(|&(id, _)| id).collect();
+        assert_eq!(ids, expected);
+        for ((_, a), (_, b)) in got.iter().zip(&sorted) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn filtered_search_tests_the_filter_during_the_scan() {
+        let idx = index();
+        for i in 0..100 {
+            let angle = i as f32 * 0.01;
+            idx.insert(&[angle.cos(), angle.sin()]).unwrap();
+        }
+        idx.remove(10);
+        let results = idx.search_filtered(&[1.0, 0.0], 3, &|h| h % 10 == 0);
+        let handles: Vec<u32> = results.iter().map(|&(h, _)| h).collect();
+        assert_eq!(handles, [0, 20, 30], "removed handles stay out");
+        assert!(idx.search_filtered(&[1.0, 0.0], 3, &|_| false).is_empty());
+    }
+
+    #[test]
+    fn distances_follow_the_metric() {
+        let idx = BruteForceIndex::new(Arc::new(EuclideanDistance::new()));
+        idx.insert(&[3.0, 4.0]).unwrap();
+        idx.insert(&[1.0, 0.0]).unwrap();
+        let results = idx.search(&[0.0, 0.0], 2);
+        assert_eq!(results, [(1, 1.0), (0, 5.0)]);
+    }
+}
@@ -29,11 +29,13 @@ use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use crossbeam_epoch::{self as epoch, Guard};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 use super::arena::Arena;
 use super::neighbors::NeighborList;
-use super::{TotalF32, VectorIndex};
-use crate::config::IndexParams;
+use super::{LayerStats, SearchStats, TotalF32, VectorIndex};
+use crate::config::{EntryStrategy, IndexParams};
 use crate::metric::DistanceMetric;
 
 use std::cmp::Reverse;
@@ -76,6 +78,34 @@ fn splitmix64(mut x: u64) -> u64 {
     z ^ (z >> 31)
 }
 
+/// Per-search accounting: counts evaluated nodes and enforces the
+/// optional visit budget.
+struct Traversal {
+    limit: usize,
+    stats: SearchStats,
+}
+
+impl Traversal {
+    fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit: limit.unwrap_or(usize::MAX),
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// Account for evaluating one more node; `false` (and the exhausted
+    /// flag) once the budget is spent. Entry points bypass this so that a
+    /// spent budget still yields a result.
+    fn admit(&mut self) -> bool {
+        if self.stats.nodes_visited >= self.limit {
+            self.stats.budget_exhausted = true;
+            return false;
+        }
+        self.stats.nodes_visited += 1;
+        true
+    }
+}
+
 /// The lock-free HNSW index. See the module docs.
 pub struct LockFreeHnsw {
     params: IndexParams,
@@ -88,13 +118,21 @@ pub struct LockFreeHnsw {
     live: AtomicUsize,
     /// Monotone counter feeding SplitMix64 for layer draws.
     layer_ticket: AtomicU64,
+    /// Monotone counter feeding SplitMix64 for
+    /// [`EntryStrategy::Random`] draws.
+    entry_ticket: AtomicU64,
     seed: u64,
+    /// Scans for a new entry point, for tests to count.
+    #[cfg(test)]
+    entry_scans: AtomicUsize,
 }
 
 impl LockFreeHnsw {
-    /// Create an index with an entropy-derived layer seed.
+    /// Create an index seeded from [`IndexParams::seed`], or from entropy
+    /// when that is unset.
     pub fn new(params: IndexParams, metric: Arc<dyn DistanceMetric>) -> Self {
-        Self::with_seed(params, metric, rand::random())
+        let seed = params.seed.unwrap_or_else(rand::random);
+        Self::with_seed(params, metric, seed)
     }
 
     /// Create an index with a fixed layer seed (deterministic layer
@@ -110,7 +148,10 @@ impl LockFreeHnsw {
             entry: AtomicU64::new(EMPTY_ENTRY),
             live: AtomicUsize::new(0),
             layer_ticket: AtomicU64::new(0),
+            entry_ticket: AtomicU64::new(0),
             seed,
+            #[cfg(test)]
+            entry_scans: AtomicUsize::new(0),
         }
     }
 
@@ -143,17 +184,21 @@ impl LockFreeHnsw {
         entry_points: &[u32],
         ef: usize,
         layer: usize,
+        traversal: &mut Traversal,
         guard: &Guard,
     ) -> Vec<(TotalF32, u32)> {
         let mut visited: HashSet<u32> = HashSet::new();
         let mut frontier: BinaryHeap<Reverse<(TotalF32, u32)>> = BinaryHeap::new();
         let mut best: BinaryHeap<(TotalF32, u32)> = BinaryHeap::new();
+        traversal.stats.layers_traversed += 1;
 
         for &ep in entry_points {
             let Some(node) = self.node(ep) else { continue };
             if layer > node.top_layer || !visited.insert(ep) {
                 continue;
             }
+            traversal.stats.nodes_visited += 1;
+            traversal.stats.distance_computations += 1;
             let d = TotalF32(self.metric.distance_prepared(&node.vector, query));
             frontier.push(Reverse((d, ep)));
             best.push((d, ep));
@@ -162,7 +207,7 @@ impl LockFreeHnsw {
             best.pop();
         }
 
-        while let Some(Reverse((dist, current))) = frontier.pop() {
+        'expand: while let Some(Reverse((dist, current))) = frontier.pop() {
             if let Some(&(worst, _)) = best.peek() {
                 if best.len() >= ef && dist > worst {
                     break;
@@ -175,9 +220,13 @@ impl LockFreeHnsw {
                 if !visited.insert(neighbor) {
                     continue;
                 }
+                if !traversal.admit() {
+                    break 'expand;
+                }
                 let Some(neighbor_node) = self.node(neighbor) else {
                     continue;
                 };
+                traversal.stats.distance_computations += 1;
                 let d = TotalF32(self.metric.distance_prepared(&neighbor_node.vector, query));
                 let admit = best.len() < ef || d < best.peek().expect("non-empty").0;
                 if admit {
@@ -193,8 +242,15 @@ impl LockFreeHnsw {
         best.into_sorted_vec()
     }
 
-    fn descend_layer(&self, query: &[f32], entry: u32, layer: usize, guard: &Guard) -> u32 {
-        self.search_layer(query, &[entry], 1, layer, guard)
+    fn descend_layer(
+        &self,
+        query: &[f32],
+        entry: u32,
+        layer: usize,
+        traversal: &mut Traversal,
+        guard: &Guard,
+    ) -> u32 {
+        self.search_layer(query, &[entry], 1, layer, traversal, guard)
             .first()
             .map(|&(_, id)| id)
             .unwrap_or(entry)
@@ -279,11 +335,204 @@ impl LockFreeHnsw {
         });
     }
 
+    /// [`search`](VectorIndex::search), also reporting the work done.
+    ///
+    /// Honors [`max_nodes_visited`](IndexParams::max_nodes_visited): once
+    /// the budget is spent the traversal stops and returns the best
+    /// candidates found so far, with
+    /// [`budget_exhausted`](SearchStats::budget_exhausted) set.
+    pub fn search_with_stats(&self, query: &[f32], ef: usize) -> (Vec<(u32, f32)>, SearchStats) {
+        let mut traversal = Traversal::new(self.params.max_nodes_visited);
+        if ef == 0 {
+            return (Vec::new(), traversal.stats);
+        }
+        let guard = epoch::pin();
+        let Some((entry_id, entry_top)) = self.search_entry(&guard) else {
+            return (Vec::new(), traversal.stats);
+        };
+
+        // Prepare the query once; the stored vectors are already prepared.
+        let query = self.metric.preprocess(query);
+
+        let mut ep = entry_id;
+        for layer in (1..=entry_top).rev() {
+            ep = self.descend_layer(&query, ep, layer, &mut traversal, &guard);
+        }
+
+        let results = self
+            .search_layer(&query, &[ep], ef, 0, &mut traversal, &guard)
+            .into_iter()
+            .filter(|&(_, id)| {
+                self.node(id)
+                    .map(|n| !n.deleted.load(Ordering::Acquire))
+                    .unwrap_or(false)
+            })
+            .map(|(d, id)| (id, d.0))
+            .collect();
+        (results, traversal.stats)
+    }
+
     /// Current entry point as `(id, top_layer)`.
     fn entry_point(&self) -> Option<(u32, usize)> {
         unpack_entry(self.entry.load(Ordering::Acquire))
     }
 
+    /// Where a search starts, as `(id, top_layer)`, per
+    /// [`entry_strategy`](IndexParams::entry_strategy). A chosen node
+    /// that an in-flight insert has not linked yet would strand the
+    /// search, so the strategies fall back to the entry point for it.
+    fn search_entry(&self, guard: &Guard) -> Option<(u32, usize)> {
+        let entry = self.entry_point()?;
+        let len = self.nodes.len() as u64;
+        let chosen = match self.params.entry_strategy {
+            EntryStrategy::HighestLayer => return Some(entry),
+            EntryStrategy::MostRecent => len - 1,
+            EntryStrategy::Random => {
+                // A stream of its own, apart from the layer draws.
+                let ticket = self.entry_ticket.fetch_add(1, Ordering::Relaxed);
+                splitmix64(self.seed.rotate_left(32) ^ ticket) % len
+            }
+        } as u32;
+        match self.node(chosen) {
+            Some(node) if chosen == entry.0 || !node.layers[0].load(guard).is_empty() => {
+                Some((chosen, node.top_layer))
+            }
+            _ => Some(entry),
+        }
+    }
+
+    /// The live, linked node with the greatest top layer, stopping early
+    /// at one reaching `ceiling`. Linear in the index size.
+    fn highest_live(&self, ceiling: usize, guard: &Guard) -> Option<(u32, usize)> {
+        #[cfg(test)]
+        self.entry_scans.fetch_add(1, Ordering::Relaxed);
+        let mut best: Option<(u32, usize)> = None;
+        for id in 0..self.nodes.len() as u32 {
+            let Some(node) = self.node(id) else { continue };
+            if node.deleted.load(Ordering::Acquire) || node.layers[0].load(guard).is_empty() {
+                continue;
+            }
+            if best.is_none_or(|(_, top)| node.top_layer > top) {
+                best = Some((id, node.top_layer));
+                if node.top_layer >= ceiling {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// Move the entry point off `id`, just removed, onto the highest live
+    /// node, so searches keep starting from a live node without looking
+    /// for one. One scan per removal of the entry node. A failed CAS means
+    /// another writer moved the entry first, and its choice stands; if
+    /// the survivor is removed before the CAS lands, its remover may have
+    /// missed it, so the loop retires it too. Leaves the tombstone in
+    /// place when no linked node survives: it still routes.
+    fn retire_entry(&self, mut id: u32, guard: &Guard) {
+        loop {
+            let current = self.entry.load(Ordering::Acquire);
+            let Some((entry, top)) = unpack_entry(current) else {
+                return;
+            };
+            if entry != id {
+                return;
+            }
+            let Some((survivor, survivor_top)) = self.highest_live(top, guard) else {
+                return;
+            };
+            if self
+                .entry
+                .compare_exchange(
+                    current,
+                    pack_entry(survivor, survivor_top),
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .is_err()
+            {
+                return;
+            }
+            match self.node(survivor) {
+                Some(node) if node.deleted.load(Ordering::Acquire) => id = survivor,
+                _ => return,
+            }
+        }
+    }
+
+    /// Arena slots in use: every node ever inserted, tombstones included.
+    pub fn arena_len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Components per stored vector, as prepared by the metric (a
+    /// projecting metric stores fewer than it is given). Set by the first
+    /// insert; `None` before it.
+    pub fn vector_dimensions(&self) -> Option<usize> {
+        self.node(0).map(|node| node.vector.len())
+    }
+
+    /// The links of node `id` at `layer`, or `None` if there is no such
+    /// node or it does not reach that layer. Tombstoned nodes keep their
+    /// links.
+    pub fn neighbors(&self, id: u32, layer: usize) -> Option<Vec<u32>> {
+        let guard = epoch::pin();
+        let list = self.node(id)?.layers.get(layer)?;
+        Some(list.load(&guard).to_vec())
+    }
+
+    /// Distance from `query` to each live node among `ids`, skipping
+    /// unknown and tombstoned ids. The query is prepared once and compared
+    /// against the stored prepared vectors, so an exhaustive scan pays no
+    /// per-node normalization — the same arithmetic as a graph search.
+    ///
+    /// Wait-free like a search by default; with the `rayon` feature the
+    /// ids are split across rayon's thread pool, which trades that
+    /// guarantee for parallelism.
+    pub fn distances(&self, query: &[f32], ids: &[u32]) -> Vec<(u32, f32)> {
+        let query = self.metric.preprocess(query);
+        #[cfg(feature = "rayon")]
+        let ids = ids.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let ids = ids.iter();
+        ids.filter_map(|&id| {
+            let node = self.node(id)?;
+            (!node.deleted.load(Ordering::Acquire))
+                .then(|| (id, self.metric.distance_prepared(&node.vector, &query)))
+        })
+        .collect()
+    }
+
+    /// Summarize the graph's layer sizes and connectivity. Walks every
+    /// node; wait-free like a search, but linear in the index size.
+    pub fn layer_stats(&self) -> LayerStats {
+        let guard = epoch::pin();
+        let mut stats = LayerStats::default();
+        for id in 0..self.nodes.len() as u32 {
+            let Some(node) = self.node(id) else { continue };
+            if node.deleted.load(Ordering::Acquire) {
+                stats.tombstoned += 1;
+            }
+            if stats.nodes_per_layer.len() <= node.top_layer {
+                stats.nodes_per_layer.resize(node.top_layer + 1, 0);
+                stats.connections_per_layer.resize(node.top_layer + 1, 0);
+            }
+            for (layer, list) in node.layers.iter().enumerate() {
+                let links = list.load(&guard).len();
+                stats.nodes_per_layer[layer] += 1;
+                stats.connections_per_layer[layer] += links;
+                stats.total_connections += links;
+            }
+        }
+        if let (Some(&nodes), Some(&links)) = (
+            stats.nodes_per_layer.first(),
+            stats.connections_per_layer.first(),
+        ) {
+            stats.average_degree = links as f64 / nodes as f64;
+        }
+        stats
+    }
+
     /// Walk the whole graph and verify structural invariants. Used by the
     /// stress-test gate; not part of the stable API.
     ///
@@ -346,6 +595,83 @@ impl LockFreeHnsw {
         Ok(())
     }
 
+    /// Reconnect under-linked nodes and return how many were repaired.
+    ///
+    /// A live node is under-linked when it has fewer than
+    /// `max_connections / 2` layer-0 links — typically after heavy churn,
+    /// or when an insert's backlinks were all pruned away. Each one gets a
+    /// fresh layer-0 neighbor search for its own vector, and the selected
+    /// neighbors are linked both ways (pruning diversely on overflow, as
+    /// inserts do). Lock-free like an insert and safe to run alongside
+    /// other operations; linear in the index size.
+    pub fn repair(&self) -> usize {
+        let guard = epoch::pin();
+        let Some((entry_id, entry_top)) = self.entry_point() else {
+            return 0;
+        };
+        let threshold = self.params.max_connections / 2;
+        let mut repaired = 0;
+        for id in 0..self.nodes.len() as u32 {
+            let Some(node) = self.node(id) else { continue };
+            if node.deleted.load(Ordering::Acquire)
+                || node.layers[0].load(&guard).len() >= threshold
+            {
+                continue;
+            }
+
+            let mut traversal = Traversal::new(None);
+            let mut ep = entry_id;
+            for layer in (1..=entry_top).rev() {
+                ep = self.descend_layer(&node.vector, ep, layer, &mut traversal, &guard);
+            }
+            let candidates: Vec<(TotalF32, u32)> = self
+                .search_layer(
+                    &node.vector,
+                    &[ep],
+                    self.params.ef_construction,
+                    0,
+                    &mut traversal,
+                    &guard,
+                )
+                .into_iter()
+                .filter(|&(_, n)| n != id)
+                .collect();
+            let selected = self.select_diverse(&candidates, self.params.max_connections);
+            if selected.is_empty() {
+                continue;
+            }
+            for &neighbor in &selected {
+                self.add_backlink(id, neighbor, 0, &guard);
+                self.add_backlink(neighbor, id, 0, &guard);
+            }
+            repaired += 1;
+        }
+        repaired
+    }
+
+    /// Drop every link to and from `id`, leaving it unreachable. Simulates
+    /// graph damage for the repair tests.
+    #[cfg(all(test, not(loom)))]
+    fn sever(&self, id: u32) {
+        let guard = epoch::pin();
+        let Some(node) = self.node(id) else { return };
+        for list in node.layers.iter() {
+            list.store(Vec::new(), &guard);
+        }
+        for other in 0..self.nodes.len() as u32 {
+            let Some(other_node) = self.node(other) else {
+                continue;
+            };
+            for list in other_node.layers.iter() {
+                list.update(&guard, |current| {
+                    current
+                        .contains(&id)
+                        .then(|| current.iter().copied().filter(|&n| n != id).collect())
+                });
+            }
+        }
+    }
+
     /// Raise the entry point to `(id, top_layer)` if it is higher than the
     /// current one. CAS loop; lock-free.
     fn raise_entry(&self, id: u32, top_layer: usize) {
@@ -411,9 +737,12 @@ impl VectorIndex for LockFreeHnsw {
             .expect("entry is non-empty past the bootstrap branch");
 
         // Phase 1 (read-only): collect neighbor selections per layer.
+        // Construction is never budgeted: a truncated neighbor search would
+        // degrade the graph for every later query.
+        let mut traversal = Traversal::new(None);
         let mut ep = entry_id;
         for layer in (top_layer + 1..=entry_top).rev() {
-            ep = self.descend_layer(&vector, ep, layer, &guard);
+            ep = self.descend_layer(&vector, ep, layer, &mut traversal, &guard);
         }
 
         let mut entry_points = vec![ep];
@@ -424,6 +753,7 @@ impl VectorIndex for LockFreeHnsw {
                 &entry_points,
                 self.params.ef_construction,
                 layer,
+                &mut traversal,
                 &guard,
             );
             let selected = self.select_diverse(&candidates, self.params.max_connections);
@@ -460,6 +790,7 @@ impl VectorIndex for LockFreeHnsw {
             .is_ok()
         {
             self.live.fetch_sub(1, Ordering::AcqRel);
+            self.retire_entry(id, &epoch::pin());
             true
         } else {
             false
@@ -467,31 +798,7 @@ impl VectorIndex for LockFreeHnsw {
     }
 
     fn search(&self, query: &[f32], ef: usize) -> Vec<(u32, f32)> {
-        if ef == 0 {
-            return Vec::new();
-        }
-        let guard = epoch::pin();
-        let Some((entry_id, entry_top)) = self.entry_point() else {
-            return Vec::new();
-        };
-
-        // Prepare the query once; the stored vectors are already prepared.
-        let query = self.metric.preprocess(query);
-
-        let mut ep = entry_id;
-        for layer in (1..=entry_top).rev() {
-            ep = self.descend_layer(&query, ep, layer, &guard);
-        }
-
-        self.search_layer(&query, &[ep], ef, 0, &guard)
-            .into_iter()
-            .filter(|&(_, id)| {
-                self.node(id)
-                    .map(|n| !n.deleted.load(Ordering::Acquire))
-                    .unwrap_or(false)
-            })
-            .map(|(d, id)| (id, d.0))
-            .collect()
+        self.search_with_stats(query, ef).0
     }
 
     fn len(&self) -> usize {
@@ -537,6 +844,31 @@ mod tests {
         assert!(results.windows(2).all(|w| w[0].1 <= w[1].1));
     }
 
+    #[test]
+    fn prepared_distances_match_the_normalizing_path() {
+        let metric = CosineDistance::new();
+        let idx = index();
+        let vectors: Vec<Vec<f32>> = (0..20)
+            .map(|i| {
+                let angle = i as f32 * 0.31;
+                vec![3.0 * angle.cos(), 0.5 * angle.sin(), i as f32 - 7.0]
+            })
+            .collect();
+        let ids: Vec<u32> = vectors.iter().map(|v| idx.insert(v).unwrap()).collect();
+        assert!(idx.remove(ids[3]));
+
+        let query = [2.0, -1.0, 0.5];
+        let distances = idx.distances(&query, &ids);
+        assert_eq!(distances.len(), ids.len() - 1, "tombstones are skipped");
+        for (id, distance) in distances {
+            let expected = metric.distance(&query, &vectors[id as usize]);
+            assert!(
+                (distance - expected).abs() < 1e-6,
+                "node {id}: prepared {distance} vs normalizing {expected}"
+            );
+        }
+    }
+
     #[test]
     fn tombstoned_vectors_disappear_from_results() {
         let idx = index();
@@ -572,6 +904,158 @@ mod tests {
         assert_eq!(a, b);
     }
 
+    #[test]
+    fn every_entry_strategy_finds_the_true_nearest() {
+        let point = |i: u32| {
+            let angle = i as f32 * 0.13;
+            [angle.cos(), angle.sin()]
+        };
+        let n = if cfg!(miri) { 15 } else { 60 };
+        for strategy in [
+            EntryStrategy::HighestLayer,
+            EntryStrategy::MostRecent,
+            EntryStrategy::Random,
+        ] {
+            let params = IndexParams {
+                entry_strategy: strategy,
+                ..IndexParams::default()
+            };
+            let idx = LockFreeHnsw::with_seed(params, Arc::new(CosineDistance::new()), 42);
+            for i in 0..n {
+                idx.insert(&point(i)).unwrap();
+            }
+            // A removed newest node still routes a most-recent entry.
+            idx.remove(n - 1);
+            for i in 0..n - 1 {
+                let results = idx.search(&point(i), 1);
+                assert_eq!(results[0].0, i, "{strategy:?}");
+            }
+            let guard = epoch::pin();
+            let (entry, top) = idx.search_entry(&guard).unwrap();
+            assert_eq!(idx.node(entry).unwrap().top_layer, top, "{strategy:?}");
+            match strategy {
+                EntryStrategy::HighestLayer => {
+                    assert!(!idx.node(entry).unwrap().deleted.load(Ordering::Acquire));
+                    let highest = (0..n - 1).map(|i| idx.node(i).unwrap().top_layer).max();
+                    assert_eq!(Some(top), highest);
+                }
+                EntryStrategy::MostRecent => assert_eq!(entry, n - 1),
+                EntryStrategy::Random => {}
+            }
+        }
+    }
+
+    #[test]
+    fn highest_layer_entry_moves_off_a_removed_top_node() {
+        let idx = index();
+        let n = if cfg!(miri) { 15 } else { 200 };
+        for i in 0..n {
+            let angle = i as f32 * 0.03;
+            idx.insert(&[angle.cos(), angle.sin()]).unwrap();
+        }
+        let guard = epoch::pin();
+        let (top_node, _) = idx.entry_point().unwrap();
+        assert_eq!(idx.search_entry(&guard), idx.entry_point());
+
+        idx.remove(top_node);
+        assert_eq!(idx.entry_scans.load(Ordering::Relaxed), 1);
+        let (entry, top) = idx.search_entry(&guard).unwrap();
+        assert_eq!(Some((entry, top)), idx.entry_point());
+        assert_ne!(entry, top_node);
+        let highest = (0..n)
+            .filter(|&i| i != top_node)
+            .map(|i| idx.node(i).unwrap().top_layer)
+            .max();
+        assert_eq!(Some(top), highest);
+        for i in (0..n).filter(|&i| i != top_node) {
+            let angle = i as f32 * 0.03;
+            assert_eq!(idx.search(&[angle.cos(), angle.sin()], 1)[0].0, i);
+        }
+        // Searches start from the moved entry point; only removing it
+        // again scans.
+        assert_eq!(idx.entry_scans.load(Ordering::Relaxed), 1);
+        idx.remove(entry);
+        assert_eq!(idx.entry_scans.load(Ordering::Relaxed), 2);
+        assert!(!idx
+            .node(idx.entry_point().unwrap().0)
+            .unwrap()
+            .deleted
+            .load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn random_entries_follow_the_seed() {
+        let entries = || {
+            let params = IndexParams {
+                entry_strategy: EntryStrategy::Random,
+                ..IndexParams::default()
+            };
+            let idx = LockFreeHnsw::with_seed(params, Arc::new(CosineDistance::new()), 42);
+            for i in 0..50 {
+                let angle = i as f32 * 0.13;
+                idx.insert(&[angle.cos(), angle.sin()]).unwrap();
+            }
+            let guard = epoch::pin();
+            (0..20)
+                .map(|_| idx.search_entry(&guard).unwrap().0)
+                .collect::<Vec<_>>()
+        };
+        let first = entries();
+        assert_eq!(first, entries());
+        assert!(first.iter().any(|&id| id != first[0]), "{first:?}");
+    }
+
+    #[test]
+    fn stats_count_distance_work_and_layers() {
+        let idx = index();
+        let n = if cfg!(miri) { 40 } else { 600 };
+        for i in 0..n {
+            let angle = i as f32 * 0.37;
+            idx.insert(&[angle.cos(), angle.sin(), (i % 7) as f32 * 0.1])
+                .unwrap();
+        }
+        let query = [0.3, 0.9, 0.2];
+        let (_, narrow) = idx.search_with_stats(&query, 4);
+        let (_, wide) = idx.search_with_stats(&query, 64);
+
+        let (_, top) = idx.entry_point().unwrap();
+        for stats in [narrow, wide] {
+            assert!(stats.distance_computations > 0);
+            assert!(stats.distance_computations <= stats.nodes_visited);
+            assert_eq!(stats.layers_traversed, top + 1);
+        }
+        assert!(
+            wide.distance_computations > narrow.distance_computations,
+            "{narrow:?} vs {wide:?}"
+        );
+    }
+
+    #[test]
+    fn repair_reconnects_a_severed_node() {
+        let idx = index();
+        let point = |i: u32| {
+            let angle = i as f32 * 0.13;
+            [angle.cos(), angle.sin()]
+        };
+        for i in 0..50 {
+            idx.insert(&point(i)).unwrap();
+        }
+        let (entry, _) = idx.entry_point().unwrap();
+        let victim = if entry == 20 { 21 } else { 20 };
+
+        idx.sever(victim);
+        assert_eq!(idx.neighbors(victim, 0), Some(Vec::new()));
+        assert!(idx
+            .search(&point(victim), 10)
+            .iter()
+            .all(|&(id, _)| id != victim));
+
+        assert!(idx.repair() >= 1);
+        assert_eq!(idx.search(&point(victim), 10)[0].0, victim);
+        idx.check_invariants().unwrap();
+        assert_eq!(idx.repair(), 0, "a repaired graph needs no further work");
+    }
+
     #[test]
     fn concurrent_inserts_build_a_searchable_graph() {
         let idx = Arc::new(index());
@@ -14,12 +14,14 @@
 // them directly.
 #[doc(hidden)]
 pub mod arena;
+mod brute_force;
 mod lockfree_hnsw;
 #[doc(hidden)]
 pub mod neighbors;
 mod rwlock_hnsw;
 mod sharded_rwlock;
 
+pub use brute_force::BruteForceIndex;
 pub use lockfree_hnsw::LockFreeHnsw;
 pub use rwlock_hnsw::RwLockHnsw;
 pub use sharded_rwlock::ShardedRwLockHnsw;
@@ -30,6 +32,10 @@ pub fn arena_capacity() -> usize {
     arena::Arena::<()>::CAPACITY
 }
 
+/// Growth factor of the candidate pool in the default
+/// [`VectorIndex::search_filtered`].
+const FILTER_OVERFETCH: usize = 4;
+
 /// An `f32` wrapper with total ordering via [`f32::total_cmp`].
 ///
 /// Heap orderings over raw `f32` break down in the presence of NaN; every
@@ -51,6 +57,74 @@ impl Ord for TotalF32 {
     }
 }
 
+/// The `k` least of `items` under `compare`, sorted ascending.
+///
+/// A partial selection followed by a sort of the survivors — `O(n + k log
+/// k)` rather than the `O(n log n)` of sorting everything and truncating,
+/// which matters when `k` is a handful and `n` a candidate pool. Elements
+/// comparing equal at the cut are kept arbitrarily.
+pub(crate) fn top_k_by<T>(
+    mut items: Vec<T>,
+    k: usize,
+    mut compare: impl FnMut(&T, &T) -> std::cmp::Ordering,
+) -> Vec<T> {
+    if k == 0 {
+        items.clear();
+        return items;
+    }
+    if k < items.len() {
+        items.select_nth_unstable_by(k - 1, &mut compare);
+        items.truncate(k);
+    }
+    items.sort_by(compare);
+    items
+}
+
+/// Work done by one search, as reported by
+/// [`ChronoMind::search_with_stats`](crate::ChronoMind::search_with_stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SearchStats {
+    /// Distinct graph nodes evaluated, summed over every layer visited.
+    pub nodes_visited: usize,
+    /// Distance evaluations between the query and a stored vector. Close
+    /// to `nodes_visited`, but it counts only work actually done — the
+    /// figure to watch when tuning `ef_search` and `max_connections`.
+    pub distance_computations: usize,
+    /// Graph layers searched, the base layer included; zero for an
+    /// exhaustive scan.
+    pub layers_traversed: usize,
+    /// Whether [`max_nodes_visited`](crate::IndexParams::max_nodes_visited)
+    /// stopped the traversal before it converged. Results are then the best
+    /// found so far, not necessarily the best the graph could reach.
+    pub budget_exhausted: bool,
+    /// Whether the store was below
+    /// [`brute_force_threshold`](crate::Config::brute_force_threshold) and
+    /// was scanned exhaustively instead; `nodes_visited` and
+    /// `distance_computations` then count the records scanned.
+    pub exhaustive: bool,
+}
+
+/// Shape of an HNSW graph, as reported by
+/// [`ChronoMind::index_stats`](crate::ChronoMind::index_stats).
+///
+/// Tombstoned nodes are counted: they keep routing traversal until a
+/// snapshot reload compacts them away.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct LayerStats {
+    /// Nodes participating in each layer; index 0 is the base layer.
+    pub nodes_per_layer: Vec<usize>,
+    /// Directed links in each layer, parallel to `nodes_per_layer`.
+    pub connections_per_layer: Vec<usize>,
+    /// Directed links summed over all layers.
+    pub total_connections: usize,
+    /// Mean out-degree in the base layer (`0.0` for an empty graph).
+    pub average_degree: f64,
+    /// Nodes marked deleted but still linked into the graph.
+    pub tombstoned: usize,
+}
+
 /// A geometric nearest-neighbor index over `f32` vectors.
 ///
 /// Implementations must be safe to share across threads. Distances are as
@@ -71,6 +145,40 @@ pub trait VectorIndex: Send + Sync {
     /// `(handle, distance)`, sorted by ascending distance.
     fn search(&self, query: &[f32], ef: usize) -> Vec<(u32, f32)>;
 
+    /// Return up to `ef` candidates nearest to `query` among the handles
+    /// `filter` accepts, as `(handle, distance)` sorted by ascending
+    /// distance.
+    ///
+    /// The default searches with a widening pool — `4 * ef`, then four
+    /// times that — until `ef` candidates pass or the index has nothing
+    /// more to return; a selective filter costs several searches.
+    /// Implementations that can test the filter while they search should
+    /// override this.
+    fn search_filtered(
+        &self,
+        query: &[f32],
+        ef: usize,
+        filter: &(dyn Fn(u32) -> bool + Sync),
+    ) -> Vec<(u32, f32)> {
+        if ef == 0 {
+            return Vec::new();
+        }
+        let mut pool = ef.saturating_mul(FILTER_OVERFETCH);
+        loop {
+            let found = self.search(query, pool);
+            let exhausted = found.len() < pool || pool >= self.len();
+            let passing: Vec<(u32, f32)> = found
+                .into_iter()
+                .filter(|&(handle, _)| filter(handle))
+                .take(ef)
+                .collect();
+            if passing.len() == ef || exhausted {
+                return passing;
+            }
+            pool = pool.saturating_mul(FILTER_OVERFETCH);
+        }
+    }
+
     /// Number of live (non-tombstoned) vectors.
     fn len(&self) -> usize;
 
@@ -79,3 +187,50 @@ pub trait VectorIndex: Send + Sync {
         self.len() == 0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn default_filtered_search_widens_until_enough_pass() {
+        use std::sync::Arc;
+
+        use crate::config::IndexParams;
+        use crate::metric::CosineDistance;
+
+        let idx = RwLockHnsw::with_seed(IndexParams::default(), Arc::new(CosineDistance::new()), 5);
+        for i in 0..400 {
+            let angle = i as f32 * 0.01;
+            idx.insert(&[angle.cos(), angle.sin()]).unwrap();
+        }
+        // One handle in fifty passes: the first pool of 4 * 3 cannot hold
+        // three of them, so the search has to widen.
+        let every_fiftieth = |handle: u32| handle % 50 == 0;
+        let results = idx.search_filtered(&[1.0, 0.0], 3, &every_fiftieth);
+        let handles: Vec<u32> = results.iter().map(|&(h, _)| h).collect();
+        assert_eq!(handles, [0, 50, 100]);
+        assert!(results.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        // A filter too selective to fill `ef` returns what exists.
+        assert_eq!(idx.search_filtered(&[1.0, 0.0], 3, &|h| h == 7).len(), 1);
+        assert!(idx.search_filtered(&[1.0, 0.0], 3, &|_| false).is_empty());
+        assert!(idx.search_filtered(&[1.0, 0.0], 0, &|_| true).is_empty());
+    }
+
+    #[test]
+    fn top_k_matches_a_full_sort() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        for n in [0usize, 1, 2, 10, 100, 1000] {
+            let scores: Vec<f32> = (0..n).map(|_| rng.gen()).collect();
+            let mut sorted = scores.clone();
+            sorted.sort_by(f32::total_cmp);
+            for k in [0usize, 1, 5, 10, 99, 100, 2000] {
+                let top = top_k_by(scores.clone(), k, f32::total_cmp);
+                assert_eq!(top, sorted[..k.min(n)], "n {n}, k {k}");
+            }
+        }
+    }
+}
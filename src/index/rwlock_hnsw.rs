@@ -56,9 +56,11 @@ pub struct RwLockHnsw {
 }
 
 impl RwLockHnsw {
-    /// Create an index with an entropy-seeded layer RNG.
+    /// Create an index with its layer RNG seeded from
+    /// [`IndexParams::seed`], or from entropy when that is unset.
     pub fn new(params: IndexParams, metric: Arc<dyn DistanceMetric>) -> Self {
-        Self::with_seed(params, metric, rand::random())
+        let seed = params.seed.unwrap_or_else(rand::random);
+        Self::with_seed(params, metric, seed)
     }
 
     /// Create an index with a fixed RNG seed (deterministic layer
@@ -412,6 +414,7 @@ mod tests {
             max_connections: 4,
             ef_construction: 16,
             ef_search: 16,
+            ..IndexParams::default()
         };
         let idx = RwLockHnsw::with_seed(params, Arc::new(CosineDistance::new()), 7);
         let n = if cfg!(miri) { 40 } else { 200 };
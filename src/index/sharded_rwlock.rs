@@ -16,7 +16,7 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use super::{RwLockHnsw, TotalF32, VectorIndex};
+use super::{top_k_by, RwLockHnsw, TotalF32, VectorIndex};
 use crate::config::IndexParams;
 use crate::metric::DistanceMetric;
 
@@ -34,9 +34,11 @@ pub struct ShardedRwLockHnsw {
 }
 
 impl ShardedRwLockHnsw {
-    /// Create a sharded index with an entropy-seeded layer RNG.
+    /// Create a sharded index seeded from [`IndexParams::seed`], or from
+    /// entropy when that is unset.
     pub fn new(params: IndexParams, metric: Arc<dyn DistanceMetric>) -> Self {
-        Self::with_seed(params, metric, rand::random())
+        let seed = params.seed.unwrap_or_else(rand::random);
+        Self::with_seed(params, metric, seed)
     }
 
     /// Create a sharded index with deterministic per-shard seeds.
@@ -88,9 +90,10 @@ impl VectorIndex for ShardedRwLockHnsw {
                 merged.push((TotalF32(dist), global));
             }
         }
-        merged.sort();
-        merged.truncate(ef);
-        merged.into_iter().map(|(d, id)| (id, d.0)).collect()
+        top_k_by(merged, ef, Ord::cmp)
+            .into_iter()
+            .map(|(d, id)| (id, d.0))
+            .collect()
     }
 
     fn len(&self) -> usize {
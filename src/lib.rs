@@ -10,7 +10,11 @@
 //! async runtime dependency, the entire API (except the `consolidate`
 //! maintenance pass) takes `&self`, and nothing anywhere blocks on a mutex
 //! or RwLock. Searches are wait-free; writes are lock-free. Share a store
-//! across threads with `Arc` and use it from all of them at once.
+//! across threads with `Arc` and use it from all of them at once. (The
+//! opt-in wrappers are the exceptions: [`WalStore`] serializes writers to
+//! keep its log in order, a [`MaintenanceScheduler`] shares its store
+//! behind an `RwLock` so it can run the `&mut self` passes, and the
+//! `rayon` feature runs exact scans on rayon's lock-coordinated pool.)
 //!
 //! # Example
 //!
@@ -39,17 +43,50 @@
 #![deny(missing_docs)]
 #![warn(rust_2018_idioms)]
 
+pub mod backup;
 pub mod config;
 pub mod error;
+pub mod eval;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod index;
+mod linalg;
+pub mod maintenance;
 pub mod metric;
+pub mod metrics;
+pub mod pca;
 pub mod persistence;
+pub mod quantization;
+pub mod rerank;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 pub mod store;
 pub mod types;
+pub mod wal;
+pub mod whitening;
 
-pub use config::{Config, ConfigBuilder, IndexParams};
+pub use backup::{backup_incremental, restore_from_dir, restore_from_dir_with_metric};
+pub use config::{Config, ConfigBuilder, EntryStrategy, IndexParams, Precision, ScoreBands};
 pub use error::{Error, Result};
-pub use metric::{CosineDistance, DistanceMetric};
-pub use persistence::{load_snapshot, save_snapshot};
+pub use index::{LayerStats, SearchStats};
+pub use maintenance::{MaintenanceSchedule, MaintenanceScheduler};
+pub use metric::{
+    BuiltinMetric, ChebyshevDistance, CompositeDistance, CosineDistance, DistanceMetric,
+    EuclideanDistance, ManhattanDistance,
+};
+pub use metrics::MetricsRegistry;
+pub use pca::{Pca, PcaMetric};
+pub use persistence::{
+    export_ndjson, import_ndjson, import_ndjson_file, load_snapshot, load_snapshot_with_metric,
+    save_snapshot, save_snapshot_compressed, save_snapshot_pruned, IMPORT_PROGRESS_INTERVAL,
+};
+pub use rerank::{Candidate, Reranker, TemporalReranker};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
 pub use store::ChronoMind;
-pub use types::{ContextSummary, Memory, MemoryAttributes, MemoryStats, Vector};
+pub use types::{
+    ContextSummary, Memory, MemoryAttributes, MemoryBuilder, MemoryStats, MultiQueryMode,
+    SearchHit, SearchMode, Vector, VectorElement,
+};
+pub use wal::WalStore;
+pub use whitening::Whitening;
@@ -0,0 +1,156 @@
+//! Small dense linear-algebra helpers for corpus-fitted transforms.
+//!
+//! Everything here works in `f64` on row-major square matrices. The sizes
+//! involved are embedding dimensionalities (hundreds to low thousands), so
+//! a straightforward cyclic Jacobi solver is accurate and fast enough for
+//! the one-off fits that use it; nothing here runs on the search path.
+
+/// Component-wise mean and covariance of `vectors` (all of length `dim`).
+///
+/// Returns the mean and the `dim x dim` covariance, row-major, normalized
+/// by `n - 1`. Callers guarantee at least two vectors.
+pub(crate) fn mean_and_covariance(vectors: &[&[f32]], dim: usize) -> (Vec<f64>, Vec<f64>) {
+    let n = vectors.len();
+    let mut mean = vec![0.0f64; dim];
+    for v in vectors {
+        for (m, &x) in mean.iter_mut().zip(v.iter()) {
+            *m += f64::from(x);
+        }
+    }
+    for m in &mut mean {
+        *m /= n as f64;
+    }
+
+    let mut covariance = vec![0.0f64; dim * dim];
+    let mut centered = vec![0.0f64; dim];
+    for v in vectors {
+        for ((c, &x), m) in centered.iter_mut().zip(v.iter()).zip(&mean) {
+            *c = f64::from(x) - m;
+        }
+        for i in 0..dim {
+            let ci = centered[i];
+            if ci == 0.0 {
+                continue;
+            }
+            let row = &mut covariance[i * dim..(i + 1) * dim];
+            for (cell, &cj) in row.iter_mut().zip(&centered).skip(i) {
+                *cell += ci * cj;
+            }
+        }
+    }
+    let denom = (n.max(2) - 1) as f64;
+    for i in 0..dim {
+        for j in i..dim {
+            let value = covariance[i * dim + j] / denom;
+            covariance[i * dim + j] = value;
+            covariance[j * dim + i] = value;
+        }
+    }
+    (mean, covariance)
+}
+
+/// Eigendecomposition of a symmetric `n x n` matrix by cyclic Jacobi
+/// rotations.
+///
+/// Returns `(eigenvalues, eigenvectors)` sorted by descending eigenvalue;
+/// eigenvector `k` is row `k` of the returned row-major matrix.
+pub(crate) fn symmetric_eigen(matrix: &[f64], n: usize) -> (Vec<f64>, Vec<f64>) {
+    const MAX_SWEEPS: usize = 64;
+
+    let mut a = matrix.to_vec();
+    // Accumulated rotations; column k converges to eigenvector k.
+    let mut v = vec![0.0f64; n * n];
+    for i in 0..n {
+        v[i * n + i] = 1.0;
+    }
+
+    let scale: f64 = a
+        .iter()
+        .map(|x| x * x)
+        .sum::<f64>()
+        .sqrt()
+        .max(f64::MIN_POSITIVE);
+    for _ in 0..MAX_SWEEPS {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i * n + j] * a[i * n + j])
+            .sum::<f64>()
+            .sqrt();
+        if off_diagonal <= 1e-12 * scale {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let apq = a[p * n + q];
+                if apq.abs() <= f64::MIN_POSITIVE {
+                    continue;
+                }
+                let theta = (a[q * n + q] - a[p * n + p]) / (2.0 * apq);
+                // signum(0.0) is 1.0, so theta == 0 yields the 45° rotation.
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for k in 0..n {
+                    let akp = a[k * n + p];
+                    let akq = a[k * n + q];
+                    a[k * n + p] = c * akp - s * akq;
+                    a[k * n + q] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let apk = a[p * n + k];
+                    let aqk = a[q * n + k];
+                    a[p * n + k] = c * apk - s * aqk;
+                    a[q * n + k] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let vkp = v[k * n + p];
+                    let vkq = v[k * n + q];
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[j * n + j].total_cmp(&a[i * n + i]));
+    let eigenvalues = order.iter().map(|&k| a[k * n + k]).collect();
+    let mut eigenvectors = vec![0.0f64; n * n];
+    for (row, &k) in order.iter().enumerate() {
+        for i in 0..n {
+            eigenvectors[row * n + i] = v[i * n + k];
+        }
+    }
+    (eigenvalues, eigenvectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eigen_reconstructs_symmetric_matrix() {
+        let m = [4.0, 1.0, 0.5, 1.0, 3.0, 0.2, 0.5, 0.2, 1.0];
+        let (values, vectors) = symmetric_eigen(&m, 3);
+        assert!(values.windows(2).all(|w| w[0] >= w[1]));
+        for i in 0..3 {
+            for j in 0..3 {
+                let rebuilt: f64 = (0..3)
+                    .map(|k| values[k] * vectors[k * 3 + i] * vectors[k * 3 + j])
+                    .sum();
+                assert!((rebuilt - m[i * 3 + j]).abs() < 1e-9, "({i}, {j})");
+            }
+        }
+    }
+
+    #[test]
+    fn covariance_of_axis_aligned_data() {
+        let data: [&[f32]; 4] = [&[1.0, 0.0], &[-1.0, 0.0], &[0.0, 2.0], &[0.0, -2.0]];
+        let (mean, cov) = mean_and_covariance(&data, 2);
+        assert_eq!(mean, vec![0.0, 0.0]);
+        assert!((cov[0] - 2.0 / 3.0).abs() < 1e-12);
+        assert!((cov[3] - 8.0 / 3.0).abs() < 1e-12);
+        assert_eq!(cov[1], 0.0);
+    }
+}
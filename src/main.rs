@@ -131,6 +131,10 @@ fn eprint_hint(err: &Error) {
             "The vector has {got} dimensions but the store expects {expected}. \
              Pass --dimensions {got} when saving, or fix the input."
         ),
+        Error::QueryDimensionMismatch { query, expected } => eprintln!(
+            "The query has {query} dimensions but the store holds {expected}-dimensional \
+             vectors. Embed the query with the same model as the stored data."
+        ),
         Error::InvalidVector(_) => {
             eprintln!("Ensure every vector component is a finite floating-point number.")
         }
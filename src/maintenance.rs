@@ -0,0 +1,290 @@
+//! Periodic maintenance on a background thread.
+//!
+//! Decay, pruning, and consolidation are passes a long-running store needs
+//! on a cadence. A [`MaintenanceScheduler`] runs them from one background
+//! thread at the intervals of a [`MaintenanceSchedule`] until it is shut
+//! down or dropped.
+//!
+//! Pruning and consolidation rebuild the store and take `&mut self`, so
+//! the scheduler shares the store as `Arc<RwLock<ChronoMind>>`: decay runs
+//! under the read lock, the other passes under the write lock. Callers
+//! that never schedule those two can keep their own readers on the read
+//! lock and contend with nothing but the decay pass.
+
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+use crate::error::{Error, Result};
+use crate::store::ChronoMind;
+
+/// How often each maintenance pass runs. `None` disables a pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MaintenanceSchedule {
+    /// Interval between [`apply_decay`](ChronoMind::apply_decay) sweeps.
+    pub decay_interval: Option<Duration>,
+    /// Interval between
+    /// [`prune_below_importance`](ChronoMind::prune_below_importance)
+    /// passes.
+    pub prune_interval: Option<Duration>,
+    /// Importance below which a prune pass removes a memory.
+    pub prune_threshold: f32,
+    /// Interval between [`consolidate`](ChronoMind::consolidate) passes.
+    pub consolidate_interval: Option<Duration>,
+}
+
+impl MaintenanceSchedule {
+    fn validate(&self) -> Result<()> {
+        let intervals = [
+            self.decay_interval,
+            self.prune_interval,
+            self.consolidate_interval,
+        ];
+        if intervals.iter().flatten().any(Duration::is_zero) {
+            return Err(Error::InvalidArgument(
+                "maintenance intervals must be greater than zero".into(),
+            ));
+        }
+        if !self.prune_threshold.is_finite() {
+            return Err(Error::InvalidArgument(format!(
+                "prune threshold must be finite, got {}",
+                self.prune_threshold
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A maintenance pass, in the order passes falling due together run:
+/// decay first so pruning sees fresh importance, pruning before
+/// consolidation so nothing about to be removed is merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Task {
+    Decay,
+    Prune,
+    Consolidate,
+}
+
+const TASKS: [Task; 3] = [Task::Decay, Task::Prune, Task::Consolidate];
+
+/// When each scheduled pass is next due.
+struct Timetable {
+    intervals: [Option<Duration>; 3],
+    next: [Option<Instant>; 3],
+}
+
+impl Timetable {
+    fn new(schedule: &MaintenanceSchedule, start: Instant) -> Self {
+        let intervals = [
+            schedule.decay_interval,
+            schedule.prune_interval,
+            schedule.consolidate_interval,
+        ];
+        Self {
+            intervals,
+            next: intervals.map(|interval| interval.map(|i| start + i)),
+        }
+    }
+
+    /// The passes due at `now`, rescheduled one interval on. A pass that
+    /// fell more than an interval behind — a long consolidation, a
+    /// suspended process — runs once and resumes from `now` rather than
+    /// catching up in a burst.
+    fn due(&mut self, now: Instant) -> Vec<Task> {
+        let mut due = Vec::new();
+        for (slot, task) in TASKS.iter().enumerate() {
+            let (Some(next), Some(interval)) = (self.next[slot], self.intervals[slot]) else {
+                continue;
+            };
+            if next <= now {
+                due.push(*task);
+                let following = next + interval;
+                self.next[slot] = Some(if following <= now {
+                    now + interval
+                } else {
+                    following
+                });
+            }
+        }
+        due
+    }
+
+    /// The earliest time any pass is due; `None` if nothing is scheduled.
+    fn next_due(&self) -> Option<Instant> {
+        self.next.iter().flatten().min().copied()
+    }
+}
+
+/// Runs a [`MaintenanceSchedule`] against a shared store on a background
+/// thread. See the module docs.
+///
+/// [`shutdown`](Self::shutdown) — or dropping the scheduler — stops the
+/// thread and waits for a pass in progress to finish.
+pub struct MaintenanceScheduler {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MaintenanceScheduler {
+    /// Start running `schedule` against `store`. Each pass first runs one
+    /// interval after the start.
+    ///
+    /// Intervals must be non-zero and the prune threshold finite. A failed
+    /// pass — a read-only store, say — is logged and retried at its next
+    /// interval.
+    pub fn start(store: Arc<RwLock<ChronoMind>>, schedule: MaintenanceSchedule) -> Result<Self> {
+        schedule.validate()?;
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let signal = Arc::clone(&stop);
+        let thread = thread::Builder::new()
+            .name("chronomind-maintenance".into())
+            .spawn(move || run(&store, &schedule, &signal))?;
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Stop the background thread, waiting for a pass in progress to
+    /// finish.
+    pub fn shutdown(mut self) {
+        self.stop_and_join();
+    }
+
+    fn stop_and_join(&mut self) {
+        let (stopped, wake) = &*self.stop;
+        *stopped.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        wake.notify_all();
+        if let Some(thread) = self.thread.take() {
+            // A panicking pass already reported itself; nothing to add.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for MaintenanceScheduler {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
+
+fn run(store: &RwLock<ChronoMind>, schedule: &MaintenanceSchedule, stop: &(Mutex<bool>, Condvar)) {
+    let (stopped, wake) = stop;
+    let mut timetable = Timetable::new(schedule, Instant::now());
+    loop {
+        for task in timetable.due(Instant::now()) {
+            run_task(store, schedule, task);
+        }
+        let guard = stopped.lock().unwrap_or_else(|e| e.into_inner());
+        let guard = match timetable.next_due() {
+            Some(due) => {
+                let wait = due.saturating_duration_since(Instant::now());
+                wake.wait_timeout_while(guard, wait, |stopped| !*stopped)
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0
+            }
+            None => wake
+                .wait_while(guard, |stopped| !*stopped)
+                .unwrap_or_else(|e| e.into_inner()),
+        };
+        if *guard {
+            return;
+        }
+    }
+}
+
+fn run_task(store: &RwLock<ChronoMind>, schedule: &MaintenanceSchedule, task: Task) {
+    match task {
+        Task::Decay => {
+            let store = store.read().unwrap_or_else(|e| e.into_inner());
+            match store.try_apply_decay() {
+                Ok(()) => debug!("scheduled decay sweep done"),
+                Err(e) => warn!(error = %e, "scheduled decay sweep failed"),
+            }
+        }
+        Task::Prune => {
+            let mut store = store.write().unwrap_or_else(|e| e.into_inner());
+            match store.prune_below_importance(schedule.prune_threshold) {
+                Ok(removed) => debug!(removed, "scheduled prune done"),
+                Err(e) => warn!(error = %e, "scheduled prune failed"),
+            }
+        }
+        Task::Consolidate => {
+            let mut store = store.write().unwrap_or_else(|e| e.into_inner());
+            match store.try_consolidate() {
+                Ok(merged) => debug!(merged, "scheduled consolidation done"),
+                Err(e) => warn!(error = %e, "scheduled consolidation failed"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> MaintenanceSchedule {
+        MaintenanceSchedule {
+            decay_interval: Some(Duration::from_secs(60)),
+            consolidate_interval: Some(Duration::from_secs(150)),
+            ..MaintenanceSchedule::default()
+        }
+    }
+
+    #[test]
+    fn passes_fall_due_at_their_intervals() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut timetable = Timetable::new(&schedule(), start);
+
+        assert_eq!(timetable.next_due(), Some(at(60)));
+        assert!(timetable.due(at(59)).is_empty());
+        assert_eq!(timetable.due(at(60)), [Task::Decay]);
+        assert!(timetable.due(at(60)).is_empty(), "each interval fires once");
+        assert_eq!(timetable.due(at(120)), [Task::Decay]);
+        assert_eq!(timetable.next_due(), Some(at(150)));
+        assert_eq!(timetable.due(at(180)), [Task::Decay, Task::Consolidate]);
+        assert_eq!(timetable.next_due(), Some(at(240)));
+    }
+
+    #[test]
+    fn a_late_pass_runs_once_and_resumes_from_now() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut timetable = Timetable::new(&schedule(), start);
+
+        assert_eq!(timetable.due(at(1_000)), [Task::Decay, Task::Consolidate]);
+        assert!(timetable.due(at(1_000)).is_empty());
+        assert_eq!(timetable.next_due(), Some(at(1_060)));
+    }
+
+    #[test]
+    fn nothing_scheduled_is_never_due() {
+        let start = Instant::now();
+        let mut timetable = Timetable::new(&MaintenanceSchedule::default(), start);
+        assert_eq!(timetable.next_due(), None);
+        assert!(timetable
+            .due(start + Duration::from_secs(1 << 20))
+            .is_empty());
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        let zero = MaintenanceSchedule {
+            decay_interval: Some(Duration::ZERO),
+            ..MaintenanceSchedule::default()
+        };
+        let nan = MaintenanceSchedule {
+            prune_threshold: f32::NAN,
+            ..MaintenanceSchedule::default()
+        };
+        for schedule in [zero, nan] {
+            assert!(matches!(
+                schedule.validate(),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+    }
+}
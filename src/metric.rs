@@ -1,10 +1,21 @@
 //! Distance metrics for vector comparison.
 //!
-//! The built-in [`CosineDistance`] uses AVX2+FMA SIMD on `x86_64` when the
-//! CPU supports it, with a portable scalar fallback everywhere else.
+//! The built-in [`CosineDistance`] uses AVX-512F or AVX2+FMA SIMD on
+//! `x86_64`, whichever the CPU supports (checked at runtime), NEON on
+//! `aarch64` (always present there), and a portable scalar fallback
+//! everywhere else.
+//!
+//! Metrics can be looked up by name through a [`MetricRegistry`], which
+//! lets configuration layers and plugins name a metric instead of
+//! constructing it.
 
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::*;
+use std::sync::{Arc, OnceLock};
+
+use crate::error::{Error, Result};
 
 /// A distance/similarity metric over `f32` vectors.
 ///
@@ -115,6 +126,44 @@ impl CosineDistance {
         (dot_s, na_s, nb_s)
     }
 
+    /// AVX-512F single-pass dot product and squared norms. The tail is
+    /// handled with masked loads, so there is no scalar remainder loop.
+    /// Compiled only with the `avx512` feature (Rust 1.89+).
+    ///
+    /// # Safety
+    /// Caller must ensure the CPU supports AVX-512F and `a.len() == b.len()`.
+    #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+    #[target_feature(enable = "avx512f")]
+    #[clippy::msrv = "1.89"]
+    unsafe fn dot_and_norms_avx512(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+        let mut dot = _mm512_setzero_ps();
+        let mut na = _mm512_setzero_ps();
+        let mut nb = _mm512_setzero_ps();
+        let chunks = a.len() / 16 * 16;
+
+        for i in (0..chunks).step_by(16) {
+            let va = _mm512_loadu_ps(a.as_ptr().add(i));
+            let vb = _mm512_loadu_ps(b.as_ptr().add(i));
+            dot = _mm512_fmadd_ps(va, vb, dot);
+            na = _mm512_fmadd_ps(va, va, na);
+            nb = _mm512_fmadd_ps(vb, vb, nb);
+        }
+        if chunks < a.len() {
+            let mask: __mmask16 = (1 << (a.len() - chunks)) - 1;
+            let va = _mm512_maskz_loadu_ps(mask, a.as_ptr().add(chunks));
+            let vb = _mm512_maskz_loadu_ps(mask, b.as_ptr().add(chunks));
+            dot = _mm512_fmadd_ps(va, vb, dot);
+            na = _mm512_fmadd_ps(va, va, na);
+            nb = _mm512_fmadd_ps(vb, vb, nb);
+        }
+
+        (
+            _mm512_reduce_add_ps(dot),
+            _mm512_reduce_add_ps(na),
+            _mm512_reduce_add_ps(nb),
+        )
+    }
+
     /// Dot product only (single pass), scalar.
     fn dot_scalar(a: &[f32], b: &[f32]) -> f32 {
         a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
@@ -146,44 +195,176 @@ impl CosineDistance {
         s
     }
 
-    /// Dispatched dot product: AVX2+FMA when available, scalar otherwise.
-    /// Callers must pass equal-length slices.
+    /// AVX-512F dot product, with a masked final chunk.
+    ///
+    /// # Safety
+    /// Caller must ensure the CPU supports AVX-512F and `a.len() == b.len()`.
+    #[cfg(all(target_arch = "x86_64", feature = "avx512"))]
+    #[target_feature(enable = "avx512f")]
+    #[clippy::msrv = "1.89"]
+    unsafe fn dot_avx512(a: &[f32], b: &[f32]) -> f32 {
+        let mut acc = _mm512_setzero_ps();
+        let chunks = a.len() / 16 * 16;
+        for i in (0..chunks).step_by(16) {
+            let va = _mm512_loadu_ps(a.as_ptr().add(i));
+            let vb = _mm512_loadu_ps(b.as_ptr().add(i));
+            acc = _mm512_fmadd_ps(va, vb, acc);
+        }
+        if chunks < a.len() {
+            let mask: __mmask16 = (1 << (a.len() - chunks)) - 1;
+            let va = _mm512_maskz_loadu_ps(mask, a.as_ptr().add(chunks));
+            let vb = _mm512_maskz_loadu_ps(mask, b.as_ptr().add(chunks));
+            acc = _mm512_fmadd_ps(va, vb, acc);
+        }
+        _mm512_reduce_add_ps(acc)
+    }
+
+    /// NEON single-pass dot product and squared norms.
+    ///
+    /// # Safety
+    /// Caller must ensure `a.len() == b.len()`. NEON itself is part of the
+    /// `aarch64` baseline.
+    #[cfg(target_arch = "aarch64")]
+    unsafe fn dot_and_norms_neon(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+        let mut dot = vdupq_n_f32(0.0);
+        let mut na = vdupq_n_f32(0.0);
+        let mut nb = vdupq_n_f32(0.0);
+        let chunks = a.len() / 4 * 4;
+
+        for i in (0..chunks).step_by(4) {
+            let va = vld1q_f32(a.as_ptr().add(i));
+            let vb = vld1q_f32(b.as_ptr().add(i));
+            dot = vfmaq_f32(dot, va, vb);
+            na = vfmaq_f32(na, va, va);
+            nb = vfmaq_f32(nb, vb, vb);
+        }
+
+        let mut dot_s = vaddvq_f32(dot);
+        let mut na_s = vaddvq_f32(na);
+        let mut nb_s = vaddvq_f32(nb);
+
+        for i in chunks..a.len() {
+            let (x, y) = (*a.get_unchecked(i), *b.get_unchecked(i));
+            dot_s += x * y;
+            na_s += x * x;
+            nb_s += y * y;
+        }
+
+        (dot_s, na_s, nb_s)
+    }
+
+    /// NEON dot product.
+    ///
+    /// # Safety
+    /// Caller must ensure `a.len() == b.len()`.
+    #[cfg(target_arch = "aarch64")]
+    unsafe fn dot_neon(a: &[f32], b: &[f32]) -> f32 {
+        let mut acc = vdupq_n_f32(0.0);
+        let chunks = a.len() / 4 * 4;
+        for i in (0..chunks).step_by(4) {
+            let va = vld1q_f32(a.as_ptr().add(i));
+            let vb = vld1q_f32(b.as_ptr().add(i));
+            acc = vfmaq_f32(acc, va, vb);
+        }
+        let mut s = vaddvq_f32(acc);
+        for i in chunks..a.len() {
+            s += *a.get_unchecked(i) * *b.get_unchecked(i);
+        }
+        s
+    }
+
+    /// Dispatched dot product: AVX-512F (with the `avx512` feature), then
+    /// AVX2+FMA, when available on `x86_64`; NEON on `aarch64`; scalar
+    /// otherwise. Callers must pass equal-length slices.
     #[inline]
     fn dot(a: &[f32], b: &[f32]) -> f32 {
         #[cfg(target_arch = "x86_64")]
         {
+            #[cfg(feature = "avx512")]
+            if is_x86_feature_detected!("avx512f") {
+                // SAFETY: feature verified here; lengths equal by contract.
+                return unsafe { Self::dot_avx512(a, b) };
+            }
             if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
                 // SAFETY: features verified here; lengths equal by contract.
                 return unsafe { Self::dot_avx2(a, b) };
             }
         }
+        #[cfg(target_arch = "aarch64")]
+        {
+            // SAFETY: NEON is baseline on aarch64; lengths equal by contract.
+            return unsafe { Self::dot_neon(a, b) };
+        }
+        #[allow(unreachable_code)]
         Self::dot_scalar(a, b)
     }
 
+    /// Dispatched single-pass dot product and squared norms, choosing
+    /// kernels like [`dot`](Self::dot). Callers must pass equal-length
+    /// slices.
+    #[inline]
+    fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+        #[cfg(target_arch = "x86_64")]
+        {
+            #[cfg(feature = "avx512")]
+            if is_x86_feature_detected!("avx512f") {
+                // SAFETY: feature verified here; lengths equal by contract.
+                return unsafe { Self::dot_and_norms_avx512(a, b) };
+            }
+            if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+                // SAFETY: features verified here; lengths equal by contract.
+                return unsafe { Self::dot_and_norms_avx2(a, b) };
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            // SAFETY: NEON is baseline on aarch64; lengths equal by contract.
+            return unsafe { Self::dot_and_norms_neon(a, b) };
+        }
+        #[allow(unreachable_code)]
+        Self::dot_and_norms_scalar(a, b)
+    }
+
     fn cosine(a: &[f32], b: &[f32]) -> Option<f32> {
         if a.is_empty() || a.len() != b.len() {
             return None;
         }
 
-        #[cfg(target_arch = "x86_64")]
-        let (dot, norm_a, norm_b) = {
-            if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
-                // SAFETY: feature support verified on the line above; lengths
-                // verified equal at the top of the function.
-                unsafe { Self::dot_and_norms_avx2(a, b) }
-            } else {
-                Self::dot_and_norms_scalar(a, b)
-            }
-        };
-        #[cfg(not(target_arch = "x86_64"))]
-        let (dot, norm_a, norm_b) = Self::dot_and_norms_scalar(a, b);
-
+        let (dot, norm_a, norm_b) = Self::dot_and_norms(a, b);
         let denom = (norm_a * norm_b).sqrt();
-        if denom <= f32::EPSILON {
-            return None; // zero vector: similarity undefined
+        let cos = dot / denom;
+        if denom > f32::EPSILON && denom.is_finite() && cos.is_finite() {
+            return Some(cos.clamp(-1.0, 1.0));
         }
-        Some((dot / denom).clamp(-1.0, 1.0))
+        Self::cosine_rescaled(a, b)
     }
+
+    /// Cosine of vectors whose squared norms overflow or underflow `f32`:
+    /// each is first scaled so its largest component has magnitude 1.
+    /// `None` for a zero vector or non-finite components.
+    #[cold]
+    fn cosine_rescaled(a: &[f32], b: &[f32]) -> Option<f32> {
+        let (scale_a, scale_b) = (max_abs(a), max_abs(b));
+        let usable = |scale: f32| scale > 0.0 && scale.is_finite();
+        if !usable(scale_a) || !usable(scale_b) {
+            return None;
+        }
+        let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+        for (x, y) in a.iter().zip(b) {
+            let (x, y) = (x / scale_a, y / scale_b);
+            dot += x * y;
+            norm_a += x * x;
+            norm_b += y * y;
+        }
+        // Both norms are at least 1: the largest component scaled to 1.
+        let cos = dot / (norm_a * norm_b).sqrt();
+        cos.is_finite().then(|| cos.clamp(-1.0, 1.0))
+    }
+}
+
+/// Largest component magnitude of `v`; NaN components are ignored.
+fn max_abs(v: &[f32]) -> f32 {
+    v.iter().fold(0.0f32, |m, x| m.max(x.abs()))
 }
 
 impl DistanceMetric for CosineDistance {
@@ -204,10 +385,15 @@ impl DistanceMetric for CosineDistance {
 
     /// Unit-normalize, so stored vectors and queries can be compared with a
     /// bare dot product. A degenerate (zero / near-zero) vector has no
-    /// direction and is left unchanged rather than producing NaNs.
+    /// direction and is left unchanged rather than producing NaNs; one
+    /// whose squared norm overflows is scaled down first.
     fn preprocess(&self, v: &[f32]) -> Vec<f32> {
         let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if norm <= f32::EPSILON {
+        if norm.is_infinite() {
+            let scale = max_abs(v);
+            return self.preprocess(&v.iter().map(|x| x / scale).collect::<Vec<_>>());
+        }
+        if norm.is_nan() || norm <= f32::EPSILON {
             return v.to_vec();
         }
         v.iter().map(|x| x / norm).collect()
@@ -220,10 +406,388 @@ impl DistanceMetric for CosineDistance {
         if a.is_empty() || a.len() != b.len() {
             return 2.0;
         }
-        (1.0 - Self::dot(a, b)).clamp(0.0, 2.0)
+        let dot = Self::dot(a, b);
+        if dot.is_nan() || dot.abs() <= f32::EPSILON {
+            // Either orthogonal, or a degenerate operand that preprocessing
+            // could not normalize; only the full computation tells them
+            // apart (distance 1.0 vs 2.0). Rare enough to pay for.
+            return self.distance(a, b);
+        }
+        (1.0 - dot).clamp(0.0, 2.0)
     }
 }
 
+/// Euclidean (L2) distance.
+///
+/// Unlike cosine distance it is unbounded, so in
+/// [`search`](crate::ChronoMind::search) scoring its scale — and so the
+/// data's scale — sets how strongly geometry weighs against recency.
+/// Mismatched lengths yield `f32::INFINITY`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EuclideanDistance;
+
+impl EuclideanDistance {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DistanceMetric for EuclideanDistance {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        if a.len() != b.len() {
+            return f32::INFINITY;
+        }
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y) * (x - y))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    /// `1 / (1 + distance)`: `1.0` for identical vectors, approaching `0.0`
+    /// with distance.
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        1.0 / (1.0 + self.distance(a, b))
+    }
+
+    fn name(&self) -> &'static str {
+        "euclidean"
+    }
+}
+
+/// Manhattan (L1) distance: the sum of absolute component differences.
+///
+/// Unbounded, like [`EuclideanDistance`]. Mismatched lengths yield
+/// `f32::INFINITY`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ManhattanDistance;
+
+impl ManhattanDistance {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DistanceMetric for ManhattanDistance {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        if a.len() != b.len() {
+            return f32::INFINITY;
+        }
+        a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum()
+    }
+
+    /// `1 / (1 + distance)`.
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        1.0 / (1.0 + self.distance(a, b))
+    }
+
+    fn name(&self) -> &'static str {
+        "manhattan"
+    }
+}
+
+/// Chebyshev (L∞) distance: the largest absolute component difference.
+///
+/// Unbounded, like [`EuclideanDistance`]. Mismatched lengths yield
+/// `f32::INFINITY`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChebyshevDistance;
+
+impl ChebyshevDistance {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DistanceMetric for ChebyshevDistance {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        if a.len() != b.len() {
+            return f32::INFINITY;
+        }
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y).abs())
+            .fold(0.0, f32::max)
+    }
+
+    /// `1 / (1 + distance)`.
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        1.0 / (1.0 + self.distance(a, b))
+    }
+
+    fn name(&self) -> &'static str {
+        "chebyshev"
+    }
+}
+
+/// The metrics that ship with the crate, for configuration layers that
+/// offer a choice of metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuiltinMetric {
+    /// [`CosineDistance`].
+    Cosine,
+    /// [`EuclideanDistance`].
+    Euclidean,
+    /// [`ManhattanDistance`].
+    Manhattan,
+    /// [`ChebyshevDistance`].
+    Chebyshev,
+}
+
+impl BuiltinMetric {
+    /// Every built-in metric.
+    pub const ALL: [Self; 4] = [
+        Self::Cosine,
+        Self::Euclidean,
+        Self::Manhattan,
+        Self::Chebyshev,
+    ];
+
+    /// A new instance of the metric.
+    pub fn build(self) -> Arc<dyn DistanceMetric> {
+        match self {
+            Self::Cosine => Arc::new(CosineDistance::new()),
+            Self::Euclidean => Arc::new(EuclideanDistance::new()),
+            Self::Manhattan => Arc::new(ManhattanDistance::new()),
+            Self::Chebyshev => Arc::new(ChebyshevDistance::new()),
+        }
+    }
+
+    /// The metric's [`name`](DistanceMetric::name), under which a fresh
+    /// [`MetricRegistry`] knows it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cosine => "cosine",
+            Self::Euclidean => "euclidean",
+            Self::Manhattan => "manhattan",
+            Self::Chebyshev => "chebyshev",
+        }
+    }
+}
+
+/// A weighted blend of other metrics, for hybrid retrieval.
+///
+/// Distance is the weighted mean of the components' distances, so it lies
+/// between the smallest and largest of them; similarity blends the same
+/// way. Components compare raw vectors — their
+/// [`preprocess`](DistanceMetric::preprocess) steps are not applied, as
+/// they may disagree.
+pub struct CompositeDistance {
+    components: Vec<(Arc<dyn DistanceMetric>, f32)>,
+    total_weight: f32,
+}
+
+impl std::fmt::Debug for CompositeDistance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let components: Vec<(&str, f32)> = self
+            .components
+            .iter()
+            .map(|(metric, weight)| (metric.name(), *weight))
+            .collect();
+        f.debug_struct("CompositeDistance")
+            .field("components", &components)
+            .finish()
+    }
+}
+
+impl CompositeDistance {
+    /// Blend `components`, each `(metric, weight)`.
+    ///
+    /// Weights must be finite and non-negative with a positive sum; they
+    /// need not sum to one.
+    pub fn new(components: Vec<(Arc<dyn DistanceMetric>, f32)>) -> Result<Self> {
+        if components.is_empty() {
+            return Err(Error::InvalidArgument(
+                "a composite metric needs at least one component".into(),
+            ));
+        }
+        if let Some((metric, weight)) = components.iter().find(|(_, w)| !w.is_finite() || *w < 0.0)
+        {
+            return Err(Error::InvalidArgument(format!(
+                "weight {weight} of component {} must be finite and non-negative",
+                metric.name()
+            )));
+        }
+        let total_weight: f32 = components.iter().map(|(_, w)| w).sum();
+        if total_weight <= 0.0 {
+            return Err(Error::InvalidArgument(
+                "composite metric weights must not all be zero".into(),
+            ));
+        }
+        Ok(Self {
+            components,
+            total_weight,
+        })
+    }
+
+    fn blend(&self, f: impl Fn(&dyn DistanceMetric) -> f32) -> f32 {
+        self.components
+            .iter()
+            .map(|(metric, weight)| weight * f(metric.as_ref()))
+            .sum::<f32>()
+            / self.total_weight
+    }
+}
+
+impl DistanceMetric for CompositeDistance {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.blend(|m| m.distance(a, b))
+    }
+
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.blend(|m| m.similarity(a, b))
+    }
+
+    fn name(&self) -> &'static str {
+        "composite"
+    }
+}
+
+/// Builds an instance of a registered metric.
+pub type MetricFactory = Box<dyn Fn() -> Arc<dyn DistanceMetric> + Send + Sync>;
+
+/// Named metric factories.
+///
+/// A fresh registry knows the built-in metrics under their
+/// [`name`](DistanceMetric::name)s. Registration and lookup are lock-free,
+/// so plugins can register from any thread at any time.
+pub struct MetricRegistry {
+    factories: papaya::HashMap<String, MetricFactory>,
+}
+
+impl std::fmt::Debug for MetricRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetricRegistry")
+            .field("names", &self.names())
+            .finish()
+    }
+}
+
+impl Default for MetricRegistry {
+    fn default() -> Self {
+        let registry = Self {
+            factories: papaya::HashMap::new(),
+        };
+        for builtin in BuiltinMetric::ALL {
+            registry.register(builtin.name(), Box::new(move || builtin.build()));
+        }
+        registry
+    }
+}
+
+impl MetricRegistry {
+    /// A registry holding only the built-in metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide registry [`metric_from_name`] consults.
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<MetricRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// Register `factory` under `name`, replacing any metric already
+    /// registered under it — built-ins included.
+    pub fn register(&self, name: &str, factory: MetricFactory) {
+        self.factories.pin().insert(name.to_owned(), factory);
+    }
+
+    /// A new instance of the metric registered as `name`.
+    pub fn create(&self, name: &str) -> Option<Arc<dyn DistanceMetric>> {
+        self.factories.pin().get(name).map(|factory| factory())
+    }
+
+    /// Every registered name, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.factories.pin().keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+/// The metric registered as `name` in the [global](MetricRegistry::global)
+/// registry.
+pub fn metric_from_name(name: &str) -> Result<Arc<dyn DistanceMetric>> {
+    MetricRegistry::global().create(name).ok_or_else(|| {
+        Error::InvalidArgument(format!(
+            "unknown distance metric {name:?} (registered: {})",
+            MetricRegistry::global().names().join(", ")
+        ))
+    })
+}
+
+/// Check that `metric` behaves the way the index and search scoring rely
+/// on, over every pair and triple drawn from `samples`:
+///
+/// - distances are non-negative and symmetric, and no vector is farther
+///   from itself than from any other;
+/// - [`distance_prepared`](DistanceMetric::distance_prepared) over
+///   [`preprocess`](DistanceMetric::preprocess)ed vectors agrees with
+///   [`distance`](DistanceMetric::distance);
+/// - [`similarity`](DistanceMetric::similarity) never ranks two vectors
+///   the opposite way distance does.
+///
+/// Comparisons allow a relative tolerance of `1e-4` for rounding. Returns
+/// [`Error::InvalidArgument`] describing the first violation found. Meant
+/// for the tests of custom metrics; the cost is cubic in `samples.len()`,
+/// so a few dozen samples are plenty.
+pub fn check_metric_consistency(metric: &dyn DistanceMetric, samples: &[Vec<f32>]) -> Result<()> {
+    const TOLERANCE: f32 = 1e-4;
+    let close = |x: f32, y: f32| (x - y).abs() <= TOLERANCE * x.abs().max(y.abs()).max(1.0);
+    let fail = |message: String| {
+        Err(Error::InvalidArgument(format!(
+            "metric {} is inconsistent: {message}",
+            metric.name()
+        )))
+    };
+
+    let prepared: Vec<Vec<f32>> = samples.iter().map(|v| metric.preprocess(v)).collect();
+    for (i, a) in samples.iter().enumerate() {
+        let own = metric.distance(a, a);
+        for (j, b) in samples.iter().enumerate() {
+            let d = metric.distance(a, b);
+            if d < -TOLERANCE {
+                return fail(format!("distance({i}, {j}) = {d} is negative"));
+            }
+            let reverse = metric.distance(b, a);
+            if !close(d, reverse) {
+                return fail(format!(
+                    "distance({i}, {j}) = {d} but distance({j}, {i}) = {reverse}"
+                ));
+            }
+            if own > d && !close(own, d) {
+                return fail(format!(
+                    "sample {i} is farther from itself ({own}) than from {j} ({d})"
+                ));
+            }
+            let fast = metric.distance_prepared(&prepared[i], &prepared[j]);
+            if !close(d, fast) {
+                return fail(format!(
+                    "distance({i}, {j}) = {d} but the prepared distance is {fast}"
+                ));
+            }
+        }
+        for (j, b) in samples.iter().enumerate() {
+            for (k, c) in samples.iter().enumerate() {
+                let (near, far) = (metric.distance(a, b), metric.distance(a, c));
+                if near < far && !close(near, far) {
+                    let (s_near, s_far) = (metric.similarity(a, b), metric.similarity(a, c));
+                    if s_near < s_far && !close(s_near, s_far) {
+                        return fail(format!(
+                            "{j} is nearer {i} than {k} is, but less similar ({s_near} < {s_far})"
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -264,6 +828,37 @@ mod tests {
         assert_eq!(m.similarity(&[0.0, 0.0], &[0.0, 0.0]), 0.0);
     }
 
+    #[test]
+    fn extreme_magnitudes_keep_their_direction() {
+        let m = CosineDistance::new();
+        // Squared norms underflow (once mistaken for zero vectors) or
+        // overflow (once NaN) the single-pass computation.
+        for scale in [1e-5f32, 1e-30, 1e-40, 1e20, 3e38] {
+            let a = [scale, scale];
+            let b = [scale, -scale];
+            assert!(m.distance(&a, &a).abs() < EPS, "{scale}");
+            assert!((m.distance(&a, &b) - 1.0).abs() < EPS, "{scale}");
+            assert!((m.similarity(&a, &a) - 1.0).abs() < EPS, "{scale}");
+            let (pa, pb) = (m.preprocess(&a), m.preprocess(&b));
+            assert!(m.distance_prepared(&pa, &pa).abs() < EPS, "{scale}");
+            assert!((m.distance_prepared(&pa, &pb) - 1.0).abs() < EPS, "{scale}");
+        }
+        assert_eq!(m.distance(&[f32::NAN, 1.0], &[1.0, 1.0]), 2.0);
+        assert_eq!(m.similarity(&[f32::INFINITY, 1.0], &[1.0, 1.0]), 0.0);
+    }
+
+    #[test]
+    fn prepared_distance_agrees_on_zero_vectors() {
+        let m = CosineDistance::new();
+        let zero = m.preprocess(&[0.0, 0.0, 0.0]);
+        let x = m.preprocess(&[3.0, 0.0, 0.0]);
+        let y = m.preprocess(&[0.0, 2.0, 0.0]);
+        assert_eq!(m.distance_prepared(&zero, &x), 2.0);
+        assert_eq!(m.distance_prepared(&x, &zero), 2.0);
+        assert_eq!(m.distance_prepared(&zero, &zero), 2.0);
+        assert!((m.distance_prepared(&x, &y) - 1.0).abs() < EPS);
+    }
+
     #[test]
     fn simd_and_scalar_paths_agree() {
         // Exercise lengths around the 8-lane SIMD boundary, including the
@@ -285,4 +880,130 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn every_dot_product_path_agrees() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let a: Vec<f32> = (0..1536).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        let b: Vec<f32> = (0..1536).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        // Relative to the sum of |products|, which bounds rounding error
+        // independent of cancellation in the sum itself.
+        let scale: f32 = a.iter().zip(&b).map(|(x, y)| (x * y).abs()).sum();
+        let agree = |got: f32, expected: f32| (got - expected).abs() <= 1e-5 * scale;
+
+        let scalar = CosineDistance::dot_scalar(&a, &b);
+        let (_, na, nb) = CosineDistance::dot_and_norms_scalar(&a, &b);
+        let mut paths = vec![];
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            paths.push(("avx2", unsafe { CosineDistance::dot_avx2(&a, &b) }));
+            let (dot, pa, pb) = unsafe { CosineDistance::dot_and_norms_avx2(&a, &b) };
+            assert!(agree(dot, scalar) && agree(pa, na) && agree(pb, nb));
+        }
+        #[cfg(feature = "avx512")]
+        if is_x86_feature_detected!("avx512f") {
+            paths.push(("avx512", unsafe { CosineDistance::dot_avx512(&a, &b) }));
+            let (dot, pa, pb) = unsafe { CosineDistance::dot_and_norms_avx512(&a, &b) };
+            assert!(agree(dot, scalar) && agree(pa, na) && agree(pb, nb));
+            // Masked tails of every length.
+            for len in 1..=33 {
+                let expected = CosineDistance::dot_scalar(&a[..len], &b[..len]);
+                let got = unsafe { CosineDistance::dot_avx512(&a[..len], &b[..len]) };
+                assert!(
+                    (got - expected).abs() < 1e-5,
+                    "len {len}: {got} vs {expected}"
+                );
+            }
+        }
+        for (name, dot) in paths {
+            assert!(agree(dot, scalar), "{name} {dot} vs scalar {scalar}");
+        }
+    }
+
+    #[test]
+    #[cfg(target_arch = "aarch64")]
+    fn neon_and_scalar_paths_agree() {
+        // Lengths around the 4-lane boundary, plus a full-size embedding.
+        for len in [1usize, 3, 4, 5, 7, 8, 9, 1536] {
+            let a: Vec<f32> = (0..len).map(|i| ((i * 37 % 19) as f32) - 9.0).collect();
+            let b: Vec<f32> = (0..len).map(|i| ((i * 53 % 23) as f32) - 11.0).collect();
+            let scale: f32 = a.iter().zip(&b).map(|(x, y)| (x * y).abs()).sum();
+            let agree = |got: f32, expected: f32| (got - expected).abs() <= 1e-5 * scale;
+
+            let scalar = CosineDistance::dot_scalar(&a, &b);
+            let (_, na, nb) = CosineDistance::dot_and_norms_scalar(&a, &b);
+            let neon = unsafe { CosineDistance::dot_neon(&a, &b) };
+            let (dot, pa, pb) = unsafe { CosineDistance::dot_and_norms_neon(&a, &b) };
+            assert!(agree(neon, scalar), "len {len}: neon {neon} vs {scalar}");
+            assert!(
+                agree(dot, scalar) && agree(pa, na) && agree(pb, nb),
+                "len {len}"
+            );
+        }
+    }
+
+    #[test]
+    fn euclidean_distance_is_l2() {
+        let m = EuclideanDistance::new();
+        assert_eq!(m.distance(&[0.0, 0.0], &[3.0, 4.0]), 5.0);
+        assert_eq!(m.similarity(&[1.0, 1.0], &[1.0, 1.0]), 1.0);
+        assert_eq!(m.distance(&[1.0], &[1.0, 2.0]), f32::INFINITY);
+    }
+
+    #[test]
+    fn composite_lies_between_its_components() {
+        let cosine: Arc<dyn DistanceMetric> = Arc::new(CosineDistance::new());
+        let euclidean: Arc<dyn DistanceMetric> = Arc::new(EuclideanDistance::new());
+        let composite = CompositeDistance::new(vec![
+            (Arc::clone(&cosine), 0.5),
+            (Arc::clone(&euclidean), 0.5),
+        ])
+        .unwrap();
+        let (a, b) = ([1.0, 0.0, 2.0], [0.5, 1.5, -1.0]);
+        let (c, e) = (cosine.distance(&a, &b), euclidean.distance(&a, &b));
+        let blended = composite.distance(&a, &b);
+        assert!(c.min(e) < blended && blended < c.max(e));
+        assert!((blended - (c + e) / 2.0).abs() < EPS);
+
+        // Weights are normalized.
+        let scaled =
+            CompositeDistance::new(vec![(Arc::clone(&cosine), 3.0), (euclidean, 3.0)]).unwrap();
+        assert!((scaled.distance(&a, &b) - blended).abs() < EPS);
+    }
+
+    #[test]
+    fn composite_weights_are_validated() {
+        let cosine = || -> Arc<dyn DistanceMetric> { Arc::new(CosineDistance::new()) };
+        assert!(CompositeDistance::new(vec![]).is_err());
+        assert!(CompositeDistance::new(vec![(cosine(), -0.5)]).is_err());
+        assert!(CompositeDistance::new(vec![(cosine(), f32::NAN)]).is_err());
+        assert!(CompositeDistance::new(vec![(cosine(), 0.0)]).is_err());
+        assert!(CompositeDistance::new(vec![(cosine(), 0.0), (cosine(), 1.0)]).is_ok());
+    }
+
+    #[test]
+    fn l1_and_linf_distances() {
+        let (a, b) = ([0.0, 0.0, 0.0], [3.0, -4.0, 1.0]);
+        assert_eq!(ManhattanDistance::new().distance(&a, &b), 8.0);
+        assert_eq!(ChebyshevDistance::new().distance(&a, &b), 4.0);
+        assert_eq!(ChebyshevDistance::new().distance(&a, &a), 0.0);
+    }
+
+    #[test]
+    fn every_builtin_builds_and_measures() {
+        let registry = MetricRegistry::new();
+        let (a, b) = ([1.0, 2.0, 3.0], [-1.0, 0.5, 2.0]);
+        for builtin in BuiltinMetric::ALL {
+            let metric = builtin.build();
+            assert_eq!(metric.name(), builtin.name());
+            let d = metric.distance(&a, &b);
+            assert!(d.is_finite() && d > 0.0, "{builtin:?}: {d}");
+            assert!(metric.distance(&a, &a).abs() < EPS, "{builtin:?}");
+            let s = metric.similarity(&a, &b);
+            assert!((-1.0..=1.0).contains(&s), "{builtin:?}: {s}");
+            assert_eq!(registry.create(builtin.name()).unwrap().distance(&a, &b), d);
+        }
+    }
 }
@@ -0,0 +1,146 @@
+//! Operation metrics with Prometheus text exposition.
+//!
+//! A [`MetricsRegistry`] counts operations and their latencies, labeled by
+//! operation name and memory context, and renders them in the Prometheus
+//! text format for a scrape endpoint the application serves. Recording is
+//! lock-free: series are created on first use in a concurrent map and
+//! updated with atomic adds, so a registry can be shared by every thread
+//! using a store.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds, in seconds, of the latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 14] = [
+    0.000_05, 0.000_1, 0.000_25, 0.000_5, 0.001, 0.002_5, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5,
+    1.0,
+];
+
+/// One `(operation, context)` series.
+#[derive(Default)]
+struct OperationSeries {
+    count: AtomicU64,
+    total_nanos: AtomicU64,
+    /// Non-cumulative: `buckets[i]` counts observations in
+    /// `(LATENCY_BUCKETS[i - 1], LATENCY_BUCKETS[i]]`.
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+}
+
+/// Operation counters and latency histograms, keyed by operation name and
+/// context label.
+///
+/// Pass an empty context for operations that are not scoped to one.
+#[derive(Default)]
+pub struct MetricsRegistry {
+    series: papaya::HashMap<(String, String), OperationSeries>,
+}
+
+impl std::fmt::Debug for MetricsRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetricsRegistry")
+            .field("series", &self.series.pin().len())
+            .finish()
+    }
+}
+
+impl MetricsRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one completed `operation` on `context` that took `duration`.
+    pub fn record_operation_duration(&self, operation: &str, context: &str, duration: Duration) {
+        let series = self.series.pin();
+        let entry = series.get_or_insert_with((operation.to_owned(), context.to_owned()), || {
+            OperationSeries::default()
+        });
+        entry.count.fetch_add(1, Ordering::Relaxed);
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        entry.total_nanos.fetch_add(nanos, Ordering::Relaxed);
+        let seconds = duration.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|&le| seconds <= le) {
+            entry.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Number of `operation`s recorded on `context`.
+    pub fn operation_count(&self, operation: &str, context: &str) -> u64 {
+        self.series
+            .pin()
+            .get(&(operation.to_owned(), context.to_owned()))
+            .map_or(0, |s| s.count.load(Ordering::Relaxed))
+    }
+
+    /// Every series in the Prometheus text exposition format (version
+    /// 0.0.4), with `HELP` and `TYPE` lines, ordered by operation and
+    /// context.
+    ///
+    /// Values are read without a global snapshot, so a scrape racing
+    /// recordings may see a histogram's count and buckets one observation
+    /// apart; Prometheus tolerates that.
+    pub fn render_prometheus(&self) -> String {
+        let series = self.series.pin();
+        let mut entries: Vec<(&(String, String), &OperationSeries)> = series.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut out = String::new();
+        out.push_str(
+            "# HELP chronomind_operations_total Completed operations, by operation and context.\n",
+        );
+        out.push_str("# TYPE chronomind_operations_total counter\n");
+        for ((operation, context), s) in &entries {
+            let labels = labels(operation, context);
+            let count = s.count.load(Ordering::Relaxed);
+            let _ = writeln!(out, "chronomind_operations_total{{{labels}}} {count}");
+        }
+
+        out.push_str(
+            "# HELP chronomind_operation_duration_seconds Operation latency, by operation and context.\n",
+        );
+        out.push_str("# TYPE chronomind_operation_duration_seconds histogram\n");
+        for ((operation, context), s) in &entries {
+            let labels = labels(operation, context);
+            let mut cumulative = 0;
+            for (le, bucket) in LATENCY_BUCKETS.iter().zip(&s.buckets) {
+                cumulative += bucket.load(Ordering::Relaxed);
+                let _ = writeln!(
+                    out,
+                    "chronomind_operation_duration_seconds_bucket{{{labels},le=\"{le}\"}} {cumulative}"
+                );
+            }
+            let count = s.count.load(Ordering::Relaxed);
+            let sum = s.total_nanos.load(Ordering::Relaxed) as f64 / 1e9;
+            let _ = writeln!(
+                out,
+                "chronomind_operation_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {count}"
+            );
+            let _ = writeln!(
+                out,
+                "chronomind_operation_duration_seconds_sum{{{labels}}} {sum}"
+            );
+            let _ = writeln!(
+                out,
+                "chronomind_operation_duration_seconds_count{{{labels}}} {count}"
+            );
+        }
+        out
+    }
+}
+
+fn labels(operation: &str, context: &str) -> String {
+    format!(
+        "operation=\"{}\",context=\"{}\"",
+        escape(operation),
+        escape(context)
+    )
+}
+
+/// Escape a label value: backslash, double quote and newline.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,219 @@
+//! Principal component analysis for reducing embedding dimensionality.
+//!
+//! Very high-dimensional embeddings often carry most of their variance in
+//! a much smaller subspace. A fitted [`Pca`] projects vectors onto the top
+//! principal components of a sample corpus, and [`PcaMetric`] applies that
+//! projection through the metric's
+//! [`preprocess`](crate::DistanceMetric::preprocess) hook, so the index
+//! holds — and traverses — reduced vectors while the store keeps the
+//! originals; see [`ChronoMind::with_pca`](crate::ChronoMind::with_pca).
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::linalg;
+use crate::metric::DistanceMetric;
+
+/// A fitted projection onto the top principal components of a corpus:
+/// `x -> C (x - mean)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pca {
+    mean: Vec<f32>,
+    /// `output_dimensions x input_dimensions`, row-major; row `k` is the
+    /// `k`-th principal axis.
+    components: Vec<f32>,
+    /// Share of the corpus's total variance along the kept axes.
+    explained_variance: f32,
+}
+
+impl Pca {
+    /// Fit a projection from `vectors` down to `target_dim` dimensions.
+    ///
+    /// `vectors` must number at least two and share one dimensionality,
+    /// and `target_dim` must be between 1 and that dimensionality. Fitting
+    /// costs `O(n·d²)` for the covariance plus `O(d³)` per solver sweep.
+    pub fn fit(vectors: &[&[f32]], target_dim: usize) -> Result<Self> {
+        if vectors.len() < 2 {
+            return Err(Error::InvalidArgument(
+                "PCA needs at least two vectors to estimate covariance".into(),
+            ));
+        }
+        let dim = vectors[0].len();
+        if let Some(bad) = vectors.iter().find(|v| v.len() != dim) {
+            return Err(Error::InvalidDimensions {
+                got: bad.len(),
+                expected: dim,
+            });
+        }
+        if target_dim == 0 || target_dim > dim {
+            return Err(Error::InvalidArgument(format!(
+                "PCA target dimensionality must be between 1 and {dim}, got {target_dim}"
+            )));
+        }
+
+        let (mean, covariance) = linalg::mean_and_covariance(vectors, dim);
+        let (values, axes) = linalg::symmetric_eigen(&covariance, dim);
+
+        let total: f64 = values.iter().map(|v| v.max(0.0)).sum();
+        let kept: f64 = values[..target_dim].iter().map(|v| v.max(0.0)).sum();
+        let explained_variance = if total > 0.0 { kept / total } else { 1.0 };
+
+        Ok(Self {
+            mean: mean.into_iter().map(|m| m as f32).collect(),
+            components: axes[..target_dim * dim].iter().map(|&c| c as f32).collect(),
+            explained_variance: explained_variance as f32,
+        })
+    }
+
+    /// Dimensionality of the vectors the projection was fitted on.
+    pub fn input_dimensions(&self) -> usize {
+        self.mean.len()
+    }
+
+    /// Dimensionality of projected vectors.
+    pub fn output_dimensions(&self) -> usize {
+        self.components.len() / self.mean.len()
+    }
+
+    /// Fraction of the fitted corpus's variance the kept components
+    /// retain, in `[0.0, 1.0]`.
+    pub fn explained_variance(&self) -> f32 {
+        self.explained_variance
+    }
+
+    /// Project one vector. Vectors of the wrong length are returned
+    /// unchanged, so the wrapped metric sees — and rejects — them as usual.
+    pub fn transform(&self, v: &[f32]) -> Vec<f32> {
+        let dim = self.mean.len();
+        if v.len() != dim {
+            return v.to_vec();
+        }
+        let centered: Vec<f32> = v.iter().zip(&self.mean).map(|(x, m)| x - m).collect();
+        self.components
+            .chunks_exact(dim)
+            .map(|axis| axis.iter().zip(&centered).map(|(c, x)| c * x).sum())
+            .collect()
+    }
+}
+
+/// A metric that compares [`Pca`]-projected vectors with an inner metric.
+///
+/// Pass one to [`load_snapshot_with_metric`](crate::load_snapshot_with_metric)
+/// to reopen a store created with
+/// [`ChronoMind::with_pca`](crate::ChronoMind::with_pca); snapshots do not
+/// record the projection.
+pub struct PcaMetric {
+    inner: Arc<dyn DistanceMetric>,
+    pca: Arc<Pca>,
+}
+
+impl PcaMetric {
+    /// Compare vectors projected by `pca` under `inner`.
+    pub fn new(inner: Arc<dyn DistanceMetric>, pca: Arc<Pca>) -> Self {
+        Self { inner, pca }
+    }
+
+    /// The projection applied before the inner metric.
+    pub fn pca(&self) -> &Pca {
+        &self.pca
+    }
+}
+
+impl DistanceMetric for PcaMetric {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.inner
+            .distance(&self.pca.transform(a), &self.pca.transform(b))
+    }
+
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.inner
+            .similarity(&self.pca.transform(a), &self.pca.transform(b))
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn preprocess(&self, v: &[f32]) -> Vec<f32> {
+        self.inner.preprocess(&self.pca.transform(v))
+    }
+
+    fn distance_prepared(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.inner.distance_prepared(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points near a 2-D plane embedded in 6 dimensions.
+    fn planar_corpus() -> Vec<Vec<f32>> {
+        (0..300)
+            .map(|i| {
+                let s = (i as f32 * 0.37).sin() * 10.0;
+                let t = (i as f32 * 1.13).cos() * 5.0;
+                let noise = (i as f32 * 7.7).sin() * 0.05;
+                vec![s + t, s - t, 2.0 * s, t + noise, 1.0, -s + noise]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn projection_keeps_the_variance_of_a_planar_corpus() {
+        let data = planar_corpus();
+        let refs: Vec<&[f32]> = data.iter().map(Vec::as_slice).collect();
+        let pca = Pca::fit(&refs, 2).unwrap();
+        assert_eq!((pca.input_dimensions(), pca.output_dimensions()), (6, 2));
+        assert!(
+            pca.explained_variance() > 0.99,
+            "{}",
+            pca.explained_variance()
+        );
+
+        // Pairwise distances survive the projection almost unchanged.
+        let euclid = |a: &[f32], b: &[f32]| -> f32 {
+            a.iter()
+                .zip(b)
+                .map(|(x, y)| (x - y).powi(2))
+                .sum::<f32>()
+                .sqrt()
+        };
+        for (a, b) in data.iter().zip(data.iter().skip(1)) {
+            let full = euclid(a, b);
+            let reduced = euclid(&pca.transform(a), &pca.transform(b));
+            assert!(
+                (full - reduced).abs() < 0.05 * full.max(1.0),
+                "{full} vs {reduced}"
+            );
+        }
+
+        // Projected coordinates are centered and uncorrelated.
+        let projected: Vec<Vec<f32>> = data.iter().map(|v| pca.transform(v)).collect();
+        let refs: Vec<&[f32]> = projected.iter().map(Vec::as_slice).collect();
+        let (mean, cov) = linalg::mean_and_covariance(&refs, 2);
+        assert!(mean.iter().all(|m| m.abs() < 1e-3));
+        assert!(cov[1].abs() < 1e-2 * cov[0]);
+        assert!(cov[0] >= cov[3], "components come in descending variance");
+    }
+
+    #[test]
+    fn fit_rejects_degenerate_input() {
+        assert!(matches!(
+            Pca::fit(&[&[1.0, 2.0]], 1),
+            Err(Error::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            Pca::fit(&[&[1.0, 2.0], &[1.0]], 1),
+            Err(Error::InvalidDimensions { .. })
+        ));
+        for target in [0, 3] {
+            assert!(matches!(
+                Pca::fit(&[&[1.0, 2.0], &[2.0, 1.0]], target),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+    }
+}
@@ -1,28 +1,50 @@
 //! Snapshot persistence: a versioned, checksummed binary format for saving
 //! and loading a complete store.
 //!
-//! Format (version 2): 7-byte magic `CHRONO1`, one format-version byte,
+//! Format (version 3): 7-byte magic `CHRONO1`, one format-version byte,
 //! a little-endian CRC32 of the body, then a bincode body containing the
 //! configuration and all memories. The index is rebuilt on load.
 //!
+//! Older versions are upgraded on load by a per-version migration (see
+//! `migrate`); files from newer versions are rejected.
+//!
+//! Compressed snapshots use the magic `CHRONOZ` and store the bincode body
+//! zstd-compressed, checksummed as stored. [`load_snapshot`] tells the two
+//! apart by their magic.
+//!
 //! Writes are crash-safe: the snapshot is written to a temporary file in
 //! the destination's directory and atomically renamed over the target, so
 //! a crash mid-write can never destroy the previous snapshot.
+//!
+//! For interchange with other tools, [`export_ndjson`] and
+//! [`import_ndjson`] move memories as newline-delimited JSON instead: one
+//! [`Memory`] object per line, no configuration, no checksum.
+//! [`import_ndjson_file`] streams large files with progress reporting.
 
+use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufReader, Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::Path;
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
 use crate::config::Config;
 use crate::error::{Error, Result};
+use crate::metric::{CosineDistance, DistanceMetric};
 use crate::store::ChronoMind;
 use crate::types::Memory;
 
 const MAGIC: &[u8; 7] = b"CHRONO1";
-const FORMAT_VERSION: u8 = 2;
+const MAGIC_COMPRESSED: &[u8; 7] = b"CHRONOZ";
+/// zstd's default level: most of the size win at a fraction of the
+/// high levels' CPU cost.
+const COMPRESSION_LEVEL: i32 = 3;
+const FORMAT_VERSION: u8 = 3;
+
+/// Oldest format version [`load_snapshot`] can still upgrade.
+const OLDEST_READABLE_VERSION: u8 = 2;
 
 #[derive(Serialize, Deserialize)]
 struct SnapshotBody {
@@ -37,13 +59,81 @@ struct SnapshotBody {
 /// renamed into place only after it is fully written and flushed — a crash
 /// at any point leaves either the old snapshot or the new one, never a
 /// torn file.
+///
+/// Memories are written in id order, so two stores with the same contents
+/// produce byte-identical snapshots.
 #[instrument(skip(store))]
 pub fn save_snapshot(store: &ChronoMind, path: &Path) -> Result<()> {
+    write_snapshot(store, store.snapshot(), path, false).map(|_| ())
+}
+
+/// Like [`save_snapshot`], with the body zstd-compressed.
+///
+/// Float-heavy bodies typically shrink by a modest fraction; the size win
+/// grows with repeated metadata (contexts, relationship ids). Load with
+/// [`load_snapshot`] as usual.
+#[instrument(skip(store))]
+pub fn save_snapshot_compressed(store: &ChronoMind, path: &Path) -> Result<()> {
+    write_snapshot(store, store.snapshot(), path, true).map(|_| ())
+}
+
+/// Like [`save_snapshot`], leaving out memories whose importance is below
+/// `min_importance`, and return how many were written.
+///
+/// Meant for a lean cold start: the snapshot holds what
+/// [`prune_below_importance`](ChronoMind::prune_below_importance) would
+/// have left, links to the omitted memories dropped, without changing
+/// the store itself. `min_importance` must be finite
+/// ([`Error::InvalidArgument`] otherwise).
+#[instrument(skip(store))]
+pub fn save_snapshot_pruned(store: &ChronoMind, path: &Path, min_importance: f32) -> Result<usize> {
+    if !min_importance.is_finite() {
+        return Err(Error::InvalidArgument(format!(
+            "importance threshold must be finite, got {min_importance}"
+        )));
+    }
+    let (mut kept, omitted): (Vec<Memory>, Vec<Memory>) = store
+        .snapshot()
+        .into_iter()
+        .partition(|m| m.attributes.importance >= min_importance);
+    let omitted: HashSet<String> = omitted.into_iter().map(|m| m.vector.id).collect();
+    for memory in &mut kept {
+        let a = &mut memory.attributes;
+        if !a.relationships.iter().any(|l| omitted.contains(l)) {
+            continue;
+        }
+        let links: Vec<(String, f32)> = a
+            .weighted_relationships()
+            .filter(|(l, _)| !omitted.contains(*l))
+            .map(|(l, s)| (l.to_owned(), s))
+            .collect();
+        (a.relationships, a.relationship_strengths) = links.into_iter().unzip();
+    }
+    write_snapshot(store, kept, path, false)
+}
+
+/// Write `memories` under `store`'s configuration, returning how many
+/// were written.
+fn write_snapshot(
+    store: &ChronoMind,
+    mut memories: Vec<Memory>,
+    path: &Path,
+    compressed: bool,
+) -> Result<usize> {
+    // Id order makes the file a pure function of the store's contents, and
+    // fixes the insertion order the index is rebuilt in on load.
+    memories.sort_by(|a, b| a.vector.id.cmp(&b.vector.id));
     let body = SnapshotBody {
         config: store.config().clone(),
-        memories: store.snapshot(),
+        memories,
+    };
+    let mut encoded = bincode::serialize(&body)?;
+    let magic = if compressed {
+        encoded = zstd::encode_all(encoded.as_slice(), COMPRESSION_LEVEL)?;
+        MAGIC_COMPRESSED
+    } else {
+        MAGIC
     };
-    let encoded = bincode::serialize(&body)?;
     let checksum = crc32fast::hash(&encoded);
 
     let directory = path.parent().filter(|p| !p.as_os_str().is_empty());
@@ -51,7 +141,7 @@ pub fn save_snapshot(store: &ChronoMind, path: &Path) -> Result<()> {
         Some(dir) => tempfile::NamedTempFile::new_in(dir)?,
         None => tempfile::NamedTempFile::new_in(".")?,
     };
-    temp.write_all(MAGIC)?;
+    temp.write_all(magic)?;
     temp.write_all(&[FORMAT_VERSION])?;
     temp.write_all(&checksum.to_le_bytes())?;
     temp.write_all(&encoded)?;
@@ -59,18 +149,48 @@ pub fn save_snapshot(store: &ChronoMind, path: &Path) -> Result<()> {
     temp.as_file().sync_all()?;
     temp.persist(path).map_err(|e| Error::Io(e.error))?;
 
-    info!(memories = body.memories.len(), ?path, "snapshot saved");
-    Ok(())
+    info!(
+        memories = body.memories.len(),
+        compressed,
+        ?path,
+        "snapshot saved"
+    );
+    Ok(body.memories.len())
+}
+
+/// The body checksum recorded in the snapshot header at `path`, which
+/// identifies the snapshot without reading its body.
+pub(crate) fn snapshot_checksum(path: &Path) -> Result<u32> {
+    let mut header = [0u8; 12];
+    File::open(path)?
+        .read_exact(&mut header)
+        .map_err(|_| Error::InvalidSnapshot("file too short to be a ChronoMind snapshot".into()))?;
+    Ok(u32::from_le_bytes(header[8..].try_into().expect("4 bytes")))
 }
 
-/// Load a store from a snapshot written by [`save_snapshot`].
+/// Load a store from a snapshot written by [`save_snapshot`] or
+/// [`save_snapshot_compressed`].
 ///
 /// The body checksum is verified before deserialization, so silent
 /// corruption is rejected rather than half-loaded. The vector index is
 /// rebuilt during load; load time scales with the number of stored
 /// memories.
-#[instrument]
+///
+/// The restored store uses cosine distance; stores built with a custom
+/// metric must be restored with [`load_snapshot_with_metric`].
 pub fn load_snapshot(path: &Path) -> Result<ChronoMind> {
+    load_snapshot_with_metric(path, Arc::new(CosineDistance::new()))
+}
+
+/// Load a store from a snapshot, rebuilding its index with `metric`.
+///
+/// Snapshots record memories and configuration, not the metric, so pass
+/// the same metric the saved store was created with.
+#[instrument(skip(metric), fields(metric = metric.name()))]
+pub fn load_snapshot_with_metric(
+    path: &Path,
+    metric: Arc<dyn DistanceMetric>,
+) -> Result<ChronoMind> {
     let file = File::open(path)?;
     let mut reader = BufReader::new(file);
 
@@ -78,20 +198,25 @@ pub fn load_snapshot(path: &Path) -> Result<ChronoMind> {
     reader
         .read_exact(&mut magic)
         .map_err(|_| Error::InvalidSnapshot("file too short to be a ChronoMind snapshot".into()))?;
-    if &magic != MAGIC {
-        return Err(Error::InvalidSnapshot(
-            "bad magic bytes: not a ChronoMind snapshot".into(),
-        ));
-    }
+    let compressed = match &magic {
+        MAGIC => false,
+        MAGIC_COMPRESSED => true,
+        _ => {
+            return Err(Error::InvalidSnapshot(
+                "bad magic bytes: not a ChronoMind snapshot".into(),
+            ))
+        }
+    };
 
     let mut version = [0u8; 1];
     reader
         .read_exact(&mut version)
         .map_err(|_| Error::InvalidSnapshot("missing format version".into()))?;
-    if version[0] != FORMAT_VERSION {
+    let version = version[0];
+    if !(OLDEST_READABLE_VERSION..=FORMAT_VERSION).contains(&version) {
         return Err(Error::InvalidSnapshot(format!(
-            "unsupported format version {} (supported: {FORMAT_VERSION})",
-            version[0]
+            "unsupported format version {version} \
+             (supported: {OLDEST_READABLE_VERSION} through {FORMAT_VERSION})"
         )));
     }
 
@@ -111,8 +236,11 @@ pub fn load_snapshot(path: &Path) -> Result<ChronoMind> {
         )));
     }
 
-    let body: SnapshotBody = bincode::deserialize(&encoded)?;
-    let store = ChronoMind::new(body.config)?;
+    if compressed {
+        encoded = zstd::decode_all(encoded.as_slice())?;
+    }
+    let body = migrate(version, &encoded)?;
+    let store = ChronoMind::with_metric(body.config, metric)?;
     let count = body.memories.len();
     for memory in body.memories {
         store.insert(memory)?;
@@ -121,3 +249,226 @@ pub fn load_snapshot(path: &Path) -> Result<ChronoMind> {
     info!(memories = count, ?path, "snapshot loaded");
     Ok(store)
 }
+
+/// Write every memory in `store` to `writer` as newline-delimited JSON,
+/// one [`Memory`] object per line in id order, and return how many were
+/// written.
+///
+/// Each line is written separately; wrap unbuffered writers such as a
+/// [`File`] in a [`BufWriter`](std::io::BufWriter).
+#[instrument(skip(store, writer))]
+pub fn export_ndjson(store: &ChronoMind, mut writer: impl Write) -> Result<usize> {
+    let mut memories = store.snapshot();
+    memories.sort_by(|a, b| a.vector.id.cmp(&b.vector.id));
+    for memory in &memories {
+        serde_json::to_writer(&mut writer, memory)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    info!(memories = memories.len(), "ndjson exported");
+    Ok(memories.len())
+}
+
+/// Insert every memory in the newline-delimited JSON read from `reader`,
+/// as written by [`export_ndjson`], and return how many were inserted.
+///
+/// Blank lines are skipped. Each memory is validated against the store's
+/// configuration like any [`insert`](ChronoMind::insert) — wrong
+/// dimensions are [`Error::InvalidDimensions`] — and an existing id is
+/// replaced. Import stops at the first bad line, reported with its line
+/// number; memories on earlier lines stay inserted.
+#[instrument(skip(store, reader))]
+pub fn import_ndjson(store: &ChronoMind, reader: impl BufRead) -> Result<usize> {
+    let count = import_lines(store, reader, false, |_| {})?;
+    info!(memories = count, "ndjson imported");
+    Ok(count)
+}
+
+/// [`import_ndjson`] from the file at `path`, streamed a line at a time so
+/// memory use stays bounded however large the file, calling `progress`
+/// with the running count every [`IMPORT_PROGRESS_INTERVAL`] inserts.
+///
+/// With `skip_errors` set, a line that is not a valid memory — malformed
+/// JSON, or a memory the store rejects as invalid — is logged and
+/// skipped instead of ending the import. I/O errors and store-level
+/// failures such as [`Error::ReadOnly`] stop the import either way.
+#[instrument(skip(store, progress))]
+pub fn import_ndjson_file(
+    store: &ChronoMind,
+    path: &Path,
+    skip_errors: bool,
+    progress: impl FnMut(usize),
+) -> Result<usize> {
+    let reader = BufReader::new(File::open(path)?);
+    let count = import_lines(store, reader, skip_errors, progress)?;
+    info!(memories = count, ?path, "ndjson file imported");
+    Ok(count)
+}
+
+/// How many inserts [`import_ndjson_file`] makes between progress calls.
+pub const IMPORT_PROGRESS_INTERVAL: usize = 10_000;
+
+fn import_lines(
+    store: &ChronoMind,
+    reader: impl BufRead,
+    skip_errors: bool,
+    mut progress: impl FnMut(usize),
+) -> Result<usize> {
+    let mut count = 0usize;
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let inserted = serde_json::from_str::<Memory>(&line)
+            .map_err(|e| Error::Serialization(format!("line {}: {e}", number + 1)))
+            .and_then(|memory| store.insert(memory));
+        match inserted {
+            Ok(()) => {}
+            Err(
+                e @ (Error::Serialization(_)
+                | Error::InvalidDimensions { .. }
+                | Error::InvalidVector(_)
+                | Error::InvalidImportance(_)
+                | Error::InvalidArgument(_)),
+            ) if skip_errors => {
+                warn!(line = number + 1, error = %e, "skipping invalid ndjson line");
+                continue;
+            }
+            Err(e) => return Err(e),
+        }
+        count += 1;
+        if count % IMPORT_PROGRESS_INTERVAL == 0 {
+            progress(count);
+        }
+    }
+    Ok(count)
+}
+
+/// Decode a body written under format `version`, upgrading it to the
+/// current layout.
+///
+/// bincode is not self-describing, so every change to a persisted struct
+/// bumps [`FORMAT_VERSION`] and adds an arm here that decodes the old
+/// layout with a frozen copy of its types and converts it forward.
+fn migrate(version: u8, encoded: &[u8]) -> Result<SnapshotBody> {
+    match version {
+        FORMAT_VERSION => Ok(bincode::deserialize(encoded)?),
+        2 => {
+            let body: v2::SnapshotBody = bincode::deserialize(encoded)?;
+            info!(from = version, to = FORMAT_VERSION, "migrating snapshot");
+            Ok(body.into())
+        }
+        _ => Err(Error::InvalidSnapshot(format!(
+            "no migration from format version {version}"
+        ))),
+    }
+}
+
+/// Format version 2 (0.2.x), frozen. Do not edit: these types define how
+/// old files decode.
+mod v2 {
+    use std::time::SystemTime;
+
+    use serde::Deserialize;
+
+    use crate::config::{Config, IndexParams, Precision, ScoreBands};
+    use crate::types::{Memory, MemoryAttributes, Vector};
+
+    #[derive(Deserialize)]
+    pub(super) struct SnapshotBody {
+        config: Config2,
+        memories: Vec<Memory2>,
+    }
+
+    #[derive(Deserialize)]
+    struct Memory2 {
+        vector: Vector2,
+        attributes: MemoryAttributes2,
+    }
+
+    #[derive(Deserialize)]
+    struct Vector2 {
+        id: String,
+        data: Vec<f32>,
+    }
+
+    #[derive(Deserialize)]
+    struct MemoryAttributes2 {
+        timestamp: SystemTime,
+        importance: f32,
+        context: String,
+        decay_rate: f32,
+        relationships: Vec<String>,
+        access_count: u32,
+        last_access: SystemTime,
+    }
+
+    impl From<Memory2> for Memory {
+        fn from(m: Memory2) -> Self {
+            let a = m.attributes;
+            Memory::new(
+                Vector::new(m.vector.id, m.vector.data),
+                MemoryAttributes {
+                    timestamp: a.timestamp,
+                    importance: a.importance,
+                    context: a.context,
+                    decay_rate: a.decay_rate,
+                    relationships: a.relationships,
+                    relationship_strengths: Vec::new(),
+                    access_count: a.access_count,
+                    last_access: a.last_access,
+                    metadata: None,
+                    pinned: false,
+                },
+            )
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Config2 {
+        dimensions: usize,
+        max_memories: usize,
+        base_decay_rate: f32,
+        temporal_weight: f32,
+        similarity_threshold: f32,
+        max_relationships: usize,
+        index: IndexParams2,
+    }
+
+    #[derive(Deserialize)]
+    struct IndexParams2 {
+        max_connections: usize,
+        ef_construction: usize,
+        ef_search: usize,
+    }
+
+    impl From<SnapshotBody> for super::SnapshotBody {
+        fn from(body: SnapshotBody) -> Self {
+            let c = body.config;
+            Self {
+                config: Config {
+                    dimensions: c.dimensions,
+                    max_memories: c.max_memories,
+                    base_decay_rate: c.base_decay_rate,
+                    temporal_weight: c.temporal_weight,
+                    similarity_threshold: c.similarity_threshold,
+                    max_relationships: c.max_relationships,
+                    consolidation_neighbors: Config::default().consolidation_neighbors,
+                    consolidation_relinks: Config::default().consolidation_relinks,
+                    index: IndexParams {
+                        max_connections: c.index.max_connections,
+                        ef_construction: c.index.ef_construction,
+                        ef_search: c.index.ef_search,
+                        ..IndexParams::default()
+                    },
+                    score_bands: ScoreBands::default(),
+                    precision: Precision::default(),
+                    brute_force_threshold: Config::default().brute_force_threshold,
+                    rerank_overfetch: Config::default().rerank_overfetch,
+                },
+                memories: body.memories.into_iter().map(Memory::from).collect(),
+            }
+        }
+    }
+}
@@ -0,0 +1,525 @@
+//! Vector quantization: compact codes for stored vectors.
+//!
+//! **Scalar (int8).** [`QuantizedVector`] maps each vector onto 256 evenly
+//! spaced levels between its own minimum and maximum component:
+//! `x ≈ offset + scale * code`. That cuts a vector to a quarter of its
+//! `f32` size at a reconstruction error of at most `scale / 2` per
+//! component, which typical embeddings tolerate with little change to
+//! their neighbor ordering. Used by
+//! [`Precision::Int8`](crate::Precision::Int8) stores, and usable
+//! standalone.
+//!
+//! **Product.** [`ProductQuantizer`] splits vectors into `m` subspaces and
+//! learns a k-means codebook for each, so a vector becomes `m` centroid
+//! indices — typically a few dozen bytes regardless of dimensionality.
+//! Queries are compared against codes through a per-query
+//! [`DistanceTable`] (asymmetric distance computation), never decoding.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Number of quantization steps above `offset` (`u8::MAX` levels + 1).
+const STEPS: f32 = u8::MAX as f32;
+
+/// A vector quantized to one byte per component.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantizedVector {
+    /// Width of one quantization step.
+    pub scale: f32,
+    /// Value of code `0` (the vector's minimum component).
+    pub offset: f32,
+    /// One code per component.
+    pub codes: Vec<u8>,
+}
+
+impl QuantizedVector {
+    /// Quantize `v` over its own value range. Components are expected to
+    /// be finite, as the store guarantees.
+    pub fn quantize(v: &[f32]) -> Self {
+        if v.is_empty() {
+            return Self {
+                scale: 0.0,
+                offset: 0.0,
+                codes: Vec::new(),
+            };
+        }
+        let (min, max) = v
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &x| {
+                (lo.min(x), hi.max(x))
+            });
+        let scale = (max - min) / STEPS;
+        let codes = if scale > 0.0 {
+            v.iter()
+                .map(|&x| ((x - min) / scale).round().clamp(0.0, STEPS) as u8)
+                .collect()
+        } else {
+            vec![0; v.len()]
+        };
+        Self {
+            scale,
+            offset: min,
+            codes,
+        }
+    }
+
+    /// Reconstruct the approximate `f32` vector.
+    pub fn dequantize(&self) -> Vec<f32> {
+        self.codes.iter().map(|&c| self.value(c)).collect()
+    }
+
+    /// Number of components.
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    /// Whether the vector has no components.
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    fn value(&self, code: u8) -> f32 {
+        self.offset + self.scale * f32::from(code)
+    }
+}
+
+/// Cosine distance between an `f32` query and a quantized vector, computed
+/// from the codes without materializing the reconstruction.
+///
+/// Agrees with [`CosineDistance`](crate::CosineDistance) on the
+/// dequantized vector up to rounding, including its conventions: mismatched
+/// lengths and zero vectors yield the maximum distance, `2.0`.
+pub fn asymmetric_distance(query: &[f32], q: &QuantizedVector) -> f32 {
+    if query.len() != q.codes.len() {
+        return 2.0;
+    }
+    let mut dot = 0.0f32;
+    let mut norm_query = 0.0f32;
+    let mut norm_stored = 0.0f32;
+    for (&x, &code) in query.iter().zip(&q.codes) {
+        let y = q.value(code);
+        dot += x * y;
+        norm_query += x * x;
+        norm_stored += y * y;
+    }
+    let denominator = (norm_query * norm_stored).sqrt();
+    if denominator == 0.0 {
+        return 2.0;
+    }
+    (1.0 - dot / denominator).clamp(0.0, 2.0)
+}
+
+/// Lloyd iterations per subspace codebook.
+const KMEANS_ITERATIONS: usize = 25;
+
+/// Seed for k-means++ initialization, so training is reproducible.
+const KMEANS_SEED: u64 = 0x9E37_79B9;
+
+/// A trained product quantizer.
+///
+/// Distances are squared Euclidean, the metric PQ codebooks are trained
+/// for; on unit-normalized vectors they rank identically to cosine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductQuantizer {
+    dimensions: usize,
+    subspaces: usize,
+    centroids_per_subspace: usize,
+    /// `subspaces * centroids_per_subspace` centroids of
+    /// `dimensions / subspaces` components each, flattened.
+    codebooks: Vec<f32>,
+}
+
+impl ProductQuantizer {
+    /// Learn codebooks for `m` subspaces of `2^nbits` centroids each.
+    ///
+    /// The dimensionality must be divisible by `m` ([`Error::Config`]
+    /// otherwise), `nbits` must be within `1..=8` (codes are bytes), and
+    /// there must be at least `2^nbits` training vectors, all of one
+    /// dimensionality.
+    pub fn train(vectors: &[Vec<f32>], m: usize, nbits: usize) -> Result<Self> {
+        if !(1..=8).contains(&nbits) {
+            return Err(Error::InvalidArgument(format!(
+                "nbits {nbits} must be within 1..=8"
+            )));
+        }
+        let k = 1usize << nbits;
+        if vectors.len() < k {
+            return Err(Error::InvalidArgument(format!(
+                "{k} centroids need at least {k} training vectors, got {}",
+                vectors.len()
+            )));
+        }
+        let dimensions = vectors[0].len();
+        if let Some(bad) = vectors.iter().find(|v| v.len() != dimensions) {
+            return Err(Error::InvalidDimensions {
+                got: bad.len(),
+                expected: dimensions,
+            });
+        }
+        if m == 0 || dimensions % m != 0 {
+            return Err(Error::Config(format!(
+                "dimension {dimensions} is not divisible into {m} subspaces"
+            )));
+        }
+
+        let sub = dimensions / m;
+        let mut rng = StdRng::seed_from_u64(KMEANS_SEED);
+        let mut codebooks = Vec::with_capacity(m * k * sub);
+        for s in 0..m {
+            let slices: Vec<&[f32]> = vectors.iter().map(|v| &v[s * sub..(s + 1) * sub]).collect();
+            codebooks.extend(kmeans(&slices, k, &mut rng));
+        }
+        Ok(Self {
+            dimensions,
+            subspaces: m,
+            centroids_per_subspace: k,
+            codebooks,
+        })
+    }
+
+    /// Dimensionality of the vectors this quantizer encodes.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Number of subspaces, and so of code bytes per vector.
+    pub fn subspaces(&self) -> usize {
+        self.subspaces
+    }
+
+    /// Encode a vector as one centroid index per subspace.
+    pub fn encode(&self, v: &[f32]) -> Result<Vec<u8>> {
+        self.check(v)?;
+        let sub = self.subspace_len();
+        Ok((0..self.subspaces)
+            .map(|s| nearest(&v[s * sub..(s + 1) * sub], self.codebook(s)) as u8)
+            .collect())
+    }
+
+    /// Reconstruct the approximate vector from its codes. Codes this
+    /// quantizer could not have produced are [`Error::InvalidArgument`].
+    pub fn decode(&self, codes: &[u8]) -> Result<Vec<f32>> {
+        check_codes(codes, self.subspaces, self.centroids_per_subspace)?;
+        let sub = self.subspace_len();
+        Ok(codes
+            .iter()
+            .enumerate()
+            .flat_map(|(s, &c)| {
+                let start = usize::from(c) * sub;
+                &self.codebook(s)[start..start + sub]
+            })
+            .copied()
+            .collect())
+    }
+
+    /// Precompute the squared distance from each subspace of `query` to
+    /// every centroid, for scoring any number of codes against it.
+    pub fn distance_table(&self, query: &[f32]) -> Result<DistanceTable> {
+        self.check(query)?;
+        let sub = self.subspace_len();
+        let mut table = Vec::with_capacity(self.subspaces * self.centroids_per_subspace);
+        for s in 0..self.subspaces {
+            let q = &query[s * sub..(s + 1) * sub];
+            table.extend(
+                self.codebook(s)
+                    .chunks_exact(sub)
+                    .map(|centroid| squared_l2(q, centroid)),
+            );
+        }
+        Ok(DistanceTable {
+            subspaces: self.subspaces,
+            centroids_per_subspace: self.centroids_per_subspace,
+            table,
+        })
+    }
+
+    fn subspace_len(&self) -> usize {
+        self.dimensions / self.subspaces
+    }
+
+    fn codebook(&self, subspace: usize) -> &[f32] {
+        let len = self.centroids_per_subspace * self.subspace_len();
+        &self.codebooks[subspace * len..(subspace + 1) * len]
+    }
+
+    fn check(&self, v: &[f32]) -> Result<()> {
+        if v.len() != self.dimensions {
+            return Err(Error::InvalidDimensions {
+                got: v.len(),
+                expected: self.dimensions,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Per-query lookup table from
+/// [`ProductQuantizer::distance_table`].
+#[derive(Debug, Clone)]
+pub struct DistanceTable {
+    subspaces: usize,
+    centroids_per_subspace: usize,
+    table: Vec<f32>,
+}
+
+impl DistanceTable {
+    /// Approximate squared Euclidean distance from the query to the vector
+    /// encoded as `codes`: one table lookup per subspace. Codes the
+    /// table's quantizer could not have produced are
+    /// [`Error::InvalidArgument`].
+    pub fn distance(&self, codes: &[u8]) -> Result<f32> {
+        check_codes(codes, self.subspaces, self.centroids_per_subspace)?;
+        Ok(codes
+            .iter()
+            .enumerate()
+            .map(|(s, &c)| self.table[s * self.centroids_per_subspace + usize::from(c)])
+            .sum())
+    }
+}
+
+/// Reject `codes` unless there is one per subspace and each names a
+/// centroid.
+fn check_codes(codes: &[u8], subspaces: usize, centroids: usize) -> Result<()> {
+    if codes.len() != subspaces {
+        return Err(Error::InvalidArgument(format!(
+            "expected {subspaces} codes, got {}",
+            codes.len()
+        )));
+    }
+    if let Some(&c) = codes.iter().find(|&&c| usize::from(c) >= centroids) {
+        return Err(Error::InvalidArgument(format!(
+            "code {c} is past the last of {centroids} centroids"
+        )));
+    }
+    Ok(())
+}
+
+fn squared_l2(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Index of the centroid in `codebook` (flattened, `point.len()` wide)
+/// nearest to `point`.
+fn nearest(point: &[f32], codebook: &[f32]) -> usize {
+    codebook
+        .chunks_exact(point.len())
+        .map(|c| squared_l2(point, c))
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+        .expect("codebook is non-empty")
+}
+
+/// k-means++ seeding followed by Lloyd iterations; returns `k` centroids,
+/// flattened. Callers guarantee `points.len() >= k`.
+fn kmeans(points: &[&[f32]], k: usize, rng: &mut StdRng) -> Vec<f32> {
+    let dim = points[0].len();
+    let mut centroids: Vec<f32> = Vec::with_capacity(k * dim);
+    centroids.extend_from_slice(points[rng.gen_range(0..points.len())]);
+    let mut nearest_sq: Vec<f32> = points
+        .iter()
+        .map(|p| squared_l2(p, &centroids[..dim]))
+        .collect();
+    while centroids.len() < k * dim {
+        let total: f32 = nearest_sq.iter().sum();
+        let pick = if total > 0.0 {
+            let mut target = rng.gen_range(0.0..total);
+            nearest_sq
+                .iter()
+                .position(|&d| {
+                    target -= d;
+                    target < 0.0
+                })
+                .unwrap_or(points.len() - 1)
+        } else {
+            // Fewer distinct points than centroids: duplicates are harmless.
+            rng.gen_range(0..points.len())
+        };
+        let start = centroids.len();
+        centroids.extend_from_slice(points[pick]);
+        for (d, p) in nearest_sq.iter_mut().zip(points) {
+            *d = d.min(squared_l2(p, &centroids[start..]));
+        }
+    }
+
+    let mut assignment = vec![0usize; points.len()];
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut changed = false;
+        for (a, p) in assignment.iter_mut().zip(points) {
+            let best = nearest(p, &centroids);
+            changed |= *a != best;
+            *a = best;
+        }
+        let mut sums = vec![0.0f32; k * dim];
+        let mut counts = vec![0usize; k];
+        for (&a, p) in assignment.iter().zip(points) {
+            counts[a] += 1;
+            for (s, x) in sums[a * dim..(a + 1) * dim].iter_mut().zip(p.iter()) {
+                *s += x;
+            }
+        }
+        for (c, &count) in counts.iter().enumerate() {
+            // An empty cluster keeps its previous centroid.
+            if count > 0 {
+                for (centroid, sum) in centroids[c * dim..(c + 1) * dim]
+                    .iter_mut()
+                    .zip(&sums[c * dim..(c + 1) * dim])
+                {
+                    *centroid = sum / count as f32;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    centroids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{CosineDistance, DistanceMetric};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn unit_vectors(n: usize, dim: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| {
+                let v: Vec<f32> = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+                let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+                v.into_iter().map(|x| x / norm).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reconstruction_error_is_within_half_a_step() {
+        for v in unit_vectors(50, 384, 1) {
+            let q = QuantizedVector::quantize(&v);
+            let restored = q.dequantize();
+            for (x, y) in v.iter().zip(&restored) {
+                assert!((x - y).abs() <= q.scale / 2.0 + 1e-6);
+            }
+        }
+        let constant = QuantizedVector::quantize(&[0.5; 8]);
+        assert_eq!(constant.dequantize(), vec![0.5; 8]);
+    }
+
+    #[test]
+    fn neighbor_ordering_is_preserved() {
+        let dim = 384;
+        let corpus = unit_vectors(1000, dim, 2);
+        let quantized: Vec<QuantizedVector> = corpus
+            .iter()
+            .map(|v| QuantizedVector::quantize(v))
+            .collect();
+        let metric = CosineDistance::new();
+
+        let mut hits = 0;
+        let queries = unit_vectors(20, dim, 3);
+        for query in &queries {
+            let top10 = |distance: &dyn Fn(usize) -> f32| {
+                let mut ids: Vec<usize> = (0..corpus.len()).collect();
+                ids.sort_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+                ids.truncate(10);
+                ids
+            };
+            let exact = top10(&|i| metric.distance(query, &corpus[i]));
+            let approx = top10(&|i| asymmetric_distance(query, &quantized[i]));
+            hits += approx.iter().filter(|i| exact.contains(i)).count();
+        }
+        let recall = hits as f32 / (10 * queries.len()) as f32;
+        assert!(recall >= 0.9, "recall@10 {recall}");
+    }
+
+    #[test]
+    fn asymmetric_distance_matches_dequantized_cosine() {
+        let metric = CosineDistance::new();
+        let vectors = unit_vectors(10, 64, 4);
+        for pair in vectors.windows(2) {
+            let q = QuantizedVector::quantize(&pair[1]);
+            let expected = metric.distance(&pair[0], &q.dequantize());
+            assert!((asymmetric_distance(&pair[0], &q) - expected).abs() < 1e-4);
+        }
+        assert_eq!(
+            asymmetric_distance(&[1.0], &QuantizedVector::quantize(&[1.0, 2.0])),
+            2.0
+        );
+    }
+
+    #[test]
+    fn product_quantizer_keeps_recall_on_clustered_data() {
+        let dim = 32;
+        let mut rng = StdRng::seed_from_u64(5);
+        let centers = unit_vectors(20, dim, 6);
+        let data: Vec<Vec<f32>> = (0..2000)
+            .map(|i| {
+                centers[i % centers.len()]
+                    .iter()
+                    .map(|c| c + rng.gen_range(-0.15..0.15))
+                    .collect()
+            })
+            .collect();
+        let pq = ProductQuantizer::train(&data, 8, 8).unwrap();
+        let codes: Vec<Vec<u8>> = data.iter().map(|v| pq.encode(v).unwrap()).collect();
+        assert_eq!(codes[0].len(), 8);
+        assert_eq!(pq.decode(&codes[0]).unwrap().len(), dim);
+
+        let mut hits = 0;
+        let queries = &data[..50];
+        for query in queries {
+            let table = pq.distance_table(query).unwrap();
+            let top10 = |distance: &dyn Fn(usize) -> f32| {
+                let mut ids: Vec<usize> = (0..data.len()).collect();
+                ids.sort_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+                ids.truncate(10);
+                ids
+            };
+            let exact = top10(&|i| squared_l2(query, &data[i]));
+            let approx = top10(&|i| table.distance(&codes[i]).unwrap());
+            hits += approx.iter().filter(|i| exact.contains(i)).count();
+        }
+        let recall = hits as f32 / (10 * queries.len()) as f32;
+        assert!(recall >= 0.5, "recall@10 {recall}");
+    }
+
+    #[test]
+    fn product_quantizer_validates_its_parameters() {
+        let data = unit_vectors(300, 10, 7);
+        assert!(matches!(
+            ProductQuantizer::train(&data, 3, 8),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(
+            ProductQuantizer::train(&data, 5, 9),
+            Err(Error::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            ProductQuantizer::train(&data[..100], 5, 8),
+            Err(Error::InvalidArgument(_))
+        ));
+        let pq = ProductQuantizer::train(&data, 5, 4).unwrap();
+        assert!(matches!(
+            pq.encode(&[0.0; 4]),
+            Err(Error::InvalidDimensions { .. })
+        ));
+
+        // 5 subspaces of 16 centroids: codes must be 5 bytes below 16.
+        let table = pq.distance_table(&data[0]).unwrap();
+        let codes = pq.encode(&data[0]).unwrap();
+        assert_eq!(pq.decode(&codes).unwrap().len(), 10);
+        assert!(table.distance(&codes).is_ok());
+        for foreign in [&codes[..4], &[0, 1, 2, 3, 4, 5][..], &[0, 0, 16, 0, 0][..]] {
+            assert!(matches!(pq.decode(foreign), Err(Error::InvalidArgument(_))));
+            assert!(matches!(
+                table.distance(foreign),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+    }
+}
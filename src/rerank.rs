@@ -0,0 +1,159 @@
+//! Pluggable ranking of search candidates.
+//!
+//! [`ChronoMind::search`](crate::ChronoMind::search) draws geometric
+//! candidates from the index and hands them to a [`Reranker`], which
+//! scores and orders them. The default, [`TemporalReranker`], is the
+//! crate's temporal relevance formula; install another with
+//! [`ChronoMind::with_reranker`](crate::ChronoMind::with_reranker) to rank
+//! by pure recency, pure similarity, importance, or any blend.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::config::Config;
+use crate::index::top_k_by;
+use crate::store::StoredMemory;
+
+const SECONDS_PER_HOUR: f32 = 3600.0;
+
+/// A memory the index returned for a query, as seen by a [`Reranker`].
+///
+/// Candidates can be reordered, rescored, and dropped, but not created:
+/// each one refers back to the stored memory it was drawn from.
+#[derive(Clone)]
+pub struct Candidate {
+    pub(crate) record: Arc<StoredMemory>,
+    /// Geometric distance to the query under the store's metric.
+    pub distance: f32,
+    /// When the memory was created.
+    pub timestamp: SystemTime,
+    /// The memory's decay rate per hour; zero means the configured
+    /// [`base_decay_rate`](Config::base_decay_rate).
+    pub decay_rate: f32,
+    /// The memory's importance at query time.
+    pub importance: f32,
+    /// Ranking score, lower is better. Zero on the way in; the reranker
+    /// sets it, and search results report it.
+    pub score: f32,
+}
+
+impl Candidate {
+    /// The memory's id.
+    pub fn id(&self) -> &str {
+        &self.record.id
+    }
+
+    /// The memory's context label.
+    pub fn context(&self) -> &str {
+        &self.record.context
+    }
+}
+
+impl std::fmt::Debug for Candidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Candidate")
+            .field("id", &self.id())
+            .field("distance", &self.distance)
+            .field("timestamp", &self.timestamp)
+            .field("score", &self.score)
+            .finish()
+    }
+}
+
+/// Scores and orders the candidates of one search.
+///
+/// `rerank` returns at least the best `k` candidates, best first, each
+/// with its [`score`](Candidate::score) set; the store keeps that order,
+/// drops repeated ids after their first occurrence, and cuts the list to
+/// the requested number of results. `k` already allows for those repeats,
+/// so a reranker can select its best `k` without sorting the whole pool;
+/// returning every candidate in order is correct too, only slower.
+/// Candidates left out are simply not returned.
+///
+/// Called once per search, possibly from many threads at once.
+pub trait Reranker: Send + Sync {
+    /// Score `candidates` for a search made at `now` under `config`, and
+    /// return at least the best `k` of them in order.
+    fn rerank(
+        &self,
+        candidates: Vec<Candidate>,
+        k: usize,
+        now: SystemTime,
+        config: &Config,
+    ) -> Vec<Candidate>;
+}
+
+/// The default [`Reranker`]: geometric distance blended with age by
+/// [`temporal_weight`](Config::temporal_weight), as documented on
+/// [`ChronoMind::search`](crate::ChronoMind::search). Equal scores are
+/// ordered by id, so ties come back the same way on every run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemporalReranker;
+
+impl TemporalReranker {
+    /// The temporal relevance score of `candidate`, for custom rerankers
+    /// that adjust it rather than replace it.
+    pub fn score(candidate: &Candidate, now: SystemTime, config: &Config) -> f32 {
+        combined_score(
+            config,
+            candidate.distance,
+            candidate.timestamp,
+            candidate.decay_rate,
+            now,
+        )
+    }
+}
+
+impl Reranker for TemporalReranker {
+    /// Selects the best `k` before sorting: `O(n + k log k)` over a pool
+    /// of `n`, not a sort of every candidate.
+    fn rerank(
+        &self,
+        mut candidates: Vec<Candidate>,
+        k: usize,
+        now: SystemTime,
+        config: &Config,
+    ) -> Vec<Candidate> {
+        for candidate in &mut candidates {
+            candidate.score = Self::score(candidate, now, config);
+        }
+        top_k_by(candidates, k, |a, b| {
+            a.score.total_cmp(&b.score).then_with(|| a.id().cmp(b.id()))
+        })
+    }
+}
+
+/// The single temporal scoring formula. See
+/// [`ChronoMind::search`](crate::ChronoMind::search).
+pub(crate) fn combined_score(
+    config: &Config,
+    distance: f32,
+    timestamp: SystemTime,
+    decay_rate: f32,
+    now: SystemTime,
+) -> f32 {
+    let w = config.temporal_weight;
+    (1.0 - w) * (distance / 2.0) + w * temporal_penalty(config, timestamp, decay_rate, now)
+}
+
+/// The age term of [`combined_score`]: `1 - exp(-r * t)`, from 0 (fresh)
+/// towards 1 (ancient).
+pub(crate) fn temporal_penalty(
+    config: &Config,
+    timestamp: SystemTime,
+    decay_rate: f32,
+    now: SystemTime,
+) -> f32 {
+    let age_hours = now
+        .duration_since(timestamp)
+        .unwrap_or_default()
+        .as_secs_f32()
+        / SECONDS_PER_HOUR;
+    let rate = if decay_rate > 0.0 {
+        decay_rate
+    } else {
+        config.base_decay_rate
+    };
+    let temporal_relevance = (-rate * age_hours).exp(); // 1 = fresh, 0 = ancient
+    1.0 - temporal_relevance
+}
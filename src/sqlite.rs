@@ -0,0 +1,275 @@
+//! SQLite-backed persistence: one row per memory in a database file that
+//! other tools can query.
+//!
+//! A [`SqliteStore`] serves searches from an in-memory [`ChronoMind`] and
+//! mirrors its inserts and removals into SQLite as they happen. Each memory
+//! is a row of the `memories` table:
+//!
+//! | column       | type               | contents                                         |
+//! |--------------|--------------------|--------------------------------------------------|
+//! | `id`         | `TEXT PRIMARY KEY` | the memory's id                                  |
+//! | `context`    | `TEXT`             | context label                                    |
+//! | `importance` | `REAL`             | importance when the row was written              |
+//! | `timestamp`  | `INTEGER`          | creation time, nanoseconds since the Unix epoch  |
+//! | `vector`     | `BLOB`             | components as little-endian `f32`                |
+//! | `attributes` | `TEXT`             | all of the memory's attributes, as JSON          |
+//!
+//! The `attributes` column is what [`open`](SqliteStore::open) reads back;
+//! the columns beside it repeat the attributes worth filtering and
+//! aggregating on in SQL. The configuration is stored as JSON in the
+//! `meta` table.
+//!
+//! Only available with the `sqlite` feature.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::UNIX_EPOCH;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tracing::{info, instrument};
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::store::ChronoMind;
+use crate::types::{Memory, MemoryAttributes, Vector};
+
+const SCHEMA: &str = "
+    CREATE TABLE meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE memories (
+        id TEXT PRIMARY KEY,
+        context TEXT NOT NULL,
+        importance REAL NOT NULL,
+        timestamp INTEGER NOT NULL,
+        vector BLOB NOT NULL,
+        attributes TEXT NOT NULL
+    );
+    CREATE INDEX memories_by_context ON memories (context);
+";
+
+/// A [`ChronoMind`] store whose inserts and removals are written through
+/// to a SQLite database.
+///
+/// Writes go through [`insert`](Self::insert) and
+/// [`remove`](Self::remove), which apply the change and write its row
+/// under one connection mutex, so writers are serialized. Reads go through
+/// [`store`](Self::store) and stay wait-free.
+///
+/// Like [`WalStore`](crate::WalStore), only inserts and removals are
+/// written as they happen. Decay, access recording, consolidation, and
+/// writes made directly on [`store`](Self::store) reach the database at
+/// the next [`checkpoint`](Self::checkpoint).
+pub struct SqliteStore {
+    store: ChronoMind,
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Create a new, empty store in the database at `path`, replacing any
+    /// ChronoMind tables already there.
+    #[instrument]
+    pub fn create(path: &Path, config: Config) -> Result<Self> {
+        let store = ChronoMind::new(config)?;
+        let mut connection = Connection::open(path)?;
+        let tx = connection.transaction()?;
+        tx.execute_batch("DROP TABLE IF EXISTS meta; DROP TABLE IF EXISTS memories;")?;
+        tx.execute_batch(SCHEMA)?;
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES ('config', ?1)",
+            [serde_json::to_string(store.config())?],
+        )?;
+        tx.commit()?;
+        Ok(Self {
+            store,
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Open the database at `path` and load every memory in it.
+    #[instrument]
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path)?;
+        let config: Option<String> = connection
+            .query_row("SELECT value FROM meta WHERE key = 'config'", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        let config = config.ok_or_else(|| {
+            Error::InvalidSnapshot(format!(
+                "{} has no ChronoMind configuration",
+                path.display()
+            ))
+        })?;
+        let store = ChronoMind::new(serde_json::from_str(&config)?)?;
+
+        load_rows(&connection, &store)?;
+
+        info!(memories = store.len(), ?path, "sqlite store opened");
+        Ok(Self {
+            store,
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// The underlying store, for reads. Writes made through it are not
+    /// written to the database until the next
+    /// [`checkpoint`](Self::checkpoint).
+    pub fn store(&self) -> &ChronoMind {
+        &self.store
+    }
+
+    /// Consume the wrapper, returning the store.
+    pub fn into_inner(self) -> ChronoMind {
+        self.store
+    }
+
+    /// Insert or replace a memory (see [`ChronoMind::insert`]) and write
+    /// its row.
+    pub fn insert(&self, memory: Memory) -> Result<()> {
+        let id = memory.vector.id.clone();
+        let connection = self.lock();
+        self.store.insert(memory)?;
+        // Write what the store holds, which has the replaced memory's
+        // links merged in.
+        if let Some(stored) = self.store.get(&id) {
+            upsert(&connection, &stored)?;
+        }
+        Ok(())
+    }
+
+    /// Remove a memory by id (see [`ChronoMind::remove`]), deleting its
+    /// row if it was present.
+    pub fn remove(&self, id: &str) -> Result<Option<Memory>> {
+        let connection = self.lock();
+        let removed = self.store.remove(id);
+        if removed.is_some() {
+            connection.execute("DELETE FROM memories WHERE id = ?1", [id])?;
+        }
+        Ok(removed)
+    }
+
+    /// Rewrite every row from the store's current state, in one
+    /// transaction: decayed importance, access counts, consolidation, and
+    /// direct writes all reach the database.
+    #[instrument(skip(self))]
+    pub fn checkpoint(&self) -> Result<()> {
+        let mut connection = self.lock();
+        let tx = connection.transaction()?;
+        tx.execute("DELETE FROM memories", [])?;
+        let memories = self.store.snapshot();
+        for memory in &memories {
+            upsert(&tx, memory)?;
+        }
+        tx.execute(
+            "UPDATE meta SET value = ?1 WHERE key = 'config'",
+            [serde_json::to_string(self.store.config())?],
+        )?;
+        tx.commit()?;
+        info!(memories = memories.len(), "sqlite store checkpointed");
+        Ok(())
+    }
+
+    /// Ids of every memory in the database, in id order.
+    pub fn ids(&self) -> Result<Vec<String>> {
+        self.query_ids("SELECT id FROM memories ORDER BY id", [])
+    }
+
+    /// Ids of the memories in the database labeled `context`, in id order.
+    pub fn ids_in_context(&self, context: &str) -> Result<Vec<String>> {
+        self.query_ids(
+            "SELECT id FROM memories WHERE context = ?1 ORDER BY id",
+            [context],
+        )
+    }
+
+    /// Number of memories in the database per context label.
+    pub fn context_counts(&self) -> Result<HashMap<String, usize>> {
+        let connection = self.lock();
+        let mut statement =
+            connection.prepare("SELECT context, COUNT(*) FROM memories GROUP BY context")?;
+        let counts = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(counts)
+    }
+
+    fn query_ids(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<String>> {
+        let connection = self.lock();
+        let mut statement = connection.prepare(sql)?;
+        let ids = statement
+            .query_map(params, |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(ids)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        // A panic mid-write leaves SQLite's own transaction state intact;
+        // the connection is still usable.
+        self.connection.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Insert every row of the `memories` table into `store`, in id order.
+fn load_rows(connection: &Connection, store: &ChronoMind) -> Result<()> {
+    let mut statement =
+        connection.prepare("SELECT id, vector, attributes FROM memories ORDER BY id")?;
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let id: String = row.get(0)?;
+        let vector: Vec<u8> = row.get(1)?;
+        let attributes: String = row.get(2)?;
+        let data = decode_vector(&vector).ok_or_else(|| {
+            Error::InvalidSnapshot(format!("memory {id} has a truncated vector blob"))
+        })?;
+        let attributes: MemoryAttributes = serde_json::from_str(&attributes)?;
+        store.insert(Memory::new(Vector::new(id, data), attributes))?;
+    }
+    Ok(())
+}
+
+/// Insert or replace `memory`'s row.
+fn upsert(connection: &Connection, memory: &Memory) -> Result<()> {
+    let a = &memory.attributes;
+    let timestamp = a
+        .timestamp
+        .duration_since(UNIX_EPOCH)
+        .map(|d| i64::try_from(d.as_nanos()).unwrap_or(i64::MAX))
+        .unwrap_or(0);
+    let vector: Vec<u8> = memory
+        .vector
+        .data
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect();
+    connection.execute(
+        "INSERT OR REPLACE INTO memories (id, context, importance, timestamp, vector, attributes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            memory.vector.id,
+            a.context,
+            f64::from(a.importance),
+            timestamp,
+            vector,
+            serde_json::to_string(a)?,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Components from a little-endian `f32` blob; `None` if its length is
+/// not a whole number of components.
+fn decode_vector(blob: &[u8]) -> Option<Vec<f32>> {
+    if blob.len() % 4 != 0 {
+        return None;
+    }
+    Some(
+        blob.chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().expect("4 bytes")))
+            .collect(),
+    )
+}
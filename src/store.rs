@@ -14,30 +14,104 @@
 //!   inside immutable records, so decay sweeps and access bumps are
 //!   CAS loops, not lock acquisitions.
 //!
-//! `consolidate` takes `&mut self` deliberately: it is an `O(n²)`
+//! `consolidate` takes `&mut self` deliberately: it is a whole-store
 //! maintenance pass whose pairwise logic is not meaningfully concurrent,
 //! and exclusive access keeps it trivially correct. That is an API choice,
 //! not a hidden lock.
 
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use half::f16;
+use papaya::{Compute, Operation};
 use tracing::{debug, instrument};
 
-use crate::config::Config;
+use crate::config::{Config, IndexParams, Precision};
 use crate::error::{Error, Result};
-use crate::index::{LockFreeHnsw, VectorIndex};
+use crate::index::{top_k_by, LayerStats, LockFreeHnsw, SearchStats, VectorIndex};
 use crate::metric::{CosineDistance, DistanceMetric};
-use crate::types::{ContextSummary, Memory, MemoryAttributes, MemoryStats, Vector};
+use crate::metrics::MetricsRegistry;
+use crate::pca::{Pca, PcaMetric};
+use crate::quantization::QuantizedVector;
+use crate::rerank::{self, Candidate, Reranker, TemporalReranker};
+use crate::types::{
+    ContextSummary, Memory, MemoryAttributes, MemoryStats, MultiQueryMode, SearchHit, SearchMode,
+    Vector,
+};
+use crate::whitening::{WhitenedMetric, Whitening};
 
 const SECONDS_PER_HOUR: f32 = 3600.0;
 
-/// Oversampling factor: the index is asked for `max(ef_search, k * OVERSAMPLE)`
-/// candidates before temporal reranking, so that recency can promote
-/// memories that are geometrically close but not in the top `k`.
-const OVERSAMPLE: usize = 3;
+/// Candidate pool factor for [`ChronoMind::search_diverse`]: MMR needs
+/// room to skip redundant near-duplicates.
+const MMR_POOL: usize = 5;
+
+/// Candidate pool factor for [`ChronoMind::search_with_negatives`]:
+/// penalized candidates need replacements from further down the list.
+const NEGATIVE_POOL: usize = 5;
+
+/// Candidate pool factor for [`ChronoMind::search_grouped_by_context`]:
+/// the pool is shared by every context, so it needs room for several.
+const GROUP_POOL: usize = 10;
+
+/// Buckets in [`MemoryStats::importance_histogram`].
+const IMPORTANCE_BUCKETS: usize = 10;
+
+/// A record ranked by [`ChronoMind::search`], with its score components.
+struct Ranked {
+    stored: Arc<StoredMemory>,
+    distance: f32,
+    temporal_penalty: f32,
+    score: f32,
+}
+
+/// A record's vector, kept at the store's [`Precision`].
+#[derive(Clone)]
+enum RecordVector {
+    F32(Vec<f32>),
+    F16(Vec<f16>),
+    Int8(QuantizedVector),
+}
+
+impl RecordVector {
+    fn new(data: &[f32], precision: Precision) -> Self {
+        match precision {
+            Precision::F32 => Self::F32(data.to_vec()),
+            Precision::F16 => Self::F16(data.iter().map(|&x| f16::from_f32(x)).collect()),
+            Precision::Int8 => Self::Int8(QuantizedVector::quantize(data)),
+        }
+    }
+
+    /// The vector as `f32`: borrowed at full precision, reconstructed
+    /// otherwise.
+    fn to_f32(&self) -> Cow<'_, [f32]> {
+        match self {
+            Self::F32(v) => Cow::Borrowed(v),
+            Self::F16(v) => Cow::Owned(v.iter().map(|x| x.to_f32()).collect()),
+            Self::Int8(q) => Cow::Owned(q.dequantize()),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::F32(v) => v.len(),
+            Self::F16(v) => v.len(),
+            Self::Int8(q) => q.len(),
+        }
+    }
+
+    /// Heap bytes held for the components.
+    fn heap_bytes(&self) -> usize {
+        match self {
+            Self::F32(v) => v.capacity() * std::mem::size_of::<f32>(),
+            Self::F16(v) => v.capacity() * std::mem::size_of::<f16>(),
+            Self::Int8(q) => q.codes.capacity(),
+        }
+    }
+}
 
 /// An immutable memory record with atomic mutable state.
 ///
@@ -45,14 +119,16 @@ const OVERSAMPLE: usize = 3;
 /// creation (re-inserting an id replaces the whole record). Importance and
 /// access tracking are atomics so they can be updated lock-free from any
 /// thread.
-struct StoredMemory {
+pub(crate) struct StoredMemory {
     handle: u32,
-    id: String,
-    data: Vec<f32>,
+    pub(crate) id: String,
+    data: RecordVector,
     timestamp: SystemTime,
-    context: String,
+    pub(crate) context: String,
     decay_rate: f32,
     relationships: Box<[String]>,
+    /// Strength of each link, parallel to `relationships`.
+    strengths: Box<[f32]>,
     importance_bits: AtomicU32,
     access_count: AtomicU32,
     last_access_nanos: AtomicU64,
@@ -60,6 +136,8 @@ struct StoredMemory {
     /// decays only the interval since this point, so periodic sweeps
     /// compose into the documented curve instead of compounding.
     decayed_through_nanos: AtomicU64,
+    metadata: Option<serde_json::Value>,
+    pinned: AtomicBool,
 }
 
 fn nanos_since_epoch(t: SystemTime) -> u64 {
@@ -68,27 +146,65 @@ fn nanos_since_epoch(t: SystemTime) -> u64 {
         .unwrap_or(0)
 }
 
+/// Ascending score, ties broken by id so equal scores rank the same way
+/// on every run rather than in map iteration order.
+fn by_score_then_id(
+    a: &(&Arc<StoredMemory>, f32),
+    b: &(&Arc<StoredMemory>, f32),
+) -> std::cmp::Ordering {
+    a.1.total_cmp(&b.1).then_with(|| a.0.id.cmp(&b.0.id))
+}
+
+/// The best `k` of `scored` (lower is better), one per id, materialized
+/// best first, equal scores by id. A reinsert publishes its new index
+/// node before tombstoning the old one, so a search racing that window
+/// can see both versions of one external id; only the better-scoring one
+/// is kept.
+fn best_k<'a>(
+    scored: impl Iterator<Item = (&'a Arc<StoredMemory>, f32)>,
+    k: usize,
+) -> Vec<(Memory, f32)> {
+    let mut best: HashMap<&str, (&Arc<StoredMemory>, f32)> = HashMap::new();
+    for (stored, score) in scored {
+        best.entry(stored.id.as_str())
+            .and_modify(|entry| {
+                if score < entry.1 {
+                    *entry = (stored, score);
+                }
+            })
+            .or_insert((stored, score));
+    }
+    top_k_by(best.into_values().collect(), k, by_score_then_id)
+        .into_iter()
+        .map(|(stored, score)| (stored.materialize(), score))
+        .collect()
+}
+
 impl StoredMemory {
-    fn from_memory(memory: &Memory, handle: u32) -> Arc<Self> {
+    fn from_memory(memory: &Memory, handle: u32, precision: Precision) -> Arc<Self> {
         let a = &memory.attributes;
         Arc::new(Self {
             handle,
             id: memory.vector.id.clone(),
-            data: memory.vector.data.clone(),
+            data: RecordVector::new(&memory.vector.data, precision),
             timestamp: a.timestamp,
             context: a.context.clone(),
             decay_rate: a.decay_rate,
             relationships: a.relationships.clone().into_boxed_slice(),
+            strengths: a.weighted_relationships().map(|(_, s)| s).collect(),
             importance_bits: AtomicU32::new(a.importance.to_bits()),
             access_count: AtomicU32::new(a.access_count),
             last_access_nanos: AtomicU64::new(nanos_since_epoch(a.last_access)),
             decayed_through_nanos: AtomicU64::new(nanos_since_epoch(a.last_access)),
+            metadata: a.metadata.clone(),
+            pinned: AtomicBool::new(a.pinned),
         })
     }
 
-    /// Rebuild with different relationships/importance, preserving identity
-    /// and access state (used by consolidation).
-    fn rebuilt(&self, relationships: Vec<String>, importance: f32) -> Arc<Self> {
+    /// Rebuild with different weighted relationships/importance,
+    /// preserving identity and access state (used by consolidation).
+    fn rebuilt(&self, links: Vec<(String, f32)>, importance: f32) -> Arc<Self> {
+        let (relationships, strengths): (Vec<String>, Vec<f32>) = links.into_iter().unzip();
         Arc::new(Self {
             handle: self.handle,
             id: self.id.clone(),
@@ -97,25 +213,87 @@ impl StoredMemory {
             context: self.context.clone(),
             decay_rate: self.decay_rate,
             relationships: relationships.into_boxed_slice(),
+            strengths: strengths.into_boxed_slice(),
             importance_bits: AtomicU32::new(importance.to_bits()),
             access_count: AtomicU32::new(self.access_count.load(Ordering::Acquire)),
             last_access_nanos: AtomicU64::new(self.last_access_nanos.load(Ordering::Acquire)),
             decayed_through_nanos: AtomicU64::new(
                 self.decayed_through_nanos.load(Ordering::Acquire),
             ),
+            metadata: self.metadata.clone(),
+            pinned: AtomicBool::new(self.pinned.load(Ordering::Acquire)),
+        })
+    }
+
+    /// Copy the record under a new context label, preserving all other
+    /// state (used by context renames).
+    fn relabeled(&self, context: &str) -> Arc<Self> {
+        Arc::new(Self {
+            handle: self.handle,
+            id: self.id.clone(),
+            data: self.data.clone(),
+            timestamp: self.timestamp,
+            context: context.to_owned(),
+            decay_rate: self.decay_rate,
+            relationships: self.relationships.clone(),
+            strengths: self.strengths.clone(),
+            importance_bits: AtomicU32::new(self.importance_bits.load(Ordering::Acquire)),
+            access_count: AtomicU32::new(self.access_count.load(Ordering::Acquire)),
+            last_access_nanos: AtomicU64::new(self.last_access_nanos.load(Ordering::Acquire)),
+            decayed_through_nanos: AtomicU64::new(
+                self.decayed_through_nanos.load(Ordering::Acquire),
+            ),
+            metadata: self.metadata.clone(),
+            pinned: AtomicBool::new(self.pinned.load(Ordering::Acquire)),
+        })
+    }
+
+    /// Copy the record under a new index handle, preserving all state
+    /// (used when the index is rebuilt).
+    fn relocated(&self, handle: u32) -> Arc<Self> {
+        Arc::new(Self {
+            handle,
+            id: self.id.clone(),
+            data: self.data.clone(),
+            timestamp: self.timestamp,
+            context: self.context.clone(),
+            decay_rate: self.decay_rate,
+            relationships: self.relationships.clone(),
+            strengths: self.strengths.clone(),
+            importance_bits: AtomicU32::new(self.importance_bits.load(Ordering::Acquire)),
+            access_count: AtomicU32::new(self.access_count.load(Ordering::Acquire)),
+            last_access_nanos: AtomicU64::new(self.last_access_nanos.load(Ordering::Acquire)),
+            decayed_through_nanos: AtomicU64::new(
+                self.decayed_through_nanos.load(Ordering::Acquire),
+            ),
+            metadata: self.metadata.clone(),
+            pinned: AtomicBool::new(self.pinned.load(Ordering::Acquire)),
         })
     }
 
+    /// Each link with its strength, in recorded order.
+    fn links(&self) -> impl Iterator<Item = (&String, f32)> {
+        self.relationships
+            .iter()
+            .zip(self.strengths.iter().copied())
+    }
+
     fn importance(&self) -> f32 {
         f32::from_bits(self.importance_bits.load(Ordering::Acquire))
     }
 
-    /// Multiply importance by `factor`, clamped to `[0, 1]`. Lock-free CAS
-    /// loop; safe against concurrent decays and consolidations.
+    /// Multiply importance by `factor`, clamped to `[0, 1]`.
     fn scale_importance(&self, factor: f32) {
+        self.update_importance(|importance| importance * factor);
+    }
+
+    /// Replace importance with `f(importance)`, clamped to `[0, 1]`.
+    /// Lock-free CAS loop; safe against concurrent decays, reinforcements,
+    /// and consolidations.
+    fn update_importance(&self, f: impl Fn(f32) -> f32) {
         let mut current = self.importance_bits.load(Ordering::Acquire);
         loop {
-            let updated = (f32::from_bits(current) * factor).clamp(0.0, 1.0);
+            let updated = f(f32::from_bits(current)).clamp(0.0, 1.0);
             match self.importance_bits.compare_exchange_weak(
                 current,
                 updated.to_bits(),
@@ -142,7 +320,7 @@ impl StoredMemory {
         Memory {
             vector: Vector {
                 id: self.id.clone(),
-                data: self.data.clone(),
+                data: self.data.to_f32().into_owned(),
             },
             attributes: MemoryAttributes {
                 timestamp: self.timestamp,
@@ -150,8 +328,11 @@ impl StoredMemory {
                 context: self.context.clone(),
                 decay_rate: self.decay_rate,
                 relationships: self.relationships.to_vec(),
+                relationship_strengths: self.strengths.to_vec(),
                 access_count: self.access_count.load(Ordering::Acquire),
                 last_access: self.last_access(),
+                metadata: self.metadata.clone(),
+                pinned: self.pinned.load(Ordering::Acquire),
             },
         }
     }
@@ -162,10 +343,29 @@ impl StoredMemory {
 /// See the [crate-level documentation](crate) for an end-to-end example.
 pub struct ChronoMind {
     config: Config,
+    /// The metric every distance goes through: `base_metric`, or
+    /// `base_metric` over a whitening transform when one is fitted.
     metric: Arc<dyn DistanceMetric>,
+    base_metric: Arc<dyn DistanceMetric>,
+    whitening: Option<Arc<Whitening>>,
+    reranker: Arc<dyn Reranker>,
+    /// Where search latencies are recorded, when set.
+    metrics: Option<Arc<MetricsRegistry>>,
     index: LockFreeHnsw,
     by_id: papaya::HashMap<String, Arc<StoredMemory>>,
     by_handle: papaya::HashMap<u32, Arc<StoredMemory>>,
+    /// Per-context mean vectors, filled lazily by
+    /// [`context_centroid`](ChronoMind::context_centroid) and dropped
+    /// whenever a context gains or loses a member.
+    centroids: papaya::HashMap<String, Arc<[f32]>>,
+    /// Bumped by every centroid invalidation, so a centroid computed
+    /// across one is never cached.
+    centroid_generation: AtomicU64,
+    /// Ids written since the last
+    /// [`backup_incremental`](crate::backup::backup_incremental) — inserted,
+    /// removed, or with changed attributes.
+    changed: papaya::HashSet<String>,
+    read_only: bool,
 }
 
 impl std::fmt::Debug for ChronoMind {
@@ -174,6 +374,7 @@ impl std::fmt::Debug for ChronoMind {
             .field("config", &self.config)
             .field("metric", &self.metric.name())
             .field("memories", &self.len())
+            .field("read_only", &self.read_only)
             .finish()
     }
 }
@@ -190,18 +391,175 @@ impl ChronoMind {
         let index = LockFreeHnsw::new(config.index.clone(), Arc::clone(&metric));
         Ok(Self {
             config,
+            base_metric: Arc::clone(&metric),
             metric,
+            whitening: None,
+            reranker: Arc::new(TemporalReranker),
+            metrics: None,
             index,
             by_id: papaya::HashMap::new(),
             by_handle: papaya::HashMap::new(),
+            centroids: papaya::HashMap::new(),
+            centroid_generation: AtomicU64::new(0),
+            changed: papaya::HashSet::new(),
+            read_only: false,
         })
     }
 
+    /// Create a store that indexes and searches vectors reduced by `pca`,
+    /// compared by cosine distance.
+    ///
+    /// Stored vectors are projected once as they are indexed and queries
+    /// once per search, so the graph holds `pca.output_dimensions()`
+    /// components per memory. Memories and queries keep the full
+    /// [`dimensions`](Config::dimensions), which must equal
+    /// `pca.input_dimensions()`, and [`get`](Self::get) returns the
+    /// original embeddings. Fit the projection on a representative sample
+    /// with [`Pca::fit`]. Like a custom metric, the projection is not part
+    /// of snapshots: reopen them with a [`PcaMetric`].
+    pub fn with_pca(config: Config, pca: Pca) -> Result<Self> {
+        if pca.input_dimensions() != config.dimensions {
+            return Err(Error::InvalidDimensions {
+                got: pca.input_dimensions(),
+                expected: config.dimensions,
+            });
+        }
+        let metric = PcaMetric::new(Arc::new(CosineDistance::new()), Arc::new(pca));
+        Self::with_metric(config, Arc::new(metric))
+    }
+
+    /// Rank search results with `reranker` instead of the default
+    /// [`TemporalReranker`].
+    ///
+    /// The reranker orders the candidates of [`search`](Self::search) and
+    /// every variant built on it ([`search_with_stats`](Self::search_with_stats),
+    /// [`search_detailed`](Self::search_detailed),
+    /// [`search_iter`](Self::search_iter),
+    /// [`search_banded`](Self::search_banded),
+    /// [`search_grouped_by_context`](Self::search_grouped_by_context), and
+    /// mean-mode [`search_multi`](Self::search_multi)). Searches that fold
+    /// their own terms into the score — diverse, negative-example,
+    /// min-distance multi-query, and in-context search — keep the temporal
+    /// formula. Like the metric, the reranker is not recorded in
+    /// snapshots.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = reranker;
+        self
+    }
+
+    /// Record the latency of every search in `registry`, as the `search`
+    /// operation.
+    ///
+    /// Covers [`search`](Self::search) and every variant built on it, plus
+    /// [`search_where`](Self::search_where); [`search_in_context`](Self::search_in_context)
+    /// records under its context label, the others under the empty
+    /// context. Share one registry between stores, or with the
+    /// application's own operations, to serve them from one scrape
+    /// endpoint via [`MetricsRegistry::render_prometheus`].
+    pub fn with_metrics(mut self, registry: Arc<MetricsRegistry>) -> Self {
+        self.metrics = Some(registry);
+        self
+    }
+
+    fn record_search(&self, context: &str, started: Instant) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_operation_duration("search", context, started.elapsed());
+        }
+    }
+
+    /// Freeze the store: every later mutation is refused.
+    ///
+    /// Meant for serving a fixed, pre-built index (typically straight out
+    /// of [`load_snapshot`](crate::load_snapshot)). The switch is one-way.
+    /// Fallible mutations return [`Error::ReadOnly`]; the infallible ones
+    /// leave the data untouched — [`remove`](Self::remove) returns `None`,
+    /// [`remove_many`](Self::remove_many) removes nothing,
+    /// [`apply_decay`](Self::apply_decay) does nothing,
+    /// [`consolidate`](Self::consolidate) absorbs nothing, and
+    /// [`access`](Self::access) reads without recording the access. Use
+    /// [`try_remove`](Self::try_remove),
+    /// [`try_apply_decay`](Self::try_apply_decay), and
+    /// [`try_consolidate`](Self::try_consolidate) to get the error instead.
+    pub fn into_read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Whether the store was frozen with [`into_read_only`](Self::into_read_only).
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+        Ok(())
+    }
+
     /// The store's configuration.
     pub fn config(&self) -> &Config {
         &self.config
     }
 
+    /// Replace the configuration of a live store.
+    ///
+    /// Scoring and maintenance tunables — decay rate, temporal weight,
+    /// similarity threshold, score bands — take effect on the next call
+    /// that reads them. A change to [`index`](Config::index) rebuilds the
+    /// index over every stored memory. Limits and precision apply to
+    /// later writes: records already stored keep their precision and
+    /// relationship lists.
+    ///
+    /// Changing [`dimensions`](Config::dimensions) would invalidate every
+    /// stored vector and is rejected, as is lowering
+    /// [`max_memories`](Config::max_memories) below the current count.
+    /// The store is unchanged on error.
+    #[instrument(skip(self, config))]
+    pub fn update_config(&mut self, config: Config) -> Result<()> {
+        config.validate()?;
+        if config.dimensions != self.config.dimensions {
+            return Err(Error::Config(format!(
+                "dimensions cannot change on a live store ({} -> {})",
+                self.config.dimensions, config.dimensions
+            )));
+        }
+        if config.max_memories < self.len() {
+            return Err(Error::Config(format!(
+                "max_memories {} is below the {} memories already stored",
+                config.max_memories,
+                self.len()
+            )));
+        }
+        let previous = std::mem::replace(&mut self.config, config);
+        if previous.index != self.config.index {
+            if let Err(e) = self.rebuild_index(Arc::clone(&self.metric)) {
+                self.config = previous;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuild the index with new construction and search parameters,
+    /// keeping every stored memory.
+    ///
+    /// Unlike [`update_config`](Self::update_config), this rebuilds even
+    /// when `params` equal the current ones, which also drops the
+    /// tombstones left by removals. The store is unchanged on error.
+    #[instrument(skip(self, params))]
+    pub fn reindex(&mut self, params: IndexParams) -> Result<()> {
+        let mut config = self.config.clone();
+        config.index = params;
+        config.validate()?;
+        let previous = std::mem::replace(&mut self.config, config);
+        if let Err(e) = self.rebuild_index(Arc::clone(&self.metric)) {
+            self.config = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+
     /// Number of stored memories.
     pub fn len(&self) -> usize {
         self.by_id.pin().len()
@@ -224,45 +582,100 @@ impl ChronoMind {
     /// concurrently inserting threads.
     #[instrument(skip(self, memory), fields(id = %memory.vector.id))]
     pub fn insert(&self, mut memory: Memory) -> Result<()> {
+        self.ensure_writable()?;
         memory.validate(&self.config)?;
 
         let map = self.by_id.pin();
-        if let Some(existing) = map.get(&memory.vector.id) {
-            let mut links: Vec<String> = existing.relationships.to_vec();
-            let known: HashSet<&String> = links.iter().collect();
-            let new_links: Vec<String> = memory
-                .attributes
-                .relationships
-                .iter()
-                .filter(|l| !known.contains(l))
-                .cloned()
+        let existing = map.get(&memory.vector.id);
+        if let Some(existing) = existing {
+            // Existing links stay in place, restated ones take their new
+            // strength, and new ones follow.
+            let incoming: HashMap<&str, f32> = memory.attributes.weighted_relationships().collect();
+            let mut links: Vec<(String, f32)> = existing
+                .links()
+                .map(|(l, s)| (l.clone(), incoming.get(l.as_str()).copied().unwrap_or(s)))
                 .collect();
-            links.extend(new_links);
+            let known: HashSet<&str> = existing.relationships.iter().map(String::as_str).collect();
+            links.extend(
+                memory
+                    .attributes
+                    .weighted_relationships()
+                    .filter(|(l, _)| !known.contains(l))
+                    .map(|(l, s)| (l.to_owned(), s)),
+            );
             links.truncate(self.config.max_relationships);
-            memory.attributes.relationships = links;
+            let (relationships, strengths) = links.into_iter().unzip();
+            memory.attributes.relationships = relationships;
+            memory.attributes.relationship_strengths = strengths;
         } else if map.len() >= self.config.max_memories {
             return Err(Error::CapacityExceeded(self.config.max_memories));
         } else {
-            memory
-                .attributes
-                .relationships
+            let a = &mut memory.attributes;
+            a.relationships.truncate(self.config.max_relationships);
+            a.relationship_strengths
                 .truncate(self.config.max_relationships);
         }
 
+        // Same vector: only the attributes changed, so keep the index node.
+        // The record is stored at `precision`, so compare it with the
+        // incoming vector quantized the same way.
+        if let Some(existing) = existing {
+            let incoming = RecordVector::new(&memory.vector.data, self.config.precision);
+            if existing.data.to_f32() == incoming.to_f32()
+                && self.replace_attributes(existing, &memory)
+            {
+                self.mark_changed(&memory.vector.id);
+                return Ok(());
+            }
+        }
+
         let handle = self
             .index
             .insert(&memory.vector.data)
             .ok_or(Error::IndexFull(crate::index::arena_capacity()))?;
-        let stored = StoredMemory::from_memory(&memory, handle);
+        let stored = StoredMemory::from_memory(&memory, handle, self.config.precision);
         self.by_handle.pin().insert(handle, Arc::clone(&stored));
         if let Some(replaced) = map.insert(memory.vector.id.clone(), stored) {
             // The old record loses both its index node and its handle entry.
             self.index.remove(replaced.handle);
             self.by_handle.pin().remove(&replaced.handle);
+            self.invalidate_centroid(&replaced.context);
         }
+        self.invalidate_centroid(&memory.attributes.context);
+        self.mark_changed(&memory.vector.id);
         Ok(())
     }
 
+    /// Swap in `memory` as a new record on `existing`'s index node. Fails
+    /// (returning `false`, leaving nothing of its own behind) if a
+    /// concurrent write replaced or removed `existing` first; that write
+    /// also retired its node, so the caller must index the vector afresh.
+    fn replace_attributes(&self, existing: &Arc<StoredMemory>, memory: &Memory) -> bool {
+        let handle = existing.handle;
+        let stored = StoredMemory::from_memory(memory, handle, self.config.precision);
+        self.by_handle.pin().insert(handle, Arc::clone(&stored));
+        let aborted = matches!(
+            self.by_id
+                .pin()
+                .compute(memory.vector.id.clone(), |entry| match entry {
+                    Some((_, current)) if Arc::ptr_eq(current, existing) => {
+                        Operation::Insert(Arc::clone(&stored))
+                    }
+                    _ => Operation::Abort(()),
+                }),
+            Compute::Aborted(())
+        );
+        if aborted {
+            // The handle is retired or contended; the caller's fresh insert
+            // replaces whatever now holds the id.
+            self.by_handle.pin().remove(&handle);
+            return false;
+        }
+        self.invalidate_centroid(&existing.context);
+        self.invalidate_centroid(&memory.attributes.context);
+        true
+    }
+
     /// Get a memory by id.
     pub fn get(&self, id: &str) -> Option<Memory> {
         self.by_id.pin().get(id).map(|s| s.materialize())
@@ -274,18 +687,74 @@ impl ChronoMind {
     pub fn access(&self, id: &str) -> Option<Memory> {
         let map = self.by_id.pin();
         let stored = map.get(id)?;
-        stored.record_access();
+        if !self.read_only {
+            stored.record_access();
+            self.mark_changed(id);
+        }
         Some(stored.materialize())
     }
 
-    /// Remove a memory by id, returning it if present.
+    /// [`access`](Self::access) every listed memory under one map guard,
+    /// returning results by position: `None` for ids not in the store. An
+    /// id listed twice is accessed twice.
+    pub fn access_many(&self, ids: &[&str]) -> Vec<Option<Memory>> {
+        let map = self.by_id.pin();
+        ids.iter()
+            .map(|&id| {
+                let stored = map.get(id)?;
+                if !self.read_only {
+                    stored.record_access();
+                    self.mark_changed(id);
+                }
+                Some(stored.materialize())
+            })
+            .collect()
+    }
+
+    /// Remove a memory by id, returning it if present. Always `None` on a
+    /// [read-only](Self::into_read_only) store.
     pub fn remove(&self, id: &str) -> Option<Memory> {
+        self.try_remove(id).ok().flatten()
+    }
+
+    /// [`remove`](Self::remove), failing with [`Error::ReadOnly`] on a
+    /// read-only store instead of removing nothing.
+    pub fn try_remove(&self, id: &str) -> Result<Option<Memory>> {
+        self.ensure_writable()?;
         let removed = self.by_id.pin().remove(id).map(|s| {
             self.index.remove(s.handle);
             self.by_handle.pin().remove(&s.handle);
+            self.invalidate_centroid(&s.context);
+            self.mark_changed(id);
             s.materialize()
         });
-        removed
+        Ok(removed)
+    }
+
+    /// Remove every listed memory, returning how many were present. Ids
+    /// not in the store are skipped. Always `0` on a
+    /// [read-only](Self::into_read_only) store.
+    pub fn remove_many(&self, ids: &[&str]) -> usize {
+        ids.iter().filter(|id| self.remove(id).is_some()).count()
+    }
+
+    /// Remove every memory and start over with an empty index.
+    ///
+    /// Unlike removing memories one by one, this also releases the index's
+    /// nodes and tombstones. Configuration, metric, and any fitted
+    /// [whitening](Self::fit_whitening) are kept. Requires `&mut self` for
+    /// the same reason [`consolidate`](Self::consolidate) does.
+    #[instrument(skip(self))]
+    pub fn clear(&mut self) -> Result<()> {
+        self.ensure_writable()?;
+        for id in self.by_id.pin().keys() {
+            self.mark_changed(id);
+        }
+        self.index = LockFreeHnsw::new(self.config.index.clone(), Arc::clone(&self.metric));
+        self.by_id = papaya::HashMap::new();
+        self.by_handle = papaya::HashMap::new();
+        self.centroids = papaya::HashMap::new();
+        Ok(())
     }
 
     /// A point-in-time snapshot of all stored memories, in arbitrary order.
@@ -309,163 +778,971 @@ impl ChronoMind {
     ///
     /// Lower scores are better. Results are `(memory, score)` pairs sorted
     /// ascending. This formula is the single definition of temporal
-    /// relevance used everywhere in the crate.
+    /// relevance used everywhere in the crate; it is the default
+    /// [`Reranker`], which [`with_reranker`](Self::with_reranker) replaces.
     ///
-    /// The index supplies `max(ef_search, 3 * k)` geometric candidates and
-    /// the formula reranks those; a memory outside that candidate pool
-    /// cannot be returned, however fresh. Raise
-    /// [`ef_search`](crate::IndexParams::ef_search) to widen the pool.
+    /// The index supplies `max(ef_search, rerank_overfetch * k)` geometric
+    /// candidates and the formula reranks those; a memory outside that
+    /// candidate pool cannot be returned, however fresh. Raise
+    /// [`ef_search`](crate::IndexParams::ef_search) or
+    /// [`rerank_overfetch`](Config::rerank_overfetch) to widen the pool. A
+    /// store smaller than
+    /// [`brute_force_threshold`](Config::brute_force_threshold) skips the
+    /// index and scores every memory.
     ///
     /// Wait-free with respect to concurrent writers.
     #[instrument(skip(self, query))]
     pub fn search(&self, query: &[f32], k: usize) -> Result<Vec<(Memory, f32)>> {
-        self.validate_query(query)?;
-        let ef = self.config.index.ef_search.max(k * OVERSAMPLE);
-        let now = SystemTime::now();
-        let handles = self.by_handle.pin();
+        self.search_with_stats(query, k).map(|(results, _)| results)
+    }
 
-        let mut scored: Vec<(Memory, f32)> = self
-            .index
-            .search(query, ef)
+    /// [`search`](Self::search), also reporting how much of the graph the
+    /// index traversal touched.
+    ///
+    /// With [`max_nodes_visited`](crate::IndexParams::max_nodes_visited)
+    /// set, the traversal stops once that many nodes have been evaluated
+    /// and the results are the best found so far;
+    /// [`SearchStats::budget_exhausted`] tells the caller when that
+    /// happened. The budget applies to plain `search` too — this method
+    /// only makes it observable.
+    #[instrument(skip(self, query))]
+    pub fn search_with_stats(
+        &self,
+        query: &[f32],
+        k: usize,
+    ) -> Result<(Vec<(Memory, f32)>, SearchStats)> {
+        let (ranked, stats) = self.ranked(query, k, None, SearchMode::Approximate)?;
+        let results = ranked
             .into_iter()
-            .filter_map(|(handle, distance)| {
-                let stored = handles.get(&handle)?;
-                let score = self.combined_score(distance, stored.timestamp, stored.decay_rate, now);
-                Some((stored.materialize(), score))
-            })
+            .map(|r| (r.stored.materialize(), r.score))
             .collect();
+        Ok((results, stats))
+    }
 
-        scored.sort_by(|(_, a), (_, b)| a.total_cmp(b));
-        // A reinsert publishes its new index node before tombstoning the
-        // old one; a search racing that window can see both versions of
-        // one external id. Keep only the best-scoring instance.
-        let mut seen: HashSet<String> = HashSet::with_capacity(scored.len());
-        scored.retain(|(m, _)| seen.insert(m.vector.id.clone()));
-        scored.truncate(k);
-        Ok(scored)
+    /// [`search`](Self::search), with each result broken down into the
+    /// geometric distance and age penalty its score combines — for
+    /// debugging why a memory ranked where it did.
+    #[instrument(skip(self, query))]
+    pub fn search_detailed(&self, query: &[f32], k: usize) -> Result<Vec<SearchHit>> {
+        let (ranked, _) = self.ranked(query, k, None, SearchMode::Approximate)?;
+        Ok(ranked
+            .into_iter()
+            .map(|r| SearchHit {
+                memory: r.stored.materialize(),
+                distance: r.distance,
+                temporal_penalty: r.temporal_penalty,
+                score: r.score,
+            })
+            .collect())
     }
 
-    /// Like [`search`](Self::search), restricted to one context label.
+    /// [`search`](Self::search) with an explicit choice between the
+    /// index and an exhaustive scan; see [`SearchMode`].
     ///
-    /// Context filtering scans the context's members exactly rather than
-    /// going through the index, so sparse contexts never come back short.
+    /// [`SearchMode::Exact`] compares the query with every memory, so the
+    /// true geometric neighbors are always among the candidates the
+    /// temporal ranking sees. Use it where a missed neighbor costs more
+    /// than a linear scan.
     #[instrument(skip(self, query))]
-    pub fn search_in_context(
+    pub fn search_with_mode(
         &self,
-        context: &str,
         query: &[f32],
         k: usize,
+        mode: SearchMode,
     ) -> Result<Vec<(Memory, f32)>> {
+        let (ranked, _) = self.ranked(query, k, None, mode)?;
+        Ok(ranked
+            .into_iter()
+            .map(|r| (r.stored.materialize(), r.score))
+            .collect())
+    }
+
+    /// [`search`](Self::search) as an iterator that builds each result's
+    /// [`Memory`] only when it is reached.
+    ///
+    /// Ranking is done up front, exactly as in `search`; what is deferred
+    /// is copying out each memory's vector and attributes, which dominates
+    /// the cost of a large `k`. A consumer that stops early — a page of
+    /// results, a first match passing some filter — pays only for what it
+    /// took. The iterator holds the ranked records rather than borrowing
+    /// the store, so a memory removed after the call is still yielded.
+    #[instrument(skip(self, query))]
+    pub fn search_iter(
+        &self,
+        query: &[f32],
+        k: usize,
+    ) -> Result<impl ExactSizeIterator<Item = (Memory, f32)>> {
+        let (ranked, _) = self.ranked(query, k, None, SearchMode::Approximate)?;
+        Ok(ranked
+            .into_iter()
+            .map(|r| (r.stored.materialize(), r.score)))
+    }
+
+    /// The `k` best records for `query` with their scores, best first,
+    /// among the handles `filter` accepts, with candidates gathered per
+    /// `mode`: the shared core of [`search`](Self::search) and its
+    /// variants. Records one search sample; see [`rank`](Self::rank) for
+    /// a pass that is part of a larger search.
+    fn ranked(
+        &self,
+        query: &[f32],
+        k: usize,
+        filter: Option<&(dyn Fn(u32) -> bool + Sync)>,
+        mode: SearchMode,
+    ) -> Result<(Vec<Ranked>, SearchStats)> {
+        let started = Instant::now();
+        let ranked = self.rank(query, k, filter, mode)?;
+        self.record_search("", started);
+        Ok(ranked)
+    }
+
+    /// [`ranked`](Self::ranked) without recording a search sample.
+    fn rank(
+        &self,
+        query: &[f32],
+        k: usize,
+        filter: Option<&(dyn Fn(u32) -> bool + Sync)>,
+        mode: SearchMode,
+    ) -> Result<(Vec<Ranked>, SearchStats)> {
         self.validate_query(query)?;
+        let ef = self
+            .config
+            .index
+            .ef_search
+            .max(k * self.config.rerank_overfetch);
         let now = SystemTime::now();
+        let handles = self.by_handle.pin();
 
-        let mut scored: Vec<(Memory, f32)> = self
-            .by_id
-            .pin()
-            .values()
-            .filter(|s| s.context == context)
-            .map(|s| {
-                let distance = self.metric.distance(&s.data, query);
-                let score = self.combined_score(distance, s.timestamp, s.decay_rate, now);
-                (s.materialize(), score)
+        let (candidates, stats) = self.candidates(query, ef, filter, mode);
+        let candidates: Vec<Candidate> = candidates
+            .into_iter()
+            .filter_map(|(handle, distance)| {
+                let stored = handles.get(&handle)?;
+                Some(Candidate {
+                    record: Arc::clone(stored),
+                    distance,
+                    timestamp: stored.timestamp,
+                    decay_rate: stored.decay_rate,
+                    importance: stored.importance(),
+                    score: 0.0,
+                })
             })
             .collect();
 
-        scored.sort_by(|(_, a), (_, b)| a.total_cmp(b));
-        scored.truncate(k);
-        Ok(scored)
+        // A reinsert publishes its new index node before tombstoning the
+        // old one; a search racing that window can see both versions of
+        // one external id. Keep only the best-ranked instance, and ask the
+        // reranker for enough that `k` distinct ids survive.
+        let repeats = {
+            let mut ids: HashSet<&str> = HashSet::with_capacity(candidates.len());
+            candidates.iter().filter(|c| !ids.insert(c.id())).count()
+        };
+        let scored = self
+            .reranker
+            .rerank(candidates, k + repeats, now, &self.config);
+        let mut seen: HashSet<&str> = HashSet::with_capacity(scored.len());
+        let mut ranked: Vec<Ranked> = Vec::with_capacity(k.min(scored.len()));
+        for c in &scored {
+            if ranked.len() == k {
+                break;
+            }
+            if seen.insert(c.id()) {
+                ranked.push(Ranked {
+                    stored: Arc::clone(&c.record),
+                    distance: c.distance,
+                    temporal_penalty: self.temporal_penalty(c.timestamp, c.decay_rate, now),
+                    score: c.score,
+                });
+            }
+        }
+        Ok((ranked, stats))
     }
 
-    fn validate_query(&self, query: &[f32]) -> Result<()> {
-        if query.len() != self.config.dimensions {
-            return Err(Error::InvalidDimensions {
-                got: query.len(),
-                expected: self.config.dimensions,
-            });
+    /// Run `sample_queries` through the index and discard the results, to
+    /// pull the graph's upper layers and the neighborhoods they lead to
+    /// into CPU caches before latency-sensitive traffic arrives — typically
+    /// right after [`load_snapshot`](crate::load_snapshot).
+    ///
+    /// A few queries resembling expected traffic are enough; each costs one
+    /// ordinary search. Every query is validated first. A no-op on an empty
+    /// store.
+    #[instrument(skip(self, sample_queries), fields(queries = sample_queries.len()))]
+    pub fn warm_up(&self, sample_queries: &[Vec<f32>]) -> Result<()> {
+        for query in sample_queries {
+            self.validate_query(query)?;
         }
-        if query.iter().any(|x| !x.is_finite()) {
-            return Err(Error::InvalidVector(
-                "query contains NaN or infinite components".into(),
-            ));
+        if self.index.is_empty() {
+            return Ok(());
+        }
+        for query in sample_queries {
+            std::hint::black_box(self.index.search(query, self.config.index.ef_search));
         }
         Ok(())
     }
 
-    /// The single temporal scoring formula. See [`search`](Self::search).
-    fn combined_score(
+    /// Geometric candidates for `query` as `(handle, distance)`: the
+    /// index's best `ef`, or every record when the store is below
+    /// [`brute_force_threshold`](Config::brute_force_threshold) or `mode`
+    /// asks for an exact search.
+    ///
+    /// The exhaustive scan measures against the index's prepared vectors,
+    /// so neither path normalizes a stored vector per query. With a
+    /// `filter`, only the handles it accepts are candidates; the index
+    /// then widens its pool until `ef` pass, and reports no traversal
+    /// statistics.
+    fn candidates(
         &self,
-        distance: f32,
-        timestamp: SystemTime,
-        decay_rate: f32,
-        now: SystemTime,
-    ) -> f32 {
-        let w = self.config.temporal_weight;
-        let age_hours = now
-            .duration_since(timestamp)
-            .unwrap_or_default()
-            .as_secs_f32()
-            / SECONDS_PER_HOUR;
-        let rate = if decay_rate > 0.0 {
-            decay_rate
-        } else {
-            self.config.base_decay_rate
+        query: &[f32],
+        ef: usize,
+        filter: Option<&(dyn Fn(u32) -> bool + Sync)>,
+        mode: SearchMode,
+    ) -> (Vec<(u32, f32)>, SearchStats) {
+        if mode == SearchMode::Approximate && self.len() >= self.config.brute_force_threshold {
+            return match filter {
+                None => self.index.search_with_stats(query, ef),
+                Some(filter) => (
+                    self.index.search_filtered(query, ef, filter),
+                    SearchStats::default(),
+                ),
+            };
+        }
+        let handles: Vec<u32> = self
+            .by_handle
+            .pin()
+            .keys()
+            .copied()
+            .filter(|&h| filter.is_none_or(|f| f(h)))
+            .collect();
+        let candidates = self.index.distances(query, &handles);
+        let stats = SearchStats {
+            nodes_visited: candidates.len(),
+            distance_computations: candidates.len(),
+            exhaustive: true,
+            ..SearchStats::default()
         };
-        let temporal_relevance = (-rate * age_hours).exp(); // 1 = fresh, 0 = ancient
-        (1.0 - w) * (distance / 2.0) + w * (1.0 - temporal_relevance)
+        (candidates, stats)
     }
 
-    /// Decay every memory's importance based on time elapsed while
-    /// unaccessed.
+    /// [`search`](Self::search), with each result labeled by the band its
+    /// score falls into under the configured
+    /// [`score_bands`](crate::Config::score_bands).
+    #[instrument(skip(self, query))]
+    pub fn search_banded(
+        &self,
+        query: &[f32],
+        k: usize,
+    ) -> Result<Vec<(Memory, f32, &'static str)>> {
+        let bands = self.config.score_bands;
+        Ok(self
+            .search(query, k)?
+            .into_iter()
+            .map(|(memory, score)| (memory, score, bands.band(score)))
+            .collect())
+    }
+
+    /// Every memory whose geometric distance to `query` is at most
+    /// `max_distance`, sorted by ascending distance.
     ///
-    /// Each sweep multiplies importance by `exp(-r * h)` where `r` is the
-    /// memory's effective decay rate and `h` is the hours **since the
-    /// later of the last access and the previous sweep** — sweeps apply
-    /// disjoint intervals, so calling this every minute or once a week
-    /// composes into the same documented curve. A per-memory CAS gate
-    /// ensures concurrent sweeps never apply the same interval twice.
-    /// Lock-free throughout; runs concurrently with reads and writes.
-    #[instrument(skip(self))]
-    pub fn apply_decay(&self) {
-        let now_nanos = nanos_since_epoch(SystemTime::now());
-        for stored in self.by_id.pin().values() {
-            let previous_sweep = stored.decayed_through_nanos.load(Ordering::Acquire);
-            let from = previous_sweep.max(stored.last_access_nanos.load(Ordering::Acquire));
-            if now_nanos <= from {
-                continue;
-            }
-            // Claim the interval (from, now]. A concurrent sweep that loses
-            // this CAS skips the memory; the winner applies the decay.
-            if stored
-                .decayed_through_nanos
-                .compare_exchange(
-                    previous_sweep,
-                    now_nanos,
-                    Ordering::AcqRel,
-                    Ordering::Acquire,
-                )
-                .is_err()
-            {
-                continue;
+    /// Results are `(memory, distance)` pairs with raw metric distances —
+    /// no temporal reranking, since "within X" is a geometric question.
+    /// The index candidate pool starts at `ef_search` and doubles until the
+    /// farthest candidate lies beyond `max_distance`, the pool covers every
+    /// live vector, or the pool reaches
+    /// [`max_memories`](Config::max_memories), whichever comes first. Like
+    /// any HNSW query the result is approximate: a qualifying memory the
+    /// graph traversal never reaches is not returned.
+    #[instrument(skip(self, query))]
+    pub fn search_within(&self, query: &[f32], max_distance: f32) -> Result<Vec<(Memory, f32)>> {
+        self.validate_query(query)?;
+        if max_distance.is_nan() {
+            return Err(Error::InvalidArgument(
+                "max_distance must not be NaN".into(),
+            ));
+        }
+        let cap = self.config.max_memories.max(1);
+        let mut ef = self.config.index.ef_search.min(cap);
+        let candidates = loop {
+            let candidates = self.index.search(query, ef);
+            let farthest_within = candidates
+                .last()
+                .is_some_and(|&(_, distance)| distance <= max_distance);
+            if !farthest_within || candidates.len() >= self.index.len() || ef >= cap {
+                break candidates;
             }
-            let hours = (now_nanos - from) as f32 / 1e9 / SECONDS_PER_HOUR;
+            ef = (ef * 2).min(cap);
+        };
+
+        let handles = self.by_handle.pin();
+        let mut seen: HashSet<&str> = HashSet::new();
+        let within: Vec<(Memory, f32)> = candidates
+            .iter()
+            .take_while(|&&(_, distance)| distance <= max_distance)
+            .filter_map(|&(handle, distance)| {
+                let stored = handles.get(&handle)?;
+                // Candidates arrive sorted, so the first instance of an id
+                // racing a reinsert is its closest.
+                seen.insert(&stored.id)
+                    .then(|| (stored.materialize(), distance))
+            })
+            .collect();
+        Ok(within)
+    }
+
+    /// The `k` memories geometrically nearest `query`, each with its
+    /// similarity `1 - distance` clamped to `[0.0, 1.0]`, most similar
+    /// first.
+    ///
+    /// No temporal reranking. The clamped value is a clean similarity only
+    /// under a bounded cosine-style metric such as the default
+    /// [`CosineDistance`], where it is the cosine itself (opposed
+    /// directions clamp to `0.0`); under an unbounded metric like
+    /// Euclidean it is merely monotone in distance and saturates at `0.0`.
+    #[instrument(skip(self, query))]
+    pub fn search_similarity(&self, query: &[f32], k: usize) -> Result<Vec<(Memory, f32)>> {
+        self.validate_query(query)?;
+        let ef = self
+            .config
+            .index
+            .ef_search
+            .max(k * self.config.rerank_overfetch);
+        let handles = self.by_handle.pin();
+        let mut seen: HashSet<&str> = HashSet::new();
+        Ok(self
+            .index
+            .search(query, ef)
+            .into_iter()
+            .filter_map(|(handle, distance)| {
+                let stored = handles.get(&handle)?;
+                seen.insert(&stored.id)
+                    .then(|| (stored.materialize(), (1.0 - distance).clamp(0.0, 1.0)))
+            })
+            .take(k)
+            .collect())
+    }
+
+    /// Diverse search by maximal marginal relevance (MMR).
+    ///
+    /// Draws `max(ef_search, 5 * k)` candidates from the index, then greedily
+    /// picks the candidate maximizing
+    ///
+    /// ```text
+    /// lambda * sim(query, doc) - (1 - lambda) * max sim(doc, selected)
+    /// ```
+    ///
+    /// with `sim` the store metric's [`similarity`](DistanceMetric::similarity).
+    /// `lambda = 1.0` is plain relevance ranking; lower values trade
+    /// relevance for covering more of the candidate space, which keeps
+    /// near-duplicates from crowding out the rest of a RAG context window.
+    ///
+    /// Results are `(memory, score)` pairs in selection order, with the
+    /// score defined as in [`search`](Self::search).
+    #[instrument(skip(self, query))]
+    pub fn search_diverse(
+        &self,
+        query: &[f32],
+        k: usize,
+        lambda: f32,
+    ) -> Result<Vec<(Memory, f32)>> {
+        self.validate_query(query)?;
+        if !(0.0..=1.0).contains(&lambda) {
+            return Err(Error::InvalidArgument(format!(
+                "lambda {lambda} must be within [0.0, 1.0]"
+            )));
+        }
+        let ef = self.config.index.ef_search.max(k * MMR_POOL);
+        let now = SystemTime::now();
+        let handles = self.by_handle.pin();
+
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut pool: Vec<(&Arc<StoredMemory>, f32, f32)> = self
+            .index
+            .search(query, ef)
+            .into_iter()
+            .filter_map(|(handle, distance)| {
+                let stored = handles.get(&handle)?;
+                seen.insert(&stored.id).then(|| {
+                    let relevance = self.metric.similarity(&stored.data.to_f32(), query);
+                    let score =
+                        self.combined_score(distance, stored.timestamp, stored.decay_rate, now);
+                    (stored, relevance, score)
+                })
+            })
+            .collect();
+
+        // Running max similarity of each pool entry to anything selected;
+        // zero until the first pick, so it starts as pure relevance.
+        let mut redundancy = vec![0.0f32; pool.len()];
+        let mut selected: Vec<(Memory, f32)> = Vec::with_capacity(k.min(pool.len()));
+        while selected.len() < k && !pool.is_empty() {
+            let best = pool
+                .iter()
+                .zip(&redundancy)
+                .map(|(&(_, relevance, _), &r)| lambda * relevance - (1.0 - lambda) * r)
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(i, _)| i)
+                .expect("pool is non-empty");
+            let (picked, _, score) = pool.swap_remove(best);
+            redundancy.swap_remove(best);
+            for ((stored, _, _), r) in pool.iter().zip(redundancy.iter_mut()) {
+                let similarity = self
+                    .metric
+                    .similarity(&stored.data.to_f32(), &picked.data.to_f32());
+                *r = if selected.is_empty() {
+                    similarity
+                } else {
+                    r.max(similarity)
+                };
+            }
+            selected.push((picked.materialize(), score));
+        }
+        Ok(selected)
+    }
+
+    /// "Like `query`, but not like `negatives`."
+    ///
+    /// Each candidate's score (as in [`search`](Self::search), lower is
+    /// better) is raised by `penalty` times its highest similarity to any
+    /// negative example; similarity below zero is not penalized. The index
+    /// supplies `max(ef_search, 5 * k)` candidates so penalized ones can be
+    /// displaced by memories further down the geometric ranking.
+    #[instrument(skip(self, query, negatives))]
+    pub fn search_with_negatives(
+        &self,
+        query: &[f32],
+        negatives: &[Vec<f32>],
+        k: usize,
+        penalty: f32,
+    ) -> Result<Vec<(Memory, f32)>> {
+        self.validate_query(query)?;
+        for negative in negatives {
+            self.validate_query(negative)?;
+        }
+        if !penalty.is_finite() || penalty < 0.0 {
+            return Err(Error::InvalidArgument(format!(
+                "penalty {penalty} must be finite and non-negative"
+            )));
+        }
+        let ef = self.config.index.ef_search.max(k * NEGATIVE_POOL);
+        let now = SystemTime::now();
+        let handles = self.by_handle.pin();
+
+        let scored = self
+            .index
+            .search(query, ef)
+            .into_iter()
+            .filter_map(|(handle, distance)| {
+                let stored = handles.get(&handle)?;
+                let likeness = negatives
+                    .iter()
+                    .map(|n| self.metric.similarity(&stored.data.to_f32(), n))
+                    .fold(0.0f32, f32::max);
+                let score = self.combined_score(distance, stored.timestamp, stored.decay_rate, now)
+                    + penalty * likeness;
+                Some((stored, score))
+            });
+        Ok(best_k(scored, k))
+    }
+
+    /// Search, then search again with the query moved toward its own best
+    /// results (pseudo-relevance feedback).
+    ///
+    /// The first search's top `feedback_n` memories are averaged, the
+    /// average is blended into the query as `(1 - alpha) * query + alpha *
+    /// average`, and the blend is rescaled to the query's length before
+    /// the second search, whose results are returned. A query at the edge
+    /// of a cluster is pulled into it, so members the first search missed
+    /// are found. `alpha` must lie in `[0.0, 1.0]`; an `alpha` of zero or
+    /// a `feedback_n` of zero is a plain [`search`](Self::search).
+    #[instrument(skip(self, query))]
+    pub fn search_with_feedback(
+        &self,
+        query: &[f32],
+        k: usize,
+        feedback_n: usize,
+        alpha: f32,
+    ) -> Result<Vec<(Memory, f32)>> {
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(Error::InvalidArgument(format!(
+                "feedback weight {alpha} must be within [0, 1]"
+            )));
+        }
+        if alpha == 0.0 || feedback_n == 0 {
+            return self.search(query, k);
+        }
+        // Both passes make up one search, recorded once.
+        let started = Instant::now();
+        let (feedback, _) = self.rank(query, feedback_n, None, SearchMode::Approximate)?;
+        if feedback.is_empty() {
+            self.record_search("", started);
+            return Ok(Vec::new());
+        }
+
+        let mut mean = vec![0.0f32; self.config.dimensions];
+        for r in &feedback {
+            for (m, x) in mean.iter_mut().zip(r.stored.data.to_f32().iter()) {
+                *m += x;
+            }
+        }
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let mut expanded: Vec<f32> = query
+            .iter()
+            .zip(&mean)
+            .map(|(q, m)| (1.0 - alpha) * q + alpha * m / feedback.len() as f32)
+            .collect();
+        let (target, current) = (norm(query), norm(&expanded));
+        if current > f32::EPSILON && current.is_finite() {
+            for x in &mut expanded {
+                *x *= target / current;
+            }
+        } else {
+            // Results directly opposite the query cancel it out.
+            expanded = query.to_vec();
+        }
+        let (ranked, _) = self.rank(&expanded, k, None, SearchMode::Approximate)?;
+        self.record_search("", started);
+        Ok(ranked
+            .into_iter()
+            .map(|r| (r.stored.materialize(), r.score))
+            .collect())
+    }
+
+    /// Search with several query vectors at once — e.g. multiple
+    /// phrasings of one question.
+    ///
+    /// With [`MultiQueryMode::Mean`] the queries are averaged
+    /// component-wise and searched once; with
+    /// [`MultiQueryMode::MinDistance`] each query is searched and every
+    /// candidate keeps its smallest distance to any of them. Either way the
+    /// distances are then scored as in [`search`](Self::search). Every
+    /// query must have the store's dimensionality, and there must be at
+    /// least one.
+    #[instrument(skip(self, queries))]
+    pub fn search_multi(
+        &self,
+        queries: &[Vec<f32>],
+        k: usize,
+        mode: MultiQueryMode,
+    ) -> Result<Vec<(Memory, f32)>> {
+        if queries.is_empty() {
+            return Err(Error::InvalidArgument(
+                "search_multi needs at least one query".into(),
+            ));
+        }
+        for query in queries {
+            self.validate_query(query)?;
+        }
+
+        match mode {
+            MultiQueryMode::Mean => {
+                let mut mean = vec![0.0f32; self.config.dimensions];
+                for query in queries {
+                    for (m, x) in mean.iter_mut().zip(query) {
+                        *m += x;
+                    }
+                }
+                for m in &mut mean {
+                    *m /= queries.len() as f32;
+                }
+                self.search(&mean, k)
+            }
+            MultiQueryMode::MinDistance => {
+                let ef = self
+                    .config
+                    .index
+                    .ef_search
+                    .max(k * self.config.rerank_overfetch);
+                let mut best: HashMap<u32, f32> = HashMap::new();
+                for query in queries {
+                    for (handle, distance) in self.index.search(query, ef) {
+                        best.entry(handle)
+                            .and_modify(|d| *d = d.min(distance))
+                            .or_insert(distance);
+                    }
+                }
+
+                let now = SystemTime::now();
+                let handles = self.by_handle.pin();
+                let scored = best.into_iter().filter_map(|(handle, distance)| {
+                    let stored = handles.get(&handle)?;
+                    let score =
+                        self.combined_score(distance, stored.timestamp, stored.decay_rate, now);
+                    Some((stored, score))
+                });
+                Ok(best_k(scored, k))
+            }
+        }
+    }
+
+    /// The best `per_context` results of each context, keyed by context
+    /// label, each list sorted best-first by the [`search`](Self::search)
+    /// score.
+    ///
+    /// One index search with a pool of `max(ef_search, per_context * 10)`
+    /// candidates is scored and split by context. Contexts none of whose
+    /// memories reach that pool are absent; use
+    /// [`search_in_context`](Self::search_in_context) for an exhaustive
+    /// answer about one context.
+    #[instrument(skip(self, query))]
+    pub fn search_grouped_by_context(
+        &self,
+        query: &[f32],
+        per_context: usize,
+    ) -> Result<HashMap<String, Vec<(Memory, f32)>>> {
+        let pool = self.config.index.ef_search.max(per_context * GROUP_POOL);
+        let mut groups: HashMap<String, Vec<(Memory, f32)>> = HashMap::new();
+        if per_context == 0 {
+            self.validate_query(query)?;
+            return Ok(groups);
+        }
+        // `search` returns best-first, so each group fills best-first.
+        for (memory, score) in self.search(query, pool)? {
+            let group = groups.entry(memory.attributes.context.clone()).or_default();
+            if group.len() < per_context {
+                group.push((memory, score));
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Like [`search`](Self::search), restricted to one context label.
+    ///
+    /// Scores are exactly those of [`search`](Self::search): distance and
+    /// age blended by [`temporal_weight`](Config::temporal_weight), within
+    /// `[0.0, 1.0]`. Context filtering scans the context's members exactly rather than
+    /// going through the index, so sparse contexts never come back short.
+    #[instrument(skip(self, query))]
+    pub fn search_in_context(
+        &self,
+        context: &str,
+        query: &[f32],
+        k: usize,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let started = Instant::now();
+        self.validate_query(query)?;
+        let now = SystemTime::now();
+        let handles = self.by_handle.pin();
+
+        let members: Vec<u32> = self
+            .by_id
+            .pin()
+            .values()
+            .filter(|s| s.context == context)
+            .map(|s| s.handle)
+            .collect();
+        let scored: Vec<(&Arc<StoredMemory>, f32)> = self
+            .index
+            .distances(query, &members)
+            .into_iter()
+            .filter_map(|(handle, distance)| {
+                let s = handles.get(&handle)?;
+                Some((
+                    s,
+                    self.combined_score(distance, s.timestamp, s.decay_rate, now),
+                ))
+            })
+            .collect();
+
+        let results = top_k_by(scored, k, by_score_then_id)
+            .into_iter()
+            .map(|(s, score)| (s.materialize(), score))
+            .collect();
+        self.record_search(context, started);
+        Ok(results)
+    }
+
+    /// Like [`search`](Self::search), restricted to memories whose
+    /// [`metadata`](crate::MemoryAttributes::metadata) satisfies
+    /// `predicate`. Memories without metadata never match.
+    ///
+    /// Scores are exactly those of [`search`](Self::search). The index
+    /// widens its candidate pool until enough memories pass, so a
+    /// selective predicate still fills `k` when that many match, at the
+    /// cost of extra traversals.
+    #[instrument(skip(self, query, predicate))]
+    pub fn search_where(
+        &self,
+        query: &[f32],
+        k: usize,
+        predicate: impl Fn(&serde_json::Value) -> bool + Sync,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let accept = |handle: u32| {
+            self.by_handle
+                .pin()
+                .get(&handle)
+                .and_then(|s| s.metadata.as_ref())
+                .is_some_and(&predicate)
+        };
+        let (ranked, _) = self.ranked(query, k, Some(&accept), SearchMode::Approximate)?;
+        Ok(ranked
+            .into_iter()
+            .map(|r| (r.stored.materialize(), r.score))
+            .collect())
+    }
+
+    /// Ids of the `k` memories geometrically nearest `query` as the index
+    /// finds them: the candidate pool [`search`](Self::search) draws from,
+    /// cut to `k` by raw distance.
+    pub(crate) fn approximate_neighbors(&self, query: &[f32], k: usize) -> Result<Vec<String>> {
+        self.validate_query(query)?;
+        let ef = self
+            .config
+            .index
+            .ef_search
+            .max(k * self.config.rerank_overfetch);
+        let handles = self.by_handle.pin();
+        let mut seen = HashSet::new();
+        Ok(self
+            .index
+            .search_with_stats(query, ef)
+            .0
+            .into_iter()
+            .filter_map(|(handle, _)| handles.get(&handle).map(|s| s.id.clone()))
+            .filter(|id| seen.insert(id.clone()))
+            .take(k)
+            .collect())
+    }
+
+    /// Ids of the `k` memories geometrically nearest `query`, by an
+    /// exhaustive scan of the records under the store's metric.
+    pub(crate) fn exact_neighbors(&self, query: &[f32], k: usize) -> Result<Vec<String>> {
+        self.validate_query(query)?;
+        let scored: Vec<(String, f32)> = self
+            .by_id
+            .pin()
+            .values()
+            .map(|s| (s.id.clone(), self.metric.distance(query, &s.data.to_f32())))
+            .collect();
+        Ok(top_k_by(scored, k, |(a_id, a), (b_id, b)| {
+            a.total_cmp(b).then_with(|| a_id.cmp(b_id))
+        })
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect())
+    }
+
+    fn validate_query(&self, query: &[f32]) -> Result<()> {
+        if query.len() != self.config.dimensions {
+            return Err(Error::QueryDimensionMismatch {
+                query: query.len(),
+                expected: self.config.dimensions,
+            });
+        }
+        if query.iter().any(|x| !x.is_finite()) {
+            return Err(Error::InvalidVector(
+                "query contains NaN or infinite components".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The single temporal scoring formula. See [`search`](Self::search).
+    fn combined_score(
+        &self,
+        distance: f32,
+        timestamp: SystemTime,
+        decay_rate: f32,
+        now: SystemTime,
+    ) -> f32 {
+        rerank::combined_score(&self.config, distance, timestamp, decay_rate, now)
+    }
+
+    /// The age term of [`combined_score`](Self::combined_score):
+    /// `1 - exp(-r * t)`, from 0 (fresh) towards 1 (ancient).
+    fn temporal_penalty(&self, timestamp: SystemTime, decay_rate: f32, now: SystemTime) -> f32 {
+        rerank::temporal_penalty(&self.config, timestamp, decay_rate, now)
+    }
+
+    /// Decay every memory's importance based on time elapsed while
+    /// unaccessed.
+    ///
+    /// Each sweep multiplies importance by `exp(-r * h)` where `r` is the
+    /// memory's effective decay rate and `h` is the hours **since the
+    /// later of the last access and the previous sweep** — sweeps apply
+    /// disjoint intervals, so calling this every minute or once a week
+    /// composes into the same documented curve. A per-memory CAS gate
+    /// ensures concurrent sweeps never apply the same interval twice.
+    /// Lock-free throughout; runs concurrently with reads and writes.
+    ///
+    /// [Pinned](Self::pin_memory) memories keep their importance; the
+    /// sweep still claims their interval, so unpinning one later does not
+    /// decay it for the time it spent pinned.
+    ///
+    /// Does nothing on a [read-only](Self::into_read_only) store.
+    pub fn apply_decay(&self) {
+        let _ = self.try_apply_decay();
+    }
+
+    /// [`apply_decay`](Self::apply_decay), failing with
+    /// [`Error::ReadOnly`] on a read-only store instead of doing nothing.
+    #[instrument(skip(self))]
+    pub fn try_apply_decay(&self) -> Result<()> {
+        self.ensure_writable()?;
+        let now_nanos = nanos_since_epoch(SystemTime::now());
+        for stored in self.by_id.pin().values() {
+            let previous_sweep = stored.decayed_through_nanos.load(Ordering::Acquire);
+            let from = previous_sweep.max(stored.last_access_nanos.load(Ordering::Acquire));
+            if now_nanos <= from {
+                continue;
+            }
+            // Claim the interval (from, now]. A concurrent sweep that loses
+            // this CAS skips the memory; the winner applies the decay.
+            if stored
+                .decayed_through_nanos
+                .compare_exchange(
+                    previous_sweep,
+                    now_nanos,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .is_err()
+            {
+                continue;
+            }
+            if stored.pinned.load(Ordering::Acquire) {
+                continue;
+            }
+            let hours = (now_nanos - from) as f32 / 1e9 / SECONDS_PER_HOUR;
             let rate = if stored.decay_rate > 0.0 {
                 stored.decay_rate
             } else {
                 self.config.base_decay_rate
             };
             stored.scale_importance((-rate * hours).exp());
+            self.mark_changed(&stored.id);
+        }
+        Ok(())
+    }
+
+    /// Record that memory `id` was used downstream: raise its importance
+    /// by `amount` (capped at `1.0`) and count an access.
+    ///
+    /// The access also refreshes
+    /// [`last_access`](crate::MemoryAttributes::last_access), which is
+    /// where [`apply_decay`](Self::apply_decay) measures unaccessed time
+    /// from, so frequently reinforced memories both gain importance and
+    /// lose less of it. Lock-free. `amount` must be finite and
+    /// non-negative; a missing id is [`Error::NotFound`].
+    pub fn reinforce(&self, id: &str, amount: f32) -> Result<()> {
+        self.ensure_writable()?;
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(Error::InvalidArgument(format!(
+                "reinforcement amount must be finite and non-negative, got {amount}"
+            )));
+        }
+        let map = self.by_id.pin();
+        let stored = map.get(id).ok_or_else(|| Error::NotFound(id.to_owned()))?;
+        stored.update_importance(|importance| importance + amount);
+        stored.record_access();
+        self.mark_changed(id);
+        Ok(())
+    }
+
+    /// Pin or unpin memory `id`. Pinned memories are skipped by
+    /// [`apply_decay`](Self::apply_decay); see
+    /// [`MemoryAttributes::pinned`](crate::MemoryAttributes::pinned).
+    /// Lock-free. A missing id is [`Error::NotFound`].
+    pub fn pin_memory(&self, id: &str, pinned: bool) -> Result<()> {
+        self.ensure_writable()?;
+        let map = self.by_id.pin();
+        let stored = map.get(id).ok_or_else(|| Error::NotFound(id.to_owned()))?;
+        stored.pinned.store(pinned, Ordering::Release);
+        self.mark_changed(id);
+        Ok(())
+    }
+
+    /// Fit a whitening transform to the stored vectors and rank through it
+    /// from now on.
+    ///
+    /// Mean-centers the corpus and rescales it along its principal axes to
+    /// unit variance, so that on anisotropic embedding spaces (most of the
+    /// variance in a few directions) the remaining directions still shape
+    /// the neighbor structure. Queries and stored vectors are both
+    /// whitened before the store's metric compares them; the index is
+    /// rebuilt over the whitened vectors. Stored data is not modified —
+    /// [`get`](Self::get) still returns the original embeddings.
+    ///
+    /// Fitting costs `O(n·d²)` for the covariance plus `O(d³)` per solver
+    /// sweep, and the rebuild re-inserts every memory. Refit after the
+    /// corpus drifts; memories inserted later are whitened with the current
+    /// transform. The transform is not part of snapshots — refit after
+    /// loading. Requires `&mut self` for the same reason
+    /// [`consolidate`](Self::consolidate) does.
+    #[instrument(skip(self))]
+    pub fn fit_whitening(&mut self) -> Result<()> {
+        self.ensure_writable()?;
+        let records: Vec<Arc<StoredMemory>> = self.by_id.pin().values().cloned().collect();
+        let vectors: Vec<Cow<'_, [f32]>> = records.iter().map(|r| r.data.to_f32()).collect();
+        let refs: Vec<&[f32]> = vectors.iter().map(AsRef::as_ref).collect();
+        let whitening = Arc::new(Whitening::fit(&refs)?);
+        let metric: Arc<dyn DistanceMetric> = Arc::new(WhitenedMetric::new(
+            Arc::clone(&self.base_metric),
+            Arc::clone(&whitening),
+        ));
+        self.rebuild_index(metric)?;
+        self.whitening = Some(whitening);
+        Ok(())
+    }
+
+    /// Drop a fitted whitening transform and rebuild the index over the raw
+    /// vectors. A no-op when none is fitted.
+    #[instrument(skip(self))]
+    pub fn clear_whitening(&mut self) -> Result<()> {
+        self.ensure_writable()?;
+        if self.whitening.is_none() {
+            return Ok(());
+        }
+        self.rebuild_index(Arc::clone(&self.base_metric))?;
+        self.whitening = None;
+        Ok(())
+    }
+
+    /// The whitening transform in effect, if one has been fitted.
+    pub fn whitening(&self) -> Option<&Whitening> {
+        self.whitening.as_deref()
+    }
+
+    /// Build a fresh index over every stored memory with `metric`, then
+    /// swap it and the re-handled records in. The store is untouched if
+    /// any insert fails.
+    fn rebuild_index(&mut self, metric: Arc<dyn DistanceMetric>) -> Result<()> {
+        let index = LockFreeHnsw::new(self.config.index.clone(), Arc::clone(&metric));
+        let by_id = papaya::HashMap::new();
+        let by_handle = papaya::HashMap::new();
+        {
+            let (ids, handles) = (by_id.pin(), by_handle.pin());
+            for stored in self.by_id.pin().values() {
+                let handle = index
+                    .insert(&stored.data.to_f32())
+                    .ok_or(Error::IndexFull(crate::index::arena_capacity()))?;
+                let moved = stored.relocated(handle);
+                handles.insert(handle, Arc::clone(&moved));
+                ids.insert(moved.id.clone(), moved);
+            }
         }
+        self.metric = metric;
+        self.index = index;
+        self.by_id = by_id;
+        self.by_handle = by_handle;
+        Ok(())
     }
 
     /// Merge near-duplicate memories.
     ///
-    /// For every pair with cosine similarity above
+    /// For every pair whose similarity under the store metric exceeds
     /// [`similarity_threshold`](Config::similarity_threshold), the
     /// lower-importance memory is absorbed into the higher-importance one:
     /// relationships merge, importance keeps the maximum, and the absorbed
     /// memory is removed. Returns the number of memories absorbed.
     ///
-    /// Takes `&mut self`: this is an `O(n²)` maintenance pass that wants
+    /// Pairs come from the index: each memory is compared with its
+    /// [`consolidation_neighbors`](Config::consolidation_neighbors) nearest
+    /// neighbors rather than every other memory, so the pass is one index
+    /// search per memory instead of `O(n²)` comparisons. Near-duplicates
+    /// are each other's nearest neighbors, so the cap only matters for
+    /// clusters of more duplicates than it allows.
+    ///
+    /// Takes `&mut self`: this is a whole-store maintenance pass that wants
     /// exclusive access for trivially correct pairwise bookkeeping — run it
     /// from a maintenance thread, not the hot path.
     ///
@@ -512,37 +1789,74 @@ impl ChronoMind {
     /// `&mut` without unwrapping. If sole ownership cannot be proven
     /// (`try_unwrap`/`get_mut` fail), a worker still holds a clone — that is
     /// the compiler enforcing the quiesce contract, not an inconvenience.
-    #[instrument(skip(self))]
+    ///
+    /// Absorbs nothing on a [read-only](Self::into_read_only) store.
     pub fn consolidate(&mut self) -> usize {
-        let records: Vec<Arc<StoredMemory>> = self.by_id.pin().values().cloned().collect();
-        let mut absorbed: HashSet<String> = HashSet::new();
+        self.try_consolidate().unwrap_or(0)
+    }
 
-        for i in 0..records.len() {
-            if absorbed.contains(&records[i].id) {
+    /// [`consolidate`](Self::consolidate), failing with
+    /// [`Error::ReadOnly`] on a read-only store instead of absorbing
+    /// nothing.
+    #[instrument(skip(self))]
+    pub fn try_consolidate(&mut self) -> Result<usize> {
+        self.ensure_writable()?;
+        let ids: Vec<String> = self.by_id.pin().keys().cloned().collect();
+        let cap = self.config.consolidation_neighbors;
+        let ef = self.config.index.ef_search.max(cap + 1);
+        // Absorbed id -> the id that absorbed it.
+        let mut absorbed: HashMap<String, String> = HashMap::new();
+
+        for id in &ids {
+            if absorbed.contains_key(id) {
                 continue;
             }
-            for j in (i + 1)..records.len() {
-                if absorbed.contains(&records[j].id) || absorbed.contains(&records[i].id) {
+            let Some(record) = self.by_id.pin().get(id).cloned() else {
+                continue;
+            };
+            let neighbors: Vec<u32> = self
+                .index
+                .search(&record.data.to_f32(), ef)
+                .into_iter()
+                .map(|(handle, _)| handle)
+                .filter(|&handle| handle != record.handle)
+                .take(cap)
+                .collect();
+
+            for handle in neighbors {
+                if absorbed.contains_key(id) {
+                    break;
+                }
+                // Re-read both sides: an earlier merge may have rebuilt them.
+                let (Some(a), Some(b)) = (
+                    self.by_id.pin().get(id).cloned(),
+                    self.by_handle.pin().get(&handle).cloned(),
+                ) else {
+                    continue;
+                };
+                if absorbed.contains_key(&b.id) {
                     continue;
                 }
-                let (a, b) = (&records[i], &records[j]);
-                let similarity = self.metric.similarity(&a.data, &b.data);
+                let similarity = self.metric.similarity(&a.data.to_f32(), &b.data.to_f32());
                 if similarity <= self.config.similarity_threshold {
                     continue;
                 }
 
                 // Keep the more important memory; absorb the other.
                 let (keeper, dropped) = if a.importance() >= b.importance() {
-                    (a, b)
+                    (&a, &b)
                 } else {
-                    (b, a)
+                    (&b, &a)
                 };
 
-                let mut links: Vec<String> = keeper.relationships.to_vec();
-                let known: HashSet<String> = links.iter().cloned().collect();
-                for link in dropped.relationships.iter() {
+                // Inherited links are weakened by how far the absorbed
+                // memory was from the keeper.
+                let mut links: Vec<(String, f32)> =
+                    keeper.links().map(|(l, s)| (l.clone(), s)).collect();
+                let known: HashSet<&String> = keeper.relationships.iter().collect();
+                for (link, strength) in dropped.links() {
                     if link != &keeper.id && !known.contains(link) {
-                        links.push(link.clone());
+                        links.push((link.clone(), strength * similarity.clamp(0.0, 1.0)));
                     }
                 }
                 links.truncate(self.config.max_relationships);
@@ -558,18 +1872,190 @@ impl ChronoMind {
                 map.remove(&dropped.id);
                 self.by_handle.pin().remove(&dropped.handle);
                 self.index.remove(dropped.handle);
+                self.invalidate_centroid(&dropped.context);
+                self.mark_changed(&keeper.id);
+                self.mark_changed(&dropped.id);
 
                 debug!(kept = %keeper.id, dropped = %dropped.id, similarity, "consolidated");
-                absorbed.insert(dropped.id.clone());
+                absorbed.insert(dropped.id.clone(), keeper.id.clone());
+            }
+        }
+
+        if self.config.consolidation_relinks && !absorbed.is_empty() {
+            self.relink(&absorbed);
+        }
+        Ok(absorbed.len())
+    }
+
+    /// Point every link to an absorbed memory at the memory that finally
+    /// absorbed it, dropping the self-links and duplicates that creates.
+    fn relink(&self, absorbed: &HashMap<String, String>) {
+        let resolve = |id: &str| -> String {
+            let mut id = id;
+            while let Some(next) = absorbed.get(id) {
+                id = next;
+            }
+            id.to_owned()
+        };
+        let map = self.by_id.pin();
+        for stored in map.values() {
+            if !stored
+                .relationships
+                .iter()
+                .any(|l| absorbed.contains_key(l))
+            {
+                continue;
             }
+            // A link resolving to one already kept keeps the stronger.
+            let mut links: Vec<(String, f32)> = Vec::new();
+            for (link, strength) in stored.links() {
+                let link = resolve(link);
+                if link == stored.id {
+                    continue;
+                }
+                match links.iter_mut().find(|(l, _)| *l == link) {
+                    Some(kept) => kept.1 = kept.1.max(strength),
+                    None => links.push((link, strength)),
+                }
+            }
+            let rebuilt = stored.rebuilt(links, stored.importance());
+            self.by_handle
+                .pin()
+                .insert(rebuilt.handle, Arc::clone(&rebuilt));
+            map.insert(stored.id.clone(), rebuilt);
+            self.mark_changed(&stored.id);
+        }
+    }
+
+    /// Remove memories whose vectors are exact duplicates of another's,
+    /// keeping the most important of each group (the smallest id on a
+    /// tie). Returns the number removed.
+    ///
+    /// Unlike [`consolidate`](Self::consolidate) this needs no threshold
+    /// and no index searches: vectors are grouped by their stored bits,
+    /// which catches the same embedding ingested under several ids. With
+    /// `merge_relationships`, the survivor also takes on the removed
+    /// memories' links and access counts, and links elsewhere to a
+    /// removed memory are pointed at its survivor; without it, the removed
+    /// memories simply go. Requires `&mut self` for the same reason
+    /// [`consolidate`](Self::consolidate) does.
+    #[instrument(skip(self))]
+    pub fn deduplicate(&mut self, merge_relationships: bool) -> Result<usize> {
+        self.ensure_writable()?;
+        let mut groups: HashMap<Vec<u32>, Vec<Arc<StoredMemory>>> = HashMap::new();
+        for stored in self.by_id.pin().values() {
+            // -0.0 and 0.0 compare equal, so they must hash equal too.
+            let key = stored
+                .data
+                .to_f32()
+                .iter()
+                .map(|&x| if x == 0.0 { 0 } else { x.to_bits() })
+                .collect();
+            groups.entry(key).or_default().push(Arc::clone(stored));
         }
 
-        absorbed.len()
+        let mut absorbed: HashMap<String, String> = HashMap::new();
+        for mut group in groups.into_values().filter(|g| g.len() > 1) {
+            group.sort_by(|a, b| {
+                b.importance()
+                    .total_cmp(&a.importance())
+                    .then_with(|| a.id.cmp(&b.id))
+            });
+            let (keeper, dropped) = group.split_first().expect("groups have members");
+            if merge_relationships {
+                let mut links: Vec<(String, f32)> =
+                    keeper.links().map(|(l, s)| (l.clone(), s)).collect();
+                for (link, strength) in dropped.iter().flat_map(|d| d.links()) {
+                    if link == &keeper.id || dropped.iter().any(|d| &d.id == link) {
+                        continue;
+                    }
+                    match links.iter_mut().find(|(l, _)| l == link) {
+                        Some(kept) => kept.1 = kept.1.max(strength),
+                        None => links.push((link.clone(), strength)),
+                    }
+                }
+                links.truncate(self.config.max_relationships);
+                let rebuilt = keeper.rebuilt(links, keeper.importance());
+                let accesses = dropped
+                    .iter()
+                    .map(|d| d.access_count.load(Ordering::Acquire))
+                    .fold(0u32, u32::saturating_add);
+                rebuilt
+                    .access_count
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                        Some(n.saturating_add(accesses))
+                    })
+                    .ok();
+                self.by_handle
+                    .pin()
+                    .insert(rebuilt.handle, Arc::clone(&rebuilt));
+                self.by_id.pin().insert(keeper.id.clone(), rebuilt);
+                self.mark_changed(&keeper.id);
+            }
+            for duplicate in dropped {
+                self.remove(&duplicate.id);
+                absorbed.insert(duplicate.id.clone(), keeper.id.clone());
+            }
+            debug!(kept = %keeper.id, removed = dropped.len(), "deduplicated");
+        }
+
+        if merge_relationships && !absorbed.is_empty() {
+            self.relink(&absorbed);
+        }
+        Ok(absorbed.len())
+    }
+
+    /// Remove every memory whose importance is below `threshold`, drop
+    /// the links remaining memories had to them, and return how many were
+    /// removed.
+    ///
+    /// Meant for periodic cleanup after [`apply_decay`](Self::apply_decay)
+    /// has worn down what is no longer used. `threshold` must be finite
+    /// ([`Error::InvalidArgument`] otherwise). Requires `&mut self` for the
+    /// same reason [`consolidate`](Self::consolidate) does.
+    #[instrument(skip(self))]
+    pub fn prune_below_importance(&mut self, threshold: f32) -> Result<usize> {
+        self.ensure_writable()?;
+        if !threshold.is_finite() {
+            return Err(Error::InvalidArgument(format!(
+                "importance threshold must be finite, got {threshold}"
+            )));
+        }
+        let doomed: HashSet<String> = self
+            .by_id
+            .pin()
+            .values()
+            .filter(|s| s.importance() < threshold)
+            .map(|s| s.id.clone())
+            .collect();
+        for id in &doomed {
+            self.remove(id);
+        }
+
+        let map = self.by_id.pin();
+        for stored in map.values() {
+            if !stored.relationships.iter().any(|l| doomed.contains(l)) {
+                continue;
+            }
+            let links = stored
+                .links()
+                .filter(|(l, _)| !doomed.contains(*l))
+                .map(|(l, s)| (l.clone(), s))
+                .collect();
+            let rebuilt = stored.rebuilt(links, stored.importance());
+            self.by_handle
+                .pin()
+                .insert(rebuilt.handle, Arc::clone(&rebuilt));
+            map.insert(stored.id.clone(), rebuilt);
+            self.mark_changed(&stored.id);
+        }
+        debug!(pruned = doomed.len(), threshold, "pruned by importance");
+        Ok(doomed.len())
     }
 
     /// Memories reachable from `id` by following relationship links, up to
-    /// `max_depth` hops, in breadth-first order. The starting memory is not
-    /// included.
+    /// `max_depth` hops, in breadth-first order, each memory's links taken
+    /// strongest first. The starting memory is not included.
     pub fn related(&self, id: &str, max_depth: usize) -> Vec<Memory> {
         let map = self.by_id.pin();
         let mut visited: HashSet<String> = HashSet::new();
@@ -586,7 +2072,9 @@ impl ChronoMind {
             let Some(stored) = map.get(&current) else {
                 continue;
             };
-            for link in stored.relationships.iter() {
+            let mut links: Vec<(&String, f32)> = stored.links().collect();
+            links.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+            for (link, _) in links {
                 if visited.insert(link.clone()) {
                     if let Some(linked) = map.get(link) {
                         result.push(linked.materialize());
@@ -599,58 +2087,348 @@ impl ChronoMind {
         result
     }
 
+    /// The shortest chain of relationship links from `from` to `to`, as ids
+    /// from `from` through `to` inclusive, or `None` if `to` is not
+    /// reachable within `max_depth` hops.
+    ///
+    /// Links are followed in the direction they were recorded, as in
+    /// [`related`](Self::related); links to ids not in the store are
+    /// skipped. A memory is connected to itself by the one-element path.
+    /// Either endpoint missing is [`Error::NotFound`].
+    pub fn relationship_path(
+        &self,
+        from: &str,
+        to: &str,
+        max_depth: usize,
+    ) -> Result<Option<Vec<String>>> {
+        let map = self.by_id.pin();
+        for id in [from, to] {
+            if !map.contains_key(id) {
+                return Err(Error::NotFound(id.to_owned()));
+            }
+        }
+
+        // Each reached id maps to the id it was first reached from.
+        let mut parents: HashMap<&str, &str> = HashMap::new();
+        let mut queue: VecDeque<(&str, usize)> = VecDeque::new();
+        parents.insert(from, from);
+        queue.push_back((from, 0));
+
+        while let Some((current, depth)) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![to.to_owned()];
+                let mut step = to;
+                while step != from {
+                    step = parents[step];
+                    path.push(step.to_owned());
+                }
+                path.reverse();
+                return Ok(Some(path));
+            }
+            if depth >= max_depth {
+                continue;
+            }
+            let Some(stored) = map.get(current) else {
+                continue;
+            };
+            for link in stored.relationships.iter() {
+                if map.contains_key(link.as_str()) && !parents.contains_key(link.as_str()) {
+                    parents.insert(link, current);
+                    queue.push_back((link, depth + 1));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Cycles in the relationship graph, each as the ids around it, starting
+    /// from its least id.
+    ///
+    /// One cycle is reported per back edge of a depth-first search over
+    /// every memory (in id order, links in recorded order): every cyclic
+    /// part of the graph shows up, but not every elementary cycle through
+    /// it is listed. A memory linking itself is a one-element cycle; a
+    /// pair linking each other, a two-element one. Links to ids not in the
+    /// store are ignored.
+    pub fn relationship_cycles(&self) -> Vec<Vec<String>> {
+        let map = self.by_id.pin();
+        let mut roots: Vec<&str> = map.keys().map(String::as_str).collect();
+        roots.sort_unstable();
+
+        let mut done: HashSet<&str> = HashSet::new();
+        let mut cycles = Vec::new();
+        for root in roots {
+            if done.contains(root) {
+                continue;
+            }
+            // The current DFS path, each id's position on it, and per path
+            // entry the index of the next link to follow.
+            let mut path: Vec<&str> = vec![root];
+            let mut position: HashMap<&str, usize> = HashMap::from([(root, 0)]);
+            let mut frames: Vec<(&Arc<StoredMemory>, usize)> =
+                vec![(map.get(root).expect("root is a key"), 0)];
+
+            while let Some((stored, next)) = frames.last_mut() {
+                let stored: &Arc<StoredMemory> = stored;
+                let Some(link) = stored.relationships.get(*next) else {
+                    let finished = path.pop().expect("one path entry per frame");
+                    position.remove(finished);
+                    done.insert(finished);
+                    frames.pop();
+                    continue;
+                };
+                *next += 1;
+                let link = link.as_str();
+                if let Some(&at) = position.get(link) {
+                    let mut cycle: Vec<String> =
+                        path[at..].iter().map(|id| id.to_string()).collect();
+                    let least = (0..cycle.len())
+                        .min_by_key(|&i| &cycle[i])
+                        .expect("cycles are non-empty");
+                    cycle.rotate_left(least);
+                    cycles.push(cycle);
+                } else if !done.contains(link) {
+                    if let Some(target) = map.get(link) {
+                        position.insert(link, path.len());
+                        path.push(link);
+                        frames.push((target, 0));
+                    }
+                }
+            }
+        }
+        cycles
+    }
+
     /// Summarize the memories sharing a context label, or `None` if the
     /// context is empty.
+    ///
+    /// The centroid comes from the
+    /// [`context_centroid`](Self::context_centroid) cache.
     pub fn context_summary(&self, context: &str) -> Option<ContextSummary> {
         let map = self.by_id.pin();
         let mut count = 0usize;
-        let mut centroid = vec![0.0f32; self.config.dimensions];
         let mut importance_sum = 0.0f32;
 
         for stored in map.values().filter(|s| s.context == context) {
             count += 1;
             importance_sum += stored.importance();
-            for (acc, x) in centroid.iter_mut().zip(&stored.data) {
-                *acc += x;
-            }
         }
         if count == 0 {
             return None;
         }
-        for acc in &mut centroid {
-            *acc /= count as f32;
-        }
 
         Some(ContextSummary {
             context: context.to_string(),
             memory_count: count,
             average_importance: importance_sum / count as f32,
-            centroid,
+            centroid: self.context_centroid(context)?,
         })
     }
 
+    /// Component-wise mean of the vectors labeled `context`, or `None` if
+    /// the context is empty.
+    ///
+    /// Computed on first use and cached until a memory joins or leaves the
+    /// context (insert, replace, remove, rename, consolidation), so hot
+    /// contexts cost one map lookup.
+    pub fn context_centroid(&self, context: &str) -> Option<Vec<f32>> {
+        let cache = self.centroids.pin();
+        if let Some(centroid) = cache.get(context) {
+            return Some(centroid.to_vec());
+        }
+
+        let generation = self.centroid_generation.load(Ordering::SeqCst);
+        let mut count = 0usize;
+        let mut centroid = vec![0.0f32; self.config.dimensions];
+        for stored in self.by_id.pin().values().filter(|s| s.context == context) {
+            count += 1;
+            for (acc, x) in centroid.iter_mut().zip(stored.data.to_f32().iter()) {
+                *acc += x;
+            }
+        }
+        if count == 0 {
+            return None;
+        }
+        for acc in &mut centroid {
+            *acc /= count as f32;
+        }
+
+        cache.insert(context.to_owned(), centroid.as_slice().into());
+        // A membership change during the scan may or may not be reflected
+        // in it; drop the entry rather than cache a possibly stale mean.
+        if self.centroid_generation.load(Ordering::SeqCst) != generation {
+            cache.remove(context);
+        }
+        Some(centroid)
+    }
+
+    /// The `top_n` contexts whose centroids are most similar to `query`,
+    /// as `(context, similarity)` pairs, most similar first.
+    ///
+    /// Answers "which context is this about?". Similarity is the store
+    /// metric's [`similarity`](DistanceMetric::similarity) against each
+    /// [`context_centroid`](Self::context_centroid), so repeated calls
+    /// reuse the centroid cache. Unlabeled memories (empty context) are
+    /// not a context and are skipped.
+    #[instrument(skip(self, query))]
+    pub fn classify_context(&self, query: &[f32], top_n: usize) -> Result<Vec<(String, f32)>> {
+        self.validate_query(query)?;
+        let contexts: HashSet<String> = self
+            .by_id
+            .pin()
+            .values()
+            .filter(|s| !s.context.is_empty())
+            .map(|s| s.context.clone())
+            .collect();
+        let ranked: Vec<(String, f32)> = contexts
+            .into_iter()
+            .filter_map(|context| {
+                let centroid = self.context_centroid(&context)?;
+                let similarity = self.metric.similarity(&centroid, query);
+                Some((context, similarity))
+            })
+            .collect();
+        Ok(top_k_by(ranked, top_n, |(a_ctx, a), (b_ctx, b)| {
+            b.total_cmp(a).then_with(|| a_ctx.cmp(b_ctx))
+        }))
+    }
+
+    /// Drop `context`'s cached centroid. Writers call this after changing
+    /// the context's membership in `by_id`.
+    fn invalidate_centroid(&self, context: &str) {
+        self.centroid_generation.fetch_add(1, Ordering::SeqCst);
+        self.centroids.pin().remove(context);
+    }
+
+    /// Move every memory labeled `from` to the context `to`, returning how
+    /// many were relabeled.
+    ///
+    /// Records keep their index nodes, vectors, and access state; only the
+    /// label changes. Renaming onto an existing context merges the two. An
+    /// empty `to` is rejected with [`Error::InvalidArgument`]. Requires
+    /// `&mut self` for the same reason [`consolidate`](Self::consolidate)
+    /// does.
+    #[instrument(skip(self))]
+    pub fn rename_context(&mut self, from: &str, to: &str) -> Result<usize> {
+        self.ensure_writable()?;
+        if to.is_empty() {
+            return Err(Error::InvalidArgument(
+                "cannot rename a context to the empty label".into(),
+            ));
+        }
+        if from == to {
+            return Ok(0);
+        }
+        let (ids, handles) = (self.by_id.pin(), self.by_handle.pin());
+        let matching: Vec<Arc<StoredMemory>> = ids
+            .values()
+            .filter(|s| s.context == from)
+            .cloned()
+            .collect();
+        for stored in &matching {
+            let renamed = stored.relabeled(to);
+            handles.insert(renamed.handle, Arc::clone(&renamed));
+            ids.insert(renamed.id.clone(), renamed);
+            self.mark_changed(&stored.id);
+        }
+        self.invalidate_centroid(from);
+        self.invalidate_centroid(to);
+        Ok(matching.len())
+    }
+
+    /// Note that `id` was written, for the next incremental backup. Called
+    /// after the write, so a backup that consumes the mark sees its result.
+    pub(crate) fn mark_changed(&self, id: &str) {
+        let changed = self.changed.pin();
+        if !changed.contains(id) {
+            changed.insert(id.to_owned());
+        }
+    }
+
+    /// Consume the ids written since the last call. Each mark is cleared
+    /// before the caller reads the memory's current state, so a write
+    /// racing the backup is either seen now or marked again.
+    pub(crate) fn take_changed(&self) -> Vec<String> {
+        let changed = self.changed.pin();
+        let ids: Vec<String> = changed.iter().cloned().collect();
+        for id in &ids {
+            changed.remove(id);
+        }
+        ids
+    }
+
+    /// Layer sizes and connectivity of the HNSW graph serving searches.
+    ///
+    /// Linear in the number of index nodes, tombstones included; meant for
+    /// diagnostics, not the hot path.
+    pub fn index_stats(&self) -> LayerStats {
+        self.index.layer_stats()
+    }
+
+    /// Reconnect HNSW nodes left with too few links and return how many
+    /// were repaired. See [`LockFreeHnsw::repair`]; like
+    /// [`index_stats`](Self::index_stats), linear in the index size.
+    pub fn repair_index(&self) -> usize {
+        self.index.repair()
+    }
+
+    /// Approximate bytes the store holds: each record (its vector at the
+    /// configured [`Precision`], id, context, and relationship strings,
+    /// plus the record itself), and the index's full-precision vector copy
+    /// (projected, for a [`with_pca`](Self::with_pca) store) and graph
+    /// links for every node — tombstones included, until a snapshot reload
+    /// compacts them.
+    ///
+    /// Metadata values, allocator overhead, and hash-map slack are not
+    /// counted. Walks the whole store and graph; meant for monitoring, not
+    /// the hot path.
+    pub fn estimated_bytes(&self) -> usize {
+        let records: usize = self
+            .by_id
+            .pin()
+            .values()
+            .map(|s| {
+                std::mem::size_of::<StoredMemory>()
+                    + s.data.heap_bytes()
+                    + s.id.capacity()
+                    + s.context.capacity()
+                    + s.relationships.len() * std::mem::size_of::<String>()
+                    + s.relationships.iter().map(String::capacity).sum::<usize>()
+                    + s.strengths.len() * std::mem::size_of::<f32>()
+            })
+            .sum();
+        let width = self.index.vector_dimensions().unwrap_or(0);
+        let vectors = self.index.arena_len() * width * std::mem::size_of::<f32>();
+        let links = self.index.layer_stats().total_connections * std::mem::size_of::<u32>();
+        records + vectors + links
+    }
+
     /// Aggregate statistics for the store.
     pub fn stats(&self) -> MemoryStats {
         let map = self.by_id.pin();
         let mut total = 0usize;
         let mut total_components = 0usize;
         let mut importance_sum = 0.0f32;
+        let mut histogram = [0usize; IMPORTANCE_BUCKETS];
         let mut contexts: HashMap<String, usize> = HashMap::new();
         let mut references: HashMap<String, usize> = HashMap::new();
 
         for stored in map.values() {
             total += 1;
             total_components += stored.data.len();
-            importance_sum += stored.importance();
+            let importance = stored.importance();
+            importance_sum += importance;
+            let bucket = (importance * IMPORTANCE_BUCKETS as f32) as usize;
+            histogram[bucket.min(IMPORTANCE_BUCKETS - 1)] += 1;
             *contexts.entry(stored.context.clone()).or_insert(0) += 1;
             for link in stored.relationships.iter() {
                 *references.entry(link.clone()).or_insert(0) += 1;
             }
         }
 
-        let mut most_referenced: Vec<(String, usize)> = references.into_iter().collect();
-        most_referenced.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
-        most_referenced.truncate(10);
+        let most_referenced = top_k_by(references.into_iter().collect(), 10, |a, b| {
+            b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0))
+        });
 
         MemoryStats {
             total_memories: total,
@@ -661,8 +2439,14 @@ impl ChronoMind {
             } else {
                 0.0
             },
+            importance_histogram: histogram
+                .iter()
+                .enumerate()
+                .map(|(i, &count)| (i as f32 / IMPORTANCE_BUCKETS as f32, count))
+                .collect(),
             context_distribution: contexts,
             most_referenced,
+            bytes_in_use: self.estimated_bytes(),
         }
     }
 }
@@ -8,13 +8,65 @@ use serde::{Deserialize, Serialize};
 use crate::config::Config;
 use crate::error::{Error, Result};
 
-/// An identified embedding vector.
+/// A scalar type embeddings can arrive in.
+///
+/// The store computes distances and keeps its index in `f32` (record
+/// storage is chosen separately, by [`Precision`](crate::Precision)), so
+/// other element types convert at the boundary: build a
+/// [`Vector<T>`](Vector) in the caller's type and insert its
+/// [`to_f32`](Vector::to_f32) form. Distance metrics stay `f32`-only —
+/// they are used as trait objects, which rules out generic methods.
+pub trait VectorElement: Copy + Send + Sync + 'static {
+    /// Convert to `f32`, rounding to nearest.
+    fn to_f32(self) -> f32;
+    /// Convert from `f32`, rounding to nearest.
+    fn from_f32(value: f32) -> Self;
+}
+
+impl VectorElement for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+impl VectorElement for f64 {
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+    fn from_f32(value: f32) -> Self {
+        f64::from(value)
+    }
+}
+
+impl VectorElement for half::f16 {
+    fn to_f32(self) -> f32 {
+        half::f16::to_f32(self)
+    }
+    fn from_f32(value: f32) -> Self {
+        half::f16::from_f32(value)
+    }
+}
+
+impl VectorElement for half::bf16 {
+    fn to_f32(self) -> f32 {
+        half::bf16::to_f32(self)
+    }
+    fn from_f32(value: f32) -> Self {
+        half::bf16::from_f32(value)
+    }
+}
+
+/// An identified embedding vector, with `f32` components unless another
+/// [`VectorElement`] type is named.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Vector {
+pub struct Vector<T = f32> {
     /// Caller-assigned unique identifier.
     pub id: String,
     /// The embedding components.
-    pub data: Vec<f32>,
+    pub data: Vec<T>,
 }
 
 impl Vector {
@@ -25,6 +77,33 @@ impl Vector {
             data,
         }
     }
+
+    /// This vector with its components converted to `T`.
+    pub fn convert<T: VectorElement>(&self) -> Vector<T> {
+        Vector {
+            id: self.id.clone(),
+            data: self.data.iter().map(|&x| T::from_f32(x)).collect(),
+        }
+    }
+}
+
+impl<T: VectorElement> Vector<T> {
+    /// Create a vector of `T` components.
+    pub fn from_elements(id: impl Into<String>, data: Vec<T>) -> Self {
+        Self {
+            id: id.into(),
+            data,
+        }
+    }
+
+    /// This vector with its components converted to `f32`, the form the
+    /// store accepts.
+    pub fn to_f32(&self) -> Vector {
+        Vector {
+            id: self.id.clone(),
+            data: self.data.iter().map(|x| x.to_f32()).collect(),
+        }
+    }
 }
 
 /// Temporal and semantic metadata attached to a [`Memory`].
@@ -42,10 +121,25 @@ pub struct MemoryAttributes {
     pub decay_rate: f32,
     /// Ids of related memories.
     pub relationships: Vec<String>,
+    /// Strength of each link in `relationships`, by position, in
+    /// `[0.0, 1.0]`. Links past the end of this list are full strength;
+    /// memories read back from a store carry one entry per link. See
+    /// [`weighted_relationships`](Self::weighted_relationships).
+    #[serde(default)]
+    pub relationship_strengths: Vec<f32>,
     /// Number of times this memory has been retrieved.
     pub access_count: u32,
     /// When this memory was last retrieved.
     pub last_access: SystemTime,
+    /// Arbitrary caller data carried with the memory (a source URL, tags,
+    /// a document reference). The store never interprets it.
+    #[serde(default, with = "json_metadata")]
+    pub metadata: Option<serde_json::Value>,
+    /// Exempt from [`apply_decay`](crate::ChronoMind::apply_decay):
+    /// importance changes only through explicit reinforcement or
+    /// consolidation. See [`pin_memory`](crate::ChronoMind::pin_memory).
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 impl Default for MemoryAttributes {
@@ -57,12 +151,58 @@ impl Default for MemoryAttributes {
             context: String::new(),
             decay_rate: 0.0,
             relationships: Vec::new(),
+            relationship_strengths: Vec::new(),
             access_count: 0,
             last_access: now,
+            metadata: None,
+            pinned: false,
         }
     }
 }
 
+/// Serde for [`MemoryAttributes::metadata`]: a native JSON value in
+/// human-readable formats, JSON text in binary ones such as snapshots,
+/// whose bincode encoding cannot decode a self-describing value.
+mod json_metadata {
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+
+    pub(super) fn serialize<S: Serializer>(
+        value: &Option<Value>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            value.serialize(serializer)
+        } else {
+            value.as_ref().map(Value::to_string).serialize(serializer)
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Value>, D::Error> {
+        if deserializer.is_human_readable() {
+            Option::<Value>::deserialize(deserializer)
+        } else {
+            Option::<String>::deserialize(deserializer)?
+                .map(|text| serde_json::from_str(&text).map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+}
+
+impl MemoryAttributes {
+    /// Each related id with its link strength, in recorded order; links
+    /// without a recorded strength are `1.0`.
+    pub fn weighted_relationships(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.relationships.iter().enumerate().map(|(i, id)| {
+            let strength = self.relationship_strengths.get(i).copied();
+            (id.as_str(), strength.unwrap_or(1.0))
+        })
+    }
+}
+
 /// A vector plus its temporal attributes — the unit of storage.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Memory {
@@ -83,6 +223,15 @@ impl Memory {
         Self::new(vector, MemoryAttributes::default())
     }
 
+    /// Start building a memory with default attributes (see
+    /// [`MemoryAttributes::default`]), overriding only what the caller
+    /// sets.
+    pub fn builder(id: impl Into<String>, data: Vec<f32>) -> MemoryBuilder {
+        MemoryBuilder {
+            memory: Self::from_vector(Vector::new(id, data)),
+        }
+    }
+
     /// Age of the memory relative to `now`.
     pub fn age(&self, now: SystemTime) -> Duration {
         now.duration_since(self.attributes.timestamp)
@@ -100,6 +249,12 @@ impl Memory {
                 expected: config.dimensions,
             });
         }
+        self.validate_values()
+    }
+
+    /// The checks of [`validate`](Self::validate) that need no
+    /// configuration: finite components and in-range attributes.
+    fn validate_values(&self) -> Result<()> {
         if self.vector.data.iter().any(|x| !x.is_finite()) {
             return Err(Error::InvalidVector(format!(
                 "vector {} contains NaN or infinite components",
@@ -117,10 +272,111 @@ impl Memory {
                 self.vector.id
             )));
         }
+        let a = &self.attributes;
+        if a.relationship_strengths.len() > a.relationships.len() {
+            return Err(Error::InvalidArgument(format!(
+                "memory {} has {} relationship strengths for {} relationships",
+                self.vector.id,
+                a.relationship_strengths.len(),
+                a.relationships.len()
+            )));
+        }
+        if let Some(s) = a
+            .relationship_strengths
+            .iter()
+            .find(|s| !(0.0..=1.0).contains(*s))
+        {
+            return Err(Error::InvalidArgument(format!(
+                "memory {} has relationship strength {s} outside [0.0, 1.0]",
+                self.vector.id
+            )));
+        }
         Ok(())
     }
 }
 
+/// Fluent builder for [`Memory`], from [`Memory::builder`].
+#[derive(Debug, Clone)]
+pub struct MemoryBuilder {
+    memory: Memory,
+}
+
+impl MemoryBuilder {
+    /// Set the importance, in `[0.0, 1.0]`.
+    pub fn importance(mut self, importance: f32) -> Self {
+        self.memory.attributes.importance = importance;
+        self
+    }
+
+    /// Set the context label.
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.memory.attributes.context = context.into();
+        self
+    }
+
+    /// Set the per-hour decay rate (`0.0` uses the store's base rate).
+    pub fn decay_rate(mut self, rate: f32) -> Self {
+        self.memory.attributes.decay_rate = rate;
+        self
+    }
+
+    /// Set the creation time. A memory that has never been retrieved was
+    /// last accessed when it was created, so this sets
+    /// [`last_access`](MemoryAttributes::last_access) too.
+    pub fn timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.memory.attributes.timestamp = timestamp;
+        self.memory.attributes.last_access = timestamp;
+        self
+    }
+
+    /// Set the ids of related memories, all at full strength.
+    pub fn relationships<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.memory.attributes.relationships = ids.into_iter().map(Into::into).collect();
+        self.memory.attributes.relationship_strengths.clear();
+        self
+    }
+
+    /// Set the ids of related memories with a strength for each.
+    pub fn weighted_relationships<I, S>(mut self, links: I) -> Self
+    where
+        I: IntoIterator<Item = (S, f32)>,
+        S: Into<String>,
+    {
+        let (ids, strengths) = links.into_iter().map(|(id, s)| (id.into(), s)).unzip();
+        self.memory.attributes.relationships = ids;
+        self.memory.attributes.relationship_strengths = strengths;
+        self
+    }
+
+    /// Attach caller data (see [`MemoryAttributes::metadata`]).
+    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.memory.attributes.metadata = Some(metadata);
+        self
+    }
+
+    /// Exempt the memory from decay (see [`MemoryAttributes::pinned`]).
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.memory.attributes.pinned = pinned;
+        self
+    }
+
+    /// Validate and build the memory.
+    ///
+    /// Runs every check of [`Memory::validate`] except the dimension
+    /// check, which needs the store's configuration and happens on insert.
+    pub fn build(self) -> Result<Memory> {
+        if self.memory.vector.id.is_empty() {
+            return Err(Error::InvalidVector("id must not be empty".into()));
+        }
+        self.memory.validate_values()?;
+        Ok(self.memory)
+    }
+}
+
 /// Aggregate statistics for a store, as returned by
 /// [`stats`](crate::ChronoMind::stats).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -133,10 +389,17 @@ pub struct MemoryStats {
     pub capacity_used: f64,
     /// Mean importance across all memories (`0.0` when empty).
     pub average_importance: f32,
+    /// Importance distribution as `(bucket lower bound, count)` over ten
+    /// equal-width buckets covering `[0.0, 1.0]`; the last bucket includes
+    /// `1.0`. All ten buckets are always present.
+    pub importance_histogram: Vec<(f32, usize)>,
     /// Number of memories per context label.
     pub context_distribution: HashMap<String, usize>,
     /// Ids most referenced by other memories' relationships, descending.
     pub most_referenced: Vec<(String, usize)>,
+    /// Approximate memory footprint; see
+    /// [`estimated_bytes`](crate::ChronoMind::estimated_bytes).
+    pub bytes_in_use: usize,
 }
 
 /// Summary of the memories sharing a context label, as returned by
@@ -153,6 +416,51 @@ pub struct ContextSummary {
     pub centroid: Vec<f32>,
 }
 
+/// One [`search_detailed`](crate::ChronoMind::search_detailed) result: a
+/// memory and the components of its score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// The matching memory.
+    pub memory: Memory,
+    /// Geometric distance from the query under the store's metric.
+    pub distance: f32,
+    /// Age penalty `1 - exp(-r * t)`: `0.0` for a fresh memory, approaching
+    /// `1.0` as it ages.
+    pub temporal_penalty: f32,
+    /// `(1 - w) * distance / 2 + w * temporal_penalty` with
+    /// `w = temporal_weight`: the score [`search`](crate::ChronoMind::search)
+    /// ranks by, lower is better.
+    pub score: f32,
+}
+
+/// How [`search_multi`](crate::ChronoMind::search_multi) combines several
+/// query vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MultiQueryMode {
+    /// Search once with the component-wise mean of the queries. Finds
+    /// memories close to what the queries have in common.
+    Mean,
+    /// Search with every query and keep each candidate's best distance.
+    /// Finds memories close to any one of the queries.
+    MinDistance,
+}
+
+/// How [`search_with_mode`](crate::ChronoMind::search_with_mode) gathers
+/// candidates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchMode {
+    /// What [`search`](crate::ChronoMind::search) does: an HNSW traversal,
+    /// or an exhaustive scan for stores smaller than
+    /// [`brute_force_threshold`](crate::Config::brute_force_threshold).
+    #[default]
+    Approximate,
+    /// Always scan every memory, so no geometric neighbor can be missed
+    /// (in parallel with the `rayon` feature). Linear in the store size,
+    /// and not subject to
+    /// [`max_nodes_visited`](crate::IndexParams::max_nodes_visited).
+    Exact,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,6 +476,46 @@ mod tests {
         Memory::from_vector(Vector::new("m1", data))
     }
 
+    #[test]
+    fn vector_elements_round_trip_through_f32() {
+        let samples = [0.0f32, -2.0, 0.5, 1024.0, 0.1, -3.3, 1e-3];
+        for &x in &samples {
+            assert_eq!(f64::from_f32(x).to_f32(), x);
+        }
+        // f64 narrows to the nearest f32.
+        assert_eq!(0.1f64.to_f32(), 0.1f32);
+        assert_eq!(1e300f64.to_f32(), f32::INFINITY);
+
+        // Exactly representable values survive both half types; the rest
+        // round to their mantissa width: 11 bits for f16, 8 for bf16.
+        for x in [0.0f32, -2.0, 0.5, 1024.0] {
+            assert_eq!(half::f16::from_f32(x).to_f32(), x);
+            assert_eq!(half::bf16::from_f32(x).to_f32(), x);
+        }
+        for &x in &samples[4..] {
+            let f16 = VectorElement::to_f32(half::f16::from_f32(x));
+            let bf16 = VectorElement::to_f32(half::bf16::from_f32(x));
+            assert!(
+                (f16 - x).abs() <= x.abs() * 2f32.powi(-11),
+                "f16 {x}: {f16}"
+            );
+            assert!(
+                (bf16 - x).abs() <= x.abs() * 2f32.powi(-8),
+                "bf16 {x}: {bf16}"
+            );
+        }
+        // f16 overflows past 65504; bf16 keeps the f32 exponent range.
+        assert_eq!(half::f16::from_f32(1e5).to_f32(), f32::INFINITY);
+        assert!(half::bf16::from_f32(1e30).to_f32().is_finite());
+
+        let vector = Vector::new("v", samples.to_vec());
+        let wide: Vector<f64> = vector.convert();
+        assert_eq!(wide.to_f32(), vector);
+        let narrow = vector.convert::<half::bf16>().to_f32();
+        assert_eq!(narrow.id, "v");
+        assert_eq!(narrow.data[..4], samples[..4]);
+    }
+
     #[test]
     fn valid_memory_passes() {
         assert!(memory(vec![0.1, 0.2, 0.3, 0.4]).validate(&config()).is_ok());
@@ -203,6 +551,93 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn minimal_builder_matches_defaults() {
+        let built = Memory::builder("m1", vec![0.1, 0.2, 0.3, 0.4])
+            .build()
+            .unwrap();
+        let mut expected = memory(vec![0.1, 0.2, 0.3, 0.4]);
+        // Default timestamps are "now" at construction.
+        expected.attributes.timestamp = built.attributes.timestamp;
+        expected.attributes.last_access = built.attributes.last_access;
+        assert_eq!(built, expected);
+    }
+
+    #[test]
+    fn customized_builder_sets_every_field() {
+        let then = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let m = Memory::builder("m1", vec![0.1, 0.2, 0.3, 0.4])
+            .importance(0.9)
+            .context("notes")
+            .decay_rate(0.2)
+            .timestamp(then)
+            .relationships(["a", "b"])
+            .build()
+            .unwrap();
+        assert_eq!(m.attributes.importance, 0.9);
+        assert_eq!(m.attributes.context, "notes");
+        assert_eq!(m.attributes.decay_rate, 0.2);
+        assert_eq!(m.attributes.timestamp, then);
+        assert_eq!(m.attributes.last_access, then);
+        assert_eq!(m.attributes.relationships, ["a", "b"]);
+        assert!(m.validate(&config()).is_ok());
+    }
+
+    #[test]
+    fn builder_validates() {
+        let data = vec![0.1, 0.2, 0.3, 0.4];
+        assert!(matches!(
+            Memory::builder("m1", data.clone()).importance(2.0).build(),
+            Err(Error::InvalidImportance(_))
+        ));
+        assert!(matches!(
+            Memory::builder("", data.clone()).build(),
+            Err(Error::InvalidVector(_))
+        ));
+        assert!(matches!(
+            Memory::builder("m1", data).decay_rate(-1.0).build(),
+            Err(Error::InvalidVector(_))
+        ));
+    }
+
+    #[test]
+    fn relationship_strengths_default_to_full_and_are_validated() {
+        let data = vec![0.1, 0.2, 0.3, 0.4];
+        let m = Memory::builder("m1", data.clone())
+            .weighted_relationships([("a", 0.25), ("b", 1.0)])
+            .build()
+            .unwrap();
+        assert_eq!(m.attributes.relationship_strengths, [0.25, 1.0]);
+
+        let mut partial = m.attributes.clone();
+        partial.relationships.push("c".into());
+        let weighted: Vec<(&str, f32)> = partial.weighted_relationships().collect();
+        assert_eq!(weighted, [("a", 0.25), ("b", 1.0), ("c", 1.0)]);
+
+        assert!(matches!(
+            Memory::builder("m1", data.clone())
+                .weighted_relationships([("a", 1.5)])
+                .build(),
+            Err(Error::InvalidArgument(_))
+        ));
+        let mut excess = Memory::builder("m1", data).build().unwrap();
+        excess.attributes.relationship_strengths = vec![0.5];
+        assert!(matches!(
+            excess.validate(&config()),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn element_types_convert_through_f32() {
+        let v = Vector::new("v", vec![0.5, -1.25, 3.0]);
+        assert_eq!(v.convert::<f64>().data, [0.5, -1.25, 3.0]);
+        assert_eq!(v.convert::<half::f16>().to_f32(), v);
+        assert_eq!(v.convert::<half::bf16>().to_f32(), v);
+        let wide = Vector::from_elements("w", vec![0.1f64, 0.2]);
+        assert_eq!(wide.to_f32().data, [0.1f32, 0.2]);
+    }
+
     #[test]
     fn out_of_range_importance_rejected() {
         let mut m = memory(vec![0.1, 0.2, 0.3, 0.4]);
@@ -0,0 +1,285 @@
+//! Write-ahead-logged persistence: a snapshot plus an append-only log of
+//! the inserts and removals made since it was written.
+//!
+//! [`save_snapshot`] rewrites the whole store, which
+//! is O(n) per save. A [`WalStore`] instead appends one record per write to
+//! `<snapshot>.wal`, and [`compact`](WalStore::compact) folds the log back
+//! into a fresh snapshot when it grows. Reopening replays the log on top of
+//! the snapshot, so a process that dies without compacting loses nothing
+//! that was logged.
+//!
+//! Log format: 7-byte magic `CHRONOW`, one format-version byte, then the
+//! little-endian body checksum of the snapshot the log applies to. Each
+//! record follows as a little-endian `u32` length, a CRC32 of the record,
+//! and a bincode-encoded record. A log whose snapshot checksum does not
+//! match the snapshot on disk predates it (a compaction was interrupted
+//! after the snapshot was replaced) and is discarded. A torn final record
+//! — a crash mid-append — is truncated away on open.
+
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::persistence::{self, load_snapshot, save_snapshot};
+use crate::store::ChronoMind;
+use crate::types::Memory;
+
+const MAGIC: &[u8; 7] = b"CHRONOW";
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: u64 = 12;
+
+/// A logged write, as encoded. Serializes identically to [`Record`].
+#[derive(Serialize)]
+enum RecordRef<'a> {
+    Insert(&'a Memory),
+    Remove(&'a str),
+}
+
+/// A logged write, as decoded during replay.
+#[derive(Deserialize)]
+enum Record {
+    Insert(Memory),
+    Remove(String),
+}
+
+/// A [`ChronoMind`] store whose inserts and removals are journaled to a
+/// write-ahead log next to its snapshot.
+///
+/// Writes go through [`insert`](Self::insert) and
+/// [`remove`](Self::remove), which apply the change and append its record
+/// under one mutex so the log order always matches the order the store
+/// saw; journaled writers are therefore serialized. Reads go through
+/// [`store`](Self::store) and stay wait-free.
+///
+/// Only inserts and removals are logged. Decay, access recording, and
+/// consolidation reach disk at the next [`compact`](Self::compact), as do
+/// writes made directly on [`store`](Self::store).
+///
+/// Records are handed to the operating system as they are appended, so
+/// they survive a process crash; call [`sync`](Self::sync) to make them
+/// survive a power loss too.
+pub struct WalStore {
+    store: ChronoMind,
+    snapshot_path: PathBuf,
+    log_path: PathBuf,
+    log: Mutex<File>,
+}
+
+impl WalStore {
+    /// Create a new, empty store at `path`, replacing any snapshot and log
+    /// already there.
+    #[instrument]
+    pub fn create(path: &Path, config: Config) -> Result<Self> {
+        let store = ChronoMind::new(config)?;
+        save_snapshot(&store, path)?;
+        Self::attach(store, path, false)
+    }
+
+    /// Open the snapshot at `path` and replay its log. A snapshot without
+    /// a log (e.g. one written by [`save_snapshot`]) is opened as-is and
+    /// starts a fresh log.
+    #[instrument]
+    pub fn open(path: &Path) -> Result<Self> {
+        let store = load_snapshot(path)?;
+        Self::attach(store, path, true)
+    }
+
+    fn attach(store: ChronoMind, path: &Path, replay: bool) -> Result<Self> {
+        let log_path = log_path(path);
+        let checksum = persistence::snapshot_checksum(path)?;
+        let log = match replay_log(&store, &log_path, checksum, replay)? {
+            Some(log) => log,
+            None => fresh_log(&log_path, checksum)?,
+        };
+        Ok(Self {
+            store,
+            snapshot_path: path.to_path_buf(),
+            log_path,
+            log: Mutex::new(log),
+        })
+    }
+
+    /// The underlying store, for reads. Writes made through it are not
+    /// journaled until the next [`compact`](Self::compact).
+    pub fn store(&self) -> &ChronoMind {
+        &self.store
+    }
+
+    /// Consume the wrapper, returning the store. The log is left as is.
+    pub fn into_inner(self) -> ChronoMind {
+        self.store
+    }
+
+    /// Path of the write-ahead log.
+    pub fn log_path(&self) -> &Path {
+        &self.log_path
+    }
+
+    /// Insert or replace a memory (see [`ChronoMind::insert`]) and log it.
+    pub fn insert(&self, memory: Memory) -> Result<()> {
+        let record = encode(&RecordRef::Insert(&memory))?;
+        let mut log = self.lock_log();
+        self.store.insert(memory)?;
+        log.write_all(&record)?;
+        Ok(())
+    }
+
+    /// Remove a memory by id (see [`ChronoMind::remove`]), logging the
+    /// removal if it happened.
+    pub fn remove(&self, id: &str) -> Result<Option<Memory>> {
+        let record = encode(&RecordRef::Remove(id))?;
+        let mut log = self.lock_log();
+        let removed = self.store.remove(id);
+        if removed.is_some() {
+            log.write_all(&record)?;
+        }
+        Ok(removed)
+    }
+
+    /// Flush logged records to stable storage.
+    pub fn sync(&self) -> Result<()> {
+        self.lock_log().sync_data()?;
+        Ok(())
+    }
+
+    /// Write a fresh snapshot of the whole store and start an empty log.
+    ///
+    /// Both files are replaced atomically; a crash between the two leaves
+    /// a log whose snapshot checksum no longer matches, which the next
+    /// open discards.
+    #[instrument(skip(self))]
+    pub fn compact(&mut self) -> Result<()> {
+        save_snapshot(&self.store, &self.snapshot_path)?;
+        let checksum = persistence::snapshot_checksum(&self.snapshot_path)?;
+        *self.log.get_mut().unwrap_or_else(|e| e.into_inner()) =
+            fresh_log(&self.log_path, checksum)?;
+        info!(path = ?self.log_path, "write-ahead log compacted");
+        Ok(())
+    }
+
+    fn lock_log(&self) -> std::sync::MutexGuard<'_, File> {
+        // A panic mid-append leaves at worst a torn record, which replay
+        // truncates; the file handle itself is still usable.
+        self.log.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn log_path(snapshot: &Path) -> PathBuf {
+    let mut path = OsString::from(snapshot.as_os_str());
+    path.push(".wal");
+    PathBuf::from(path)
+}
+
+/// Frame one record: length, checksum, body.
+fn encode(record: &RecordRef<'_>) -> Result<Vec<u8>> {
+    let body = bincode::serialize(record)?;
+    let len = u32::try_from(body.len())
+        .map_err(|_| Error::InvalidArgument("record too large for the write-ahead log".into()))?;
+    let mut framed = Vec::with_capacity(8 + body.len());
+    framed.extend_from_slice(&len.to_le_bytes());
+    framed.extend_from_slice(&crc32fast::hash(&body).to_le_bytes());
+    framed.extend_from_slice(&body);
+    Ok(framed)
+}
+
+/// Atomically replace the log at `path` with an empty one for the snapshot
+/// with body checksum `checksum`, returning it open for appending.
+fn fresh_log(path: &Path, checksum: u32) -> Result<File> {
+    let directory = path.parent().filter(|p| !p.as_os_str().is_empty());
+    let mut temp = match directory {
+        Some(dir) => tempfile::NamedTempFile::new_in(dir)?,
+        None => tempfile::NamedTempFile::new_in(".")?,
+    };
+    temp.write_all(MAGIC)?;
+    temp.write_all(&[FORMAT_VERSION])?;
+    temp.write_all(&checksum.to_le_bytes())?;
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|e| Error::Io(e.error))?;
+    Ok(OpenOptions::new().append(true).open(path)?)
+}
+
+/// Replay the log at `path` into `store`, returning it open for appending,
+/// or `None` when there is no usable log (missing, or written for another
+/// snapshot) and a fresh one is needed.
+fn replay_log(
+    store: &ChronoMind,
+    path: &Path,
+    checksum: u32,
+    replay: bool,
+) -> Result<Option<File>> {
+    if !replay || !path.exists() {
+        return Ok(None);
+    }
+    let mut reader = BufReader::new(File::open(path)?);
+
+    let mut header = [0u8; HEADER_LEN as usize];
+    reader
+        .read_exact(&mut header)
+        .map_err(|_| Error::InvalidSnapshot("write-ahead log header is truncated".into()))?;
+    if &header[..7] != MAGIC {
+        return Err(Error::InvalidSnapshot(
+            "bad magic bytes: not a ChronoMind write-ahead log".into(),
+        ));
+    }
+    if header[7] != FORMAT_VERSION {
+        return Err(Error::InvalidSnapshot(format!(
+            "unsupported write-ahead log version {} (supported: {FORMAT_VERSION})",
+            header[7]
+        )));
+    }
+    let logged_for = u32::from_le_bytes(header[8..12].try_into().expect("4 bytes"));
+    if logged_for != checksum {
+        warn!(
+            ?path,
+            "write-ahead log predates the snapshot; discarding it"
+        );
+        return Ok(None);
+    }
+
+    let mut valid_len = HEADER_LEN;
+    let mut replayed = 0usize;
+    while let Some((record, framed_len)) = read_record(&mut reader)? {
+        match record {
+            Record::Insert(memory) => store.insert(memory)?,
+            Record::Remove(id) => {
+                store.remove(&id);
+            }
+        }
+        valid_len += framed_len;
+        replayed += 1;
+    }
+
+    let log = OpenOptions::new().append(true).open(path)?;
+    if log.metadata()?.len() > valid_len {
+        warn!(?path, valid_len, "truncating torn write-ahead log tail");
+        log.set_len(valid_len)?;
+    }
+    info!(records = replayed, ?path, "write-ahead log replayed");
+    Ok(Some(log))
+}
+
+/// Read one framed record, or `None` at the end of the log or at a torn
+/// or corrupt record (everything from there on is discarded).
+fn read_record(reader: &mut impl Read) -> Result<Option<(Record, u64)>> {
+    let mut frame = [0u8; 8];
+    if reader.read_exact(&mut frame).is_err() {
+        return Ok(None);
+    }
+    let len = u32::from_le_bytes(frame[..4].try_into().expect("4 bytes"));
+    let expected = u32::from_le_bytes(frame[4..].try_into().expect("4 bytes"));
+    let mut body = Vec::new();
+    reader.take(u64::from(len)).read_to_end(&mut body)?;
+    if body.len() != len as usize || crc32fast::hash(&body) != expected {
+        return Ok(None);
+    }
+    Ok(bincode::deserialize(&body)
+        .ok()
+        .map(|record| (record, 8 + u64::from(len))))
+}
@@ -0,0 +1,174 @@
+//! Corpus-fitted whitening for anisotropic embedding spaces.
+//!
+//! Many embedding models produce vectors that crowd into a narrow cone:
+//! a few directions carry most of the variance, so cosine similarity is
+//! dominated by them and the finer neighbor structure is washed out.
+//! Whitening mean-centers the corpus and rescales it along its principal
+//! axes to unit variance (ZCA: `W = Σ^{-1/2}`), after which every direction
+//! counts equally.
+//!
+//! The transform is applied through the metric's
+//! [`preprocess`](crate::DistanceMetric::preprocess) hook, so stored
+//! vectors are whitened once at index time and queries once per search;
+//! see [`ChronoMind::fit_whitening`](crate::ChronoMind::fit_whitening).
+
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::linalg;
+use crate::metric::DistanceMetric;
+
+/// Variance floor added to every eigenvalue before inversion, so directions
+/// the corpus never varies along are not amplified without bound.
+const VARIANCE_FLOOR: f64 = 1e-6;
+
+/// A fitted ZCA whitening transform: `x -> W (x - mean)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Whitening {
+    mean: Vec<f32>,
+    /// `dim x dim`, row-major.
+    matrix: Vec<f32>,
+}
+
+impl Whitening {
+    /// Fit a transform to `vectors`, which must number at least two and all
+    /// share one dimensionality.
+    pub fn fit(vectors: &[&[f32]]) -> Result<Self> {
+        if vectors.len() < 2 {
+            return Err(Error::InvalidArgument(
+                "whitening needs at least two vectors to estimate covariance".into(),
+            ));
+        }
+        let dim = vectors[0].len();
+        if let Some(bad) = vectors.iter().find(|v| v.len() != dim) {
+            return Err(Error::InvalidDimensions {
+                got: bad.len(),
+                expected: dim,
+            });
+        }
+
+        let (mean, covariance) = linalg::mean_and_covariance(vectors, dim);
+        let (values, vectors) = linalg::symmetric_eigen(&covariance, dim);
+
+        // W = V diag(1 / sqrt(λ + floor)) Vᵀ
+        let mut matrix = vec![0.0f64; dim * dim];
+        for (k, &value) in values.iter().enumerate() {
+            let scale = 1.0 / (value.max(0.0) + VARIANCE_FLOOR).sqrt();
+            let axis = &vectors[k * dim..(k + 1) * dim];
+            for i in 0..dim {
+                let a = axis[i] * scale;
+                for j in 0..dim {
+                    matrix[i * dim + j] += a * axis[j];
+                }
+            }
+        }
+
+        Ok(Self {
+            mean: mean.into_iter().map(|m| m as f32).collect(),
+            matrix: matrix.into_iter().map(|w| w as f32).collect(),
+        })
+    }
+
+    /// Dimensionality the transform was fitted for.
+    pub fn dimensions(&self) -> usize {
+        self.mean.len()
+    }
+
+    /// Whiten one vector. Vectors of the wrong length are returned
+    /// unchanged, so the wrapped metric sees — and rejects — them as usual.
+    pub fn apply(&self, v: &[f32]) -> Vec<f32> {
+        let dim = self.mean.len();
+        if v.len() != dim {
+            return v.to_vec();
+        }
+        let centered: Vec<f32> = v.iter().zip(&self.mean).map(|(x, m)| x - m).collect();
+        self.matrix
+            .chunks_exact(dim)
+            .map(|row| row.iter().zip(&centered).map(|(w, x)| w * x).sum())
+            .collect()
+    }
+}
+
+/// A metric that compares whitened vectors with an inner metric.
+pub(crate) struct WhitenedMetric {
+    inner: Arc<dyn DistanceMetric>,
+    whitening: Arc<Whitening>,
+}
+
+impl WhitenedMetric {
+    pub(crate) fn new(inner: Arc<dyn DistanceMetric>, whitening: Arc<Whitening>) -> Self {
+        Self { inner, whitening }
+    }
+}
+
+impl DistanceMetric for WhitenedMetric {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.inner
+            .distance(&self.whitening.apply(a), &self.whitening.apply(b))
+    }
+
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.inner
+            .similarity(&self.whitening.apply(a), &self.whitening.apply(b))
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn preprocess(&self, v: &[f32]) -> Vec<f32> {
+        self.inner.preprocess(&self.whitening.apply(v))
+    }
+
+    fn distance_prepared(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.inner.distance_prepared(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitened_corpus_has_identity_covariance() {
+        let data: Vec<Vec<f32>> = (0..200)
+            .map(|i| {
+                let t = i as f32 * 0.37;
+                let u = i as f32 * 1.13;
+                // Correlated, badly scaled axes.
+                vec![
+                    50.0 * t.sin() + 3.0,
+                    50.0 * t.sin() + 0.5 * u.cos(),
+                    0.2 * u.sin(),
+                ]
+            })
+            .collect();
+        let refs: Vec<&[f32]> = data.iter().map(Vec::as_slice).collect();
+        let whitening = Whitening::fit(&refs).unwrap();
+
+        let whitened: Vec<Vec<f32>> = data.iter().map(|v| whitening.apply(v)).collect();
+        let refs: Vec<&[f32]> = whitened.iter().map(Vec::as_slice).collect();
+        let (mean, cov) = linalg::mean_and_covariance(&refs, 3);
+        for m in mean {
+            assert!(m.abs() < 1e-3);
+        }
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((cov[i * 3 + j] - expected).abs() < 1e-2, "cov[{i}][{j}]");
+            }
+        }
+    }
+
+    #[test]
+    fn fit_rejects_degenerate_input() {
+        assert!(matches!(
+            Whitening::fit(&[&[1.0, 2.0]]),
+            Err(Error::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            Whitening::fit(&[&[1.0, 2.0], &[1.0]]),
+            Err(Error::InvalidDimensions { .. })
+        ));
+    }
+}
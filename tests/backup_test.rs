@@ -0,0 +1,131 @@
+use std::fs;
+
+use chronomind::{
+    backup_incremental, restore_from_dir, ChronoMind, Config, Error, Memory, MemoryAttributes,
+    Vector,
+};
+
+fn store() -> ChronoMind {
+    ChronoMind::new(Config {
+        dimensions: 3,
+        ..Config::default()
+    })
+    .unwrap()
+}
+
+fn memory(id: &str, data: Vec<f32>) -> Memory {
+    Memory::new(
+        Vector::new(id, data),
+        MemoryAttributes {
+            context: "notes".into(),
+            ..MemoryAttributes::default()
+        },
+    )
+}
+
+fn assert_same_contents(a: &ChronoMind, b: &ChronoMind) {
+    let mut left = a.snapshot();
+    let mut right = b.snapshot();
+    left.sort_by(|x, y| x.vector.id.cmp(&y.vector.id));
+    right.sort_by(|x, y| x.vector.id.cmp(&y.vector.id));
+    assert_eq!(left, right);
+}
+
+#[test]
+fn segments_replay_to_the_final_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = store();
+    for i in 0..10 {
+        let x = i as f32;
+        store
+            .insert(memory(&format!("m{i}"), vec![x, 1.0, 0.5]))
+            .unwrap();
+    }
+    assert_eq!(backup_incremental(&store, dir.path()).unwrap(), 10);
+
+    store.insert(memory("m10", vec![0.0, 0.0, 1.0])).unwrap();
+    store.remove("m3").unwrap();
+    store.reinforce("m5", 0.2).unwrap();
+    assert_eq!(backup_incremental(&store, dir.path()).unwrap(), 3);
+    assert_eq!(
+        backup_incremental(&store, dir.path()).unwrap(),
+        0,
+        "nothing changed, nothing written"
+    );
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+
+    let restored = restore_from_dir(dir.path()).unwrap();
+    assert_eq!(restored.config(), store.config());
+    assert!(restored.get("m3").is_none());
+    assert_same_contents(&restored, &store);
+    let results = restored.search(&[0.0, 0.0, 1.0], 1).unwrap();
+    assert_eq!(results[0].0.vector.id, "m10");
+}
+
+#[test]
+fn replayed_memories_replace_rather_than_merge() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = store();
+    store
+        .insert(
+            Memory::builder("a", vec![1.0, 0.0, 0.0])
+                .relationships(["b"])
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+    store.insert(memory("b", vec![0.0, 1.0, 0.0])).unwrap();
+    backup_incremental(&store, dir.path()).unwrap();
+
+    // Reinserted without its link: a replay that merged into the earlier
+    // segment's record would bring the link back.
+    store.remove("a");
+    store.insert(memory("a", vec![1.0, 0.0, 0.0])).unwrap();
+    store.rename_context("notes", "archive").unwrap();
+    backup_incremental(&store, dir.path()).unwrap();
+
+    let restored = restore_from_dir(dir.path()).unwrap();
+    assert_same_contents(&restored, &store);
+    let a = restored.get("a").unwrap();
+    assert!(a.attributes.relationships.is_empty());
+    assert_eq!(a.attributes.context, "archive");
+}
+
+#[test]
+fn a_new_directory_starts_with_a_full_segment() {
+    let (first, second) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let store = store();
+    store.insert(memory("a", vec![1.0, 0.0, 0.0])).unwrap();
+    backup_incremental(&store, first.path()).unwrap();
+    store.insert(memory("b", vec![0.0, 1.0, 0.0])).unwrap();
+
+    assert_eq!(backup_incremental(&store, second.path()).unwrap(), 2);
+    assert_same_contents(&restore_from_dir(second.path()).unwrap(), &store);
+}
+
+#[test]
+fn restoring_requires_intact_segments() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        restore_from_dir(dir.path()),
+        Err(Error::InvalidSnapshot(_))
+    ));
+
+    let store = store();
+    store.insert(memory("a", vec![1.0, 0.0, 0.0])).unwrap();
+    backup_incremental(&store, dir.path()).unwrap();
+    let segment = fs::read_dir(dir.path())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let mut bytes = fs::read(&segment).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    fs::write(&segment, bytes).unwrap();
+    assert!(matches!(
+        restore_from_dir(dir.path()),
+        Err(Error::InvalidSnapshot(_))
+    ));
+}
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::PathBuf;
+
+use chronomind::{Config, EntryStrategy, Error, Precision};
+
+fn write(dir: &tempfile::TempDir, name: &str, contents: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn expected() -> Config {
+    let mut config = Config {
+        dimensions: 384,
+        temporal_weight: 0.5,
+        precision: Precision::F16,
+        ..Config::default()
+    };
+    config.index.ef_search = 100;
+    config.index.entry_strategy = EntryStrategy::MostRecent;
+    config
+}
+
+#[test]
+fn partial_files_fill_in_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    let toml = write(
+        &dir,
+        "store.toml",
+        r#"
+dimensions = 384
+temporal_weight = 0.5
+precision = "F16"
+
+[index]
+ef_search = 100
+entry_strategy = "MostRecent"
+"#,
+    );
+    assert_eq!(Config::from_toml_path(&toml).unwrap(), expected());
+
+    let yaml = write(
+        &dir,
+        "store.yaml",
+        "dimensions: 384\ntemporal_weight: 0.5\nprecision: F16\nindex:\n  ef_search: 100\n  entry_strategy: MostRecent\n",
+    );
+    assert_eq!(Config::from_yaml_path(&yaml).unwrap(), expected());
+}
+
+#[test]
+fn out_of_range_fields_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let toml = write(&dir, "store.toml", "temporal_weight = 1.5\n");
+    let yaml = write(&dir, "store.yaml", "index:\n  ef_search: 0\n");
+    for result in [Config::from_toml_path(&toml), Config::from_yaml_path(&yaml)] {
+        match result {
+            Err(Error::Config(message)) => assert!(
+                message.contains("temporal_weight") || message.contains("ef_search"),
+                "{message}"
+            ),
+            other => panic!("expected a config error, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn malformed_files_are_config_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let toml = write(&dir, "store.toml", "dimensions = = 3\n");
+    let mistyped = write(&dir, "typed.toml", "dimensions = \"many\"\n");
+    let yaml = write(&dir, "store.yaml", "dimensions: [3\n");
+    for result in [
+        Config::from_toml_path(&toml),
+        Config::from_toml_path(&mistyped),
+        Config::from_yaml_path(&yaml),
+    ] {
+        assert!(matches!(result, Err(Error::Config(_))), "{result:?}");
+    }
+    assert!(matches!(
+        Config::from_toml_path(&dir.path().join("missing.toml")),
+        Err(Error::Io(_))
+    ));
+}
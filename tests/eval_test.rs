@@ -0,0 +1,101 @@
+use chronomind::config::IndexParams;
+use chronomind::eval::recall_at_k;
+use chronomind::{ChronoMind, Config, Error, Memory, Vector};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn random_vectors(n: usize, dim: usize, seed: u64) -> Vec<Vec<f32>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect()
+}
+
+#[test]
+fn exhaustive_index_has_perfect_recall() {
+    // ef_search covers the whole store, so every search visits every node.
+    let mut config = Config::builder().dimensions(16).build().unwrap();
+    config.index.ef_search = 200;
+    let store = ChronoMind::new(config).unwrap();
+    for (i, v) in random_vectors(100, 16, 1).into_iter().enumerate() {
+        store
+            .insert(Memory::from_vector(Vector::new(format!("m{i}"), v)))
+            .unwrap();
+    }
+
+    let queries = random_vectors(10, 16, 2);
+    assert_eq!(recall_at_k(&store, &queries, 10).unwrap(), 1.0);
+    // k beyond the store size scores against everything there is.
+    assert_eq!(recall_at_k(&store, &queries[..1], 500).unwrap(), 1.0);
+}
+
+#[test]
+fn invalid_evaluations_are_rejected() {
+    let store = ChronoMind::new(Config::builder().dimensions(4).build().unwrap()).unwrap();
+    let queries = random_vectors(2, 4, 3);
+    assert_eq!(recall_at_k(&store, &queries, 5).unwrap(), 1.0);
+    assert!(matches!(
+        recall_at_k(&store, &queries, 0),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        recall_at_k(&store, &[], 5),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        recall_at_k(&store, &[vec![1.0; 3]], 5),
+        Err(Error::QueryDimensionMismatch { .. })
+    ));
+}
+
+#[test]
+fn reindexing_with_more_connections_does_not_lose_recall() {
+    let mut config = Config::builder().dimensions(32).build().unwrap();
+    config.index = IndexParams {
+        max_connections: 2,
+        ef_construction: 8,
+        ef_search: 10,
+        seed: Some(5),
+        ..IndexParams::default()
+    };
+    let mut store = ChronoMind::new(config).unwrap();
+    for (i, v) in random_vectors(1_000, 32, 4).into_iter().enumerate() {
+        store
+            .insert(Memory::from_vector(Vector::new(format!("m{i}"), v)))
+            .unwrap();
+    }
+    let queries = random_vectors(20, 32, 5);
+    let before = recall_at_k(&store, &queries, 10).unwrap();
+
+    let tuned = IndexParams {
+        max_connections: 24,
+        ef_construction: 200,
+        ..store.config().index.clone()
+    };
+    store.reindex(tuned.clone()).unwrap();
+
+    assert_eq!(store.len(), 1_000);
+    assert_eq!(store.config().index, tuned);
+    assert!(store.get("m7").is_some());
+    let after = recall_at_k(&store, &queries, 10).unwrap();
+    assert!(after >= before, "recall fell from {before} to {after}");
+}
+
+#[test]
+fn invalid_reindex_leaves_the_store_unchanged() {
+    let mut store = ChronoMind::new(Config::builder().dimensions(4).build().unwrap()).unwrap();
+    store
+        .insert(Memory::from_vector(Vector::new(
+            "a",
+            vec![1.0, 0.0, 0.0, 0.0],
+        )))
+        .unwrap();
+    let bad = IndexParams {
+        max_connections: 0,
+        ..IndexParams::default()
+    };
+    assert!(matches!(store.reindex(bad), Err(Error::Config(_))));
+    assert_eq!(store.config().index, IndexParams::default());
+    assert_eq!(store.len(), 1);
+}
@@ -0,0 +1,167 @@
+//! Drives the C ABI end to end, the way a foreign caller would: raw
+//! pointers, caller-owned buffers, status codes, explicit frees.
+
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use chronomind::ffi::*;
+use chronomind::ChronoMind;
+
+fn init(dimensions: usize) -> *mut ChronoMind {
+    let mut store = ptr::null_mut();
+    assert_eq!(
+        unsafe { chrono_mind_init(dimensions, &mut store) },
+        CHRONO_MIND_OK
+    );
+    assert!(!store.is_null());
+    store
+}
+
+fn save(store: *mut ChronoMind, id: &str, vector: &[f32]) -> i32 {
+    let id = CString::new(id).unwrap();
+    unsafe { chrono_mind_save(store, id.as_ptr(), vector.as_ptr(), vector.len()) }
+}
+
+/// Search, copy the results out, and free every returned id.
+fn search(store: *mut ChronoMind, query: &[f32], k: usize) -> Result<Vec<(String, f32)>, i32> {
+    let mut ids: Vec<*mut c_char> = vec![ptr::null_mut(); k];
+    let mut scores = vec![0.0f32; k];
+    let mut count = usize::MAX;
+    let status = unsafe {
+        chrono_mind_search(
+            store,
+            query.as_ptr(),
+            query.len(),
+            k,
+            ids.as_mut_ptr(),
+            scores.as_mut_ptr(),
+            &mut count,
+        )
+    };
+    if status != CHRONO_MIND_OK {
+        assert_eq!(count, usize::MAX, "outputs are untouched on failure");
+        return Err(status);
+    }
+    let results = ids[..count]
+        .iter()
+        .zip(&scores)
+        .map(|(&id, &score)| {
+            let owned = unsafe { CStr::from_ptr(id) }.to_str().unwrap().to_owned();
+            unsafe { chrono_mind_free_string(id) };
+            (owned, score)
+        })
+        .collect();
+    Ok(results)
+}
+
+#[test]
+fn save_search_free_round_trip() {
+    let store = init(3);
+    assert_eq!(save(store, "x", &[1.0, 0.0, 0.0]), CHRONO_MIND_OK);
+    assert_eq!(save(store, "y", &[0.0, 1.0, 0.0]), CHRONO_MIND_OK);
+    assert_eq!(save(store, "z", &[0.0, 0.0, 1.0]), CHRONO_MIND_OK);
+
+    let results = search(store, &[0.9, 0.1, 0.0], 2).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, "x");
+    assert!(results[0].1 <= results[1].1);
+
+    // k beyond the store size writes only what exists.
+    assert_eq!(search(store, &[1.0, 0.0, 0.0], 10).unwrap().len(), 3);
+    unsafe { chrono_mind_free(store) };
+}
+
+#[test]
+fn errors_map_to_status_codes() {
+    let store = init(3);
+    assert_eq!(
+        save(store, "short", &[1.0, 0.0]),
+        CHRONO_MIND_DIMENSION_MISMATCH
+    );
+    assert_eq!(
+        save(store, "nan", &[f32::NAN, 0.0, 0.0]),
+        CHRONO_MIND_INVALID_VECTOR
+    );
+    assert_eq!(
+        search(store, &[1.0], 1).unwrap_err(),
+        CHRONO_MIND_DIMENSION_MISMATCH
+    );
+
+    let bad_utf8 = [0xffu8, 0];
+    let vector = [1.0f32, 0.0, 0.0];
+    let status = unsafe {
+        chrono_mind_save(
+            store,
+            bad_utf8.as_ptr().cast(),
+            vector.as_ptr(),
+            vector.len(),
+        )
+    };
+    assert_eq!(status, CHRONO_MIND_INVALID_UTF8);
+
+    let mut out = ptr::null_mut();
+    assert_eq!(
+        unsafe { chrono_mind_init(0, &mut out) },
+        CHRONO_MIND_INVALID_ARGUMENT
+    );
+    assert!(out.is_null(), "no handle is produced on failure");
+    unsafe { chrono_mind_free(store) };
+}
+
+#[test]
+fn null_pointers_are_rejected_and_frees_tolerate_null() {
+    assert_eq!(
+        unsafe { chrono_mind_init(3, ptr::null_mut()) },
+        CHRONO_MIND_NULL_POINTER
+    );
+    let vector = [1.0f32, 0.0, 0.0];
+    let id = CString::new("a").unwrap();
+    assert_eq!(
+        unsafe { chrono_mind_save(ptr::null_mut(), id.as_ptr(), vector.as_ptr(), 3) },
+        CHRONO_MIND_NULL_POINTER
+    );
+    let store = init(3);
+    assert_eq!(
+        unsafe { chrono_mind_save(store, ptr::null(), vector.as_ptr(), 3) },
+        CHRONO_MIND_NULL_POINTER
+    );
+    let mut count = 0;
+    assert_eq!(
+        unsafe {
+            chrono_mind_search(
+                store,
+                vector.as_ptr(),
+                3,
+                1,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut count,
+            )
+        },
+        CHRONO_MIND_NULL_POINTER
+    );
+
+    unsafe {
+        chrono_mind_free_string(ptr::null_mut());
+        chrono_mind_free(ptr::null_mut());
+        chrono_mind_free(store);
+    }
+}
+
+#[test]
+fn many_handles_are_created_and_freed() {
+    // Every handle and returned id is released exactly once; under Miri or
+    // a leak checker this loop reports any imbalance.
+    for round in 0..20 {
+        let store = init(2);
+        for i in 0..10 {
+            let angle = (round * 10 + i) as f32 * 0.1;
+            assert_eq!(
+                save(store, &format!("m{i}"), &[angle.cos(), angle.sin()]),
+                CHRONO_MIND_OK
+            );
+        }
+        assert_eq!(search(store, &[1.0, 0.0], 5).unwrap().len(), 5);
+        unsafe { chrono_mind_free(store) };
+    }
+}
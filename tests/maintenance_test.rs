@@ -0,0 +1,73 @@
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chronomind::{
+    ChronoMind, Config, MaintenanceSchedule, MaintenanceScheduler, Memory, MemoryAttributes, Vector,
+};
+
+fn memory(id: &str, data: Vec<f32>, importance: f32) -> Memory {
+    Memory::new(
+        Vector::new(id, data),
+        MemoryAttributes {
+            importance,
+            ..MemoryAttributes::default()
+        },
+    )
+}
+
+#[test]
+fn scheduled_passes_run_in_the_background_until_shutdown() {
+    let store = ChronoMind::new(Config {
+        dimensions: 2,
+        ..Config::default()
+    })
+    .unwrap();
+    store.insert(memory("faint", vec![1.0, 0.0], 0.05)).unwrap();
+    store.insert(memory("strong", vec![0.0, 1.0], 0.9)).unwrap();
+    let store = Arc::new(RwLock::new(store));
+
+    let scheduler = MaintenanceScheduler::start(
+        Arc::clone(&store),
+        MaintenanceSchedule {
+            decay_interval: Some(Duration::from_millis(5)),
+            prune_interval: Some(Duration::from_millis(10)),
+            prune_threshold: 0.1,
+            ..MaintenanceSchedule::default()
+        },
+    )
+    .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while store.read().unwrap().get("faint").is_some() {
+        assert!(Instant::now() < deadline, "prune pass never ran");
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(store.read().unwrap().get("strong").is_some());
+
+    let stopping = Instant::now();
+    scheduler.shutdown();
+    assert!(
+        stopping.elapsed() < Duration::from_secs(5),
+        "shutdown waits on no timer"
+    );
+
+    // Nothing runs after shutdown.
+    store
+        .read()
+        .unwrap()
+        .insert(memory("later", vec![1.0, 1.0], 0.05))
+        .unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert!(store.read().unwrap().get("later").is_some());
+}
+
+#[test]
+fn invalid_schedules_never_start() {
+    let store = Arc::new(RwLock::new(ChronoMind::new(Config::default()).unwrap()));
+    let schedule = MaintenanceSchedule {
+        consolidate_interval: Some(Duration::ZERO),
+        ..MaintenanceSchedule::default()
+    };
+    assert!(MaintenanceScheduler::start(store, schedule).is_err());
+}
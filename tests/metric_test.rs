@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use chronomind::metric::{check_metric_consistency, metric_from_name, MetricRegistry};
+use chronomind::{
+    BuiltinMetric, ChronoMind, CompositeDistance, Config, CosineDistance, DistanceMetric, Error,
+    Memory, Vector,
+};
+
+/// Distance by first component only.
+struct FirstAxis;
+
+impl DistanceMetric for FirstAxis {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        (a[0] - b[0]).abs()
+    }
+
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        1.0 / (1.0 + self.distance(a, b))
+    }
+
+    fn name(&self) -> &'static str {
+        "first-axis"
+    }
+}
+
+#[test]
+fn registered_metric_backs_a_store() {
+    MetricRegistry::global().register("first-axis", Box::new(|| Arc::new(FirstAxis)));
+    assert!(MetricRegistry::global()
+        .names()
+        .contains(&"first-axis".to_string()));
+
+    let metric = metric_from_name("first-axis").unwrap();
+    assert_eq!(metric.name(), "first-axis");
+    let store =
+        ChronoMind::with_metric(Config::builder().dimensions(2).build().unwrap(), metric).unwrap();
+    store
+        .insert(Memory::from_vector(Vector::new("near", vec![1.0, 100.0])))
+        .unwrap();
+    store
+        .insert(Memory::from_vector(Vector::new("far", vec![5.0, 0.0])))
+        .unwrap();
+
+    // Under cosine "far" would win; the custom metric ignores the second axis.
+    let results = store.search(&[1.5, 0.0], 1).unwrap();
+    assert_eq!(results[0].0.vector.id, "near");
+}
+
+/// Negated inner product: larger magnitudes along the query rank closer.
+struct NegativeDot;
+
+impl DistanceMetric for NegativeDot {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        -self.similarity(a, b)
+    }
+
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    fn name(&self) -> &'static str {
+        "negative-dot"
+    }
+}
+
+#[test]
+fn stores_do_not_normalize_behind_a_magnitude_metric() {
+    // Only the metric's own preprocess step normalizes; a metric that
+    // keeps the default sees the vectors exactly as inserted.
+    let store = ChronoMind::with_metric(
+        Config::builder().dimensions(2).build().unwrap(),
+        Arc::new(NegativeDot),
+    )
+    .unwrap();
+    store
+        .insert(Memory::from_vector(Vector::new("unit", vec![1.0, 0.0])))
+        .unwrap();
+    store
+        .insert(Memory::from_vector(Vector::new("large", vec![3.0, 0.5])))
+        .unwrap();
+
+    let results = store.search(&[1.0, 0.0], 2).unwrap();
+    let ids: Vec<&str> = results.iter().map(|(m, _)| m.vector.id.as_str()).collect();
+    assert_eq!(ids, ["large", "unit"]);
+    assert_eq!(store.get("large").unwrap().vector.data, [3.0, 0.5]);
+}
+
+#[test]
+fn builtins_are_registered_and_unknown_names_rejected() {
+    let registry = MetricRegistry::new();
+    assert_eq!(registry.create("cosine").unwrap().name(), "cosine");
+    assert!(registry.create("nope").is_none());
+    assert!(matches!(
+        metric_from_name("nope"),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+/// Random unit vectors.
+fn unit_samples(n: usize, dim: usize) -> Vec<Vec<f32>> {
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+    (0..n)
+        .map(|_| {
+            let v: Vec<f32> = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+            v.into_iter().map(|x| x / norm).collect()
+        })
+        .collect()
+}
+
+#[test]
+fn builtin_metrics_are_consistent_on_unit_vectors() {
+    let samples = unit_samples(24, 16);
+    for builtin in BuiltinMetric::ALL {
+        check_metric_consistency(builtin.build().as_ref(), &samples)
+            .unwrap_or_else(|e| panic!("{builtin:?}: {e}"));
+    }
+    let blend = CompositeDistance::new(vec![
+        (BuiltinMetric::Cosine.build(), 1.0),
+        (BuiltinMetric::Euclidean.build(), 0.5),
+    ])
+    .unwrap();
+    check_metric_consistency(&blend, &samples).unwrap();
+}
+
+#[test]
+fn cosine_and_dot_product_agree_on_unit_vectors() {
+    let samples = unit_samples(24, 16);
+    let cosine = CosineDistance::new();
+    let euclidean = BuiltinMetric::Euclidean.build();
+    let query = &samples[0];
+    for v in &samples {
+        let dot: f32 = query.iter().zip(v).map(|(a, b)| a * b).sum();
+        assert!((cosine.distance(query, v) - (1.0 - dot)).abs() < 1e-5);
+        assert!((cosine.similarity(query, v) - dot).abs() < 1e-5);
+        // |a - b|² = 2 - 2·dot: Euclidean ranks unit vectors like cosine.
+        let d = euclidean.distance(query, v);
+        assert!((d * d - 2.0 * (1.0 - dot)).abs() < 1e-4);
+    }
+}
+
+/// Distance by first component, with similarity backwards.
+struct Inverted;
+
+impl DistanceMetric for Inverted {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        (a[0] - b[0]).abs()
+    }
+
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        self.distance(a, b)
+    }
+
+    fn name(&self) -> &'static str {
+        "inverted"
+    }
+}
+
+#[test]
+fn inconsistent_metrics_are_reported() {
+    let samples = unit_samples(8, 4);
+    match check_metric_consistency(&Inverted, &samples) {
+        Err(Error::InvalidArgument(message)) => {
+            assert!(message.contains("less similar"), "{message}")
+        }
+        other => panic!("expected an inconsistency, got {other:?}"),
+    }
+    assert!(check_metric_consistency(&FirstAxis, &samples).is_ok());
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chronomind::{ChronoMind, Config, Memory, MetricsRegistry};
+
+/// The value of the sample line starting with `series`.
+fn sample(output: &str, series: &str) -> f64 {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix(series))
+        .and_then(|rest| rest.trim().parse().ok())
+        .unwrap_or_else(|| panic!("no sample for {series} in:\n{output}"))
+}
+
+#[test]
+fn recorded_operations_render_as_prometheus_text() {
+    let registry = MetricsRegistry::new();
+    for micros in [80, 300, 2_000] {
+        registry.record_operation_duration("search", "", Duration::from_micros(micros));
+    }
+    registry.record_operation_duration("insert", "notes", Duration::from_secs(3));
+    assert_eq!(registry.operation_count("search", ""), 3);
+    assert_eq!(registry.operation_count("search", "notes"), 0);
+
+    let out = registry.render_prometheus();
+    assert!(out.contains("# TYPE chronomind_operations_total counter"));
+    assert!(out.contains("# TYPE chronomind_operation_duration_seconds histogram"));
+    assert_eq!(
+        sample(
+            &out,
+            r#"chronomind_operations_total{operation="search",context=""}"#
+        ),
+        3.0
+    );
+    assert_eq!(
+        sample(
+            &out,
+            r#"chronomind_operation_duration_seconds_bucket{operation="search",context="",le="0.0005"}"#
+        ),
+        2.0
+    );
+    let sum = sample(
+        &out,
+        r#"chronomind_operation_duration_seconds_sum{operation="search",context=""}"#,
+    );
+    assert!((sum - 0.00238).abs() < 1e-9, "sum {sum}");
+    // Slower than every finite bucket: only +Inf counts it.
+    assert_eq!(
+        sample(
+            &out,
+            r#"chronomind_operation_duration_seconds_bucket{operation="insert",context="notes",le="1"}"#
+        ),
+        0.0
+    );
+    assert_eq!(
+        sample(
+            &out,
+            r#"chronomind_operation_duration_seconds_bucket{operation="insert",context="notes",le="+Inf"}"#
+        ),
+        1.0
+    );
+}
+
+#[test]
+fn label_values_are_escaped() {
+    let registry = MetricsRegistry::new();
+    registry.record_operation_duration("search", "say \"hi\"\\\n", Duration::ZERO);
+    let out = registry.render_prometheus();
+    assert!(out.contains(r#"context="say \"hi\"\\\n""#), "{out}");
+}
+
+#[test]
+fn a_store_records_its_search_latencies() {
+    let registry = Arc::new(MetricsRegistry::new());
+    let store = ChronoMind::new(Config {
+        dimensions: 2,
+        ..Config::default()
+    })
+    .unwrap()
+    .with_metrics(Arc::clone(&registry));
+    for (i, context) in ["notes", "notes", "chat"].into_iter().enumerate() {
+        let memory = Memory::builder(format!("m{i}"), vec![1.0, i as f32])
+            .context(context)
+            .build()
+            .unwrap();
+        store.insert(memory).unwrap();
+    }
+
+    for _ in 0..4 {
+        store.search(&[1.0, 0.0], 2).unwrap();
+    }
+    store.search_detailed(&[1.0, 0.0], 1).unwrap();
+    store.search_in_context("notes", &[1.0, 0.0], 1).unwrap();
+    assert!(
+        store.search(&[1.0], 1).is_err(),
+        "rejected queries are not timed"
+    );
+
+    assert_eq!(registry.operation_count("search", ""), 5);
+    assert_eq!(registry.operation_count("search", "notes"), 1);
+    let out = registry.render_prometheus();
+    assert_eq!(
+        sample(
+            &out,
+            r#"chronomind_operation_duration_seconds_count{operation="search",context=""}"#
+        ),
+        5.0
+    );
+    assert_eq!(
+        sample(
+            &out,
+            r#"chronomind_operation_duration_seconds_bucket{operation="search",context="",le="+Inf"}"#
+        ),
+        5.0
+    );
+}
+
+#[test]
+fn a_feedback_search_records_one_sample() {
+    let registry = Arc::new(MetricsRegistry::new());
+    let store = ChronoMind::new(Config {
+        dimensions: 2,
+        ..Config::default()
+    })
+    .unwrap()
+    .with_metrics(Arc::clone(&registry));
+    for i in 0..5 {
+        let memory = Memory::builder(format!("m{i}"), vec![1.0, i as f32 * 0.1])
+            .build()
+            .unwrap();
+        store.insert(memory).unwrap();
+    }
+
+    // Two passes through the index, one search.
+    store.search_with_feedback(&[1.0, 0.0], 2, 3, 0.5).unwrap();
+    assert_eq!(registry.operation_count("search", ""), 1);
+    store.search_with_feedback(&[1.0, 0.0], 2, 0, 0.5).unwrap();
+    assert_eq!(registry.operation_count("search", ""), 2);
+}
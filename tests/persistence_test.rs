@@ -1,8 +1,11 @@
 use std::fs;
 use std::io::Write;
+use std::sync::Arc;
 
 use chronomind::{
-    load_snapshot, save_snapshot, ChronoMind, Config, Error, Memory, MemoryAttributes, Vector,
+    export_ndjson, import_ndjson, import_ndjson_file, load_snapshot, load_snapshot_with_metric,
+    save_snapshot, save_snapshot_compressed, save_snapshot_pruned, ChronoMind, Config,
+    DistanceMetric, Error, Memory, MemoryAttributes, Vector, IMPORT_PROGRESS_INTERVAL,
 };
 
 fn sample_store() -> ChronoMind {
@@ -55,6 +58,67 @@ fn loaded_store_is_searchable() {
     assert_eq!(results[0].0.vector.id, "m0");
 }
 
+#[test]
+fn pruned_snapshot_leaves_out_unimportant_memories() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pruned.chrono");
+    let store = sample_store();
+    let linked = Memory::builder("linked", vec![1.0, 0.0, 0.0, 0.0])
+        .importance(0.9)
+        .weighted_relationships([("m3", 0.5), ("m12", 0.7)])
+        .build()
+        .unwrap();
+    store.insert(linked).unwrap();
+
+    // Importance is i / 20: m10 through m19 clear the bar, plus "linked".
+    assert_eq!(save_snapshot_pruned(&store, &path, 0.5).unwrap(), 11);
+    let loaded = load_snapshot(&path).unwrap();
+    assert_eq!(loaded.len(), 11);
+    assert!(loaded.len() < store.len());
+    assert!(loaded.get("m9").is_none());
+    assert_eq!(loaded.get("m10"), store.get("m10"));
+    let links = loaded.get("linked").unwrap().attributes;
+    assert_eq!(links.relationships, ["m12"]);
+    assert_eq!(links.relationship_strengths, [0.7]);
+
+    // The store itself is untouched.
+    assert_eq!(store.len(), 21);
+    assert!(matches!(
+        save_snapshot_pruned(&store, &path, f32::NAN),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn read_only_snapshot_serves_concurrent_searches() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("test.chrono");
+    save_snapshot(&sample_store(), &path).unwrap();
+
+    let store = Arc::new(load_snapshot(&path).unwrap().into_read_only());
+    let expected = store.search(&[5.0, 6.0, 7.0, 8.0], 3).unwrap();
+    std::thread::scope(|s| {
+        for _ in 0..2 {
+            let (store, expected) = (Arc::clone(&store), &expected);
+            s.spawn(move || {
+                for _ in 0..200 {
+                    let results = store.search(&[5.0, 6.0, 7.0, 8.0], 3).unwrap();
+                    let ids: Vec<&str> =
+                        results.iter().map(|(m, _)| m.vector.id.as_str()).collect();
+                    let want: Vec<&str> =
+                        expected.iter().map(|(m, _)| m.vector.id.as_str()).collect();
+                    assert_eq!(ids, want);
+                    assert_eq!(store.get("m5").unwrap().vector.data, [5.0, 6.0, 7.0, 8.0]);
+                }
+            });
+        }
+    });
+    assert!(matches!(
+        store.insert(Memory::from_vector(Vector::new("new", vec![0.0; 4]))),
+        Err(Error::ReadOnly)
+    ));
+}
+
 #[test]
 fn bad_magic_is_rejected() {
     let dir = tempfile::tempdir().unwrap();
@@ -91,6 +155,41 @@ fn unsupported_version_is_rejected() {
     let err = load_snapshot(&path).unwrap_err();
     assert!(matches!(err, Error::InvalidSnapshot(_)));
     assert!(err.to_string().contains("99"));
+
+    // Versions older than any migration are just as unreadable.
+    fs::write(&path, b"CHRONO1\x01").unwrap();
+    let err = load_snapshot(&path).unwrap_err();
+    assert!(err.to_string().contains("unsupported format version 1"));
+}
+
+#[test]
+fn version_2_snapshot_is_migrated() {
+    // Written by chronomind 0.2.5.
+    let path =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshot_v2.chrono");
+    let store = load_snapshot(&path).unwrap();
+
+    let config = store.config();
+    assert_eq!(config.dimensions, 3);
+    assert_eq!(config.max_memories, 1000);
+    assert_eq!(config.temporal_weight, 0.25);
+    assert_eq!(config.index.max_connections, 8);
+    assert_eq!(config.index.ef_construction, 32);
+    assert_eq!(config.index.ef_search, 24);
+    // Fields added since version 2 take their defaults.
+    assert_eq!(config.index.max_nodes_visited, None);
+    assert_eq!(config.score_bands, Default::default());
+
+    assert_eq!(store.len(), 3);
+    let m2 = store.get("m2").unwrap();
+    assert_eq!(m2.vector.data, [2.0, 1.0, 0.5]);
+    assert_eq!(m2.attributes.importance, 0.75);
+    assert_eq!(m2.attributes.context, "legacy");
+    assert_eq!(m2.attributes.relationships, ["m0"]);
+    assert_eq!(
+        store.search(&[2.0, 1.0, 0.5], 1).unwrap()[0].0.vector.id,
+        "m2"
+    );
 }
 
 #[test]
@@ -141,6 +240,73 @@ fn save_replaces_existing_snapshot_atomically() {
     assert!(loaded.get("only").is_some());
 }
 
+#[test]
+fn interrupted_save_leaves_the_previous_snapshot_intact() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store.chrono");
+    save_snapshot(&sample_store(), &path).unwrap();
+    let original = fs::read(&path).unwrap();
+
+    // A crash mid-save leaves only a partial temp file beside the target;
+    // the target itself is never opened for writing.
+    let partial = dir.path().join(".tmpCRASH");
+    fs::write(&partial, &original[..original.len() / 2]).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), original);
+    assert_eq!(load_snapshot(&path).unwrap().len(), 20);
+
+    // A save that fails before the rename does not touch the target.
+    let unwritable = dir.path().join("missing-dir").join("store.chrono");
+    assert!(matches!(
+        save_snapshot(&sample_store(), &unwritable),
+        Err(Error::Io(_))
+    ));
+    assert_eq!(fs::read(&path).unwrap(), original);
+
+    // The stray temp file does not get in the way of the next save.
+    save_snapshot(&sample_store(), &path).unwrap();
+    assert_eq!(load_snapshot(&path).unwrap().len(), 20);
+}
+
+#[test]
+fn binary_snapshot_is_smaller_than_json() {
+    use chronomind::IndexParams;
+
+    let store = ChronoMind::new(Config {
+        dimensions: 16,
+        // Cheap construction: this test is about the file, not the graph.
+        index: IndexParams {
+            max_connections: 4,
+            ef_construction: 8,
+            ..IndexParams::default()
+        },
+        ..Config::default()
+    })
+    .unwrap();
+    for i in 0..10_000 {
+        let data = (0..16)
+            .map(|j| ((i * 16 + j) as f32 * 0.37).sin())
+            .collect();
+        store
+            .insert(Memory::from_vector(Vector::new(format!("m{i}"), data)))
+            .unwrap();
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("large.chrono");
+    save_snapshot(&store, &path).unwrap();
+    let json = serde_json::to_vec(&store.snapshot()).unwrap();
+    let binary = fs::metadata(&path).unwrap().len() as usize;
+    assert!(
+        binary < json.len(),
+        "binary {binary} >= json {}",
+        json.len()
+    );
+
+    let loaded = load_snapshot(&path).unwrap();
+    assert_eq!(loaded.len(), 10_000);
+    assert_eq!(loaded.get("m1234"), store.get("m1234"));
+}
+
 #[test]
 fn missing_file_is_io_error() {
     assert!(matches!(
@@ -148,3 +314,323 @@ fn missing_file_is_io_error() {
         Err(Error::Io(_))
     ));
 }
+
+/// Plain Euclidean distance, to prove the restored index uses the metric it
+/// was handed rather than the cosine default.
+struct Euclidean;
+
+impl DistanceMetric for Euclidean {
+    fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y) * (x - y))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        1.0 / (1.0 + self.distance(a, b))
+    }
+
+    fn name(&self) -> &'static str {
+        "euclidean"
+    }
+}
+
+#[test]
+fn custom_metric_roundtrip_answers_identically() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("euclidean.chrono");
+
+    let store = ChronoMind::with_metric(
+        Config {
+            dimensions: 3,
+            temporal_weight: 0.0,
+            ..Config::default()
+        },
+        Arc::new(Euclidean),
+    )
+    .unwrap();
+    for i in 0..50 {
+        let x = i as f32;
+        store
+            .insert(Memory::new(
+                Vector::new(format!("m{i}"), vec![x, 2.0 * x, 0.5]),
+                MemoryAttributes {
+                    relationships: vec![format!("m{}", (i + 1) % 50), format!("m{}", (i + 7) % 50)],
+                    ..MemoryAttributes::default()
+                },
+            ))
+            .unwrap();
+    }
+
+    save_snapshot(&store, &path).unwrap();
+    let loaded = load_snapshot_with_metric(&path, Arc::new(Euclidean)).unwrap();
+
+    assert_eq!(loaded.len(), 50);
+    for original in store.snapshot() {
+        assert_eq!(loaded.get(&original.vector.id).unwrap(), original);
+    }
+    // All vectors share a direction, so cosine would tie them; Euclidean
+    // ranks by magnitude and must agree with the original store exactly.
+    let query = [10.2, 20.4, 0.5];
+    let ids = |s: &ChronoMind| -> Vec<String> {
+        s.search(&query, 10)
+            .unwrap()
+            .into_iter()
+            .map(|(m, _)| m.vector.id)
+            .collect()
+    };
+    assert_eq!(ids(&loaded), ids(&store));
+    assert_eq!(ids(&loaded)[0], "m10");
+}
+
+#[test]
+fn seeded_stores_are_reproducible_end_to_end() {
+    use chronomind::IndexParams;
+    use std::time::{Duration, SystemTime};
+
+    let dir = tempfile::tempdir().unwrap();
+    let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let build = || {
+        let store = ChronoMind::new(Config {
+            dimensions: 4,
+            index: IndexParams {
+                seed: Some(42),
+                ..IndexParams::default()
+            },
+            ..Config::default()
+        })
+        .unwrap();
+        for i in 0..200 {
+            let x = i as f32 * 0.1;
+            store
+                .insert(Memory::new(
+                    Vector::new(format!("m{i}"), vec![x.sin(), x.cos(), x, 1.0]),
+                    MemoryAttributes {
+                        timestamp: epoch + Duration::from_secs(i),
+                        last_access: epoch + Duration::from_secs(i),
+                        ..MemoryAttributes::default()
+                    },
+                ))
+                .unwrap();
+        }
+        store.remove("m7");
+        store
+    };
+
+    let (a, b) = (build(), build());
+    let (path_a, path_b) = (dir.path().join("a.chrono"), dir.path().join("b.chrono"));
+    save_snapshot(&a, &path_a).unwrap();
+    save_snapshot(&b, &path_b).unwrap();
+    assert_eq!(fs::read(&path_a).unwrap(), fs::read(&path_b).unwrap());
+
+    // Same seed, same insertion order: same graph, same answers.
+    assert_eq!(a.index_stats(), b.index_stats());
+    let ids = |store: &ChronoMind| -> Vec<String> {
+        store
+            .search(&[0.3, 0.9, 1.0, 1.0], 10)
+            .unwrap()
+            .into_iter()
+            .map(|(m, _)| m.vector.id)
+            .collect()
+    };
+    assert_eq!(ids(&a), ids(&b));
+}
+
+#[test]
+fn compressed_roundtrip_is_identical_and_smaller() {
+    let dir = tempfile::tempdir().unwrap();
+    let plain = dir.path().join("plain.chrono");
+    let compressed = dir.path().join("compressed.chrono");
+
+    let store = sample_store();
+    save_snapshot(&store, &plain).unwrap();
+    save_snapshot_compressed(&store, &compressed).unwrap();
+    assert!(
+        fs::metadata(&compressed).unwrap().len() < fs::metadata(&plain).unwrap().len(),
+        "compression did not shrink the snapshot"
+    );
+
+    let loaded = load_snapshot(&compressed).unwrap();
+    let sorted = |store: &ChronoMind| {
+        let mut memories = store.snapshot();
+        memories.sort_by(|a, b| a.vector.id.cmp(&b.vector.id));
+        memories
+    };
+    assert_eq!(sorted(&loaded), sorted(&store));
+    assert_eq!(loaded.config(), store.config());
+
+    // The compressed body is checksummed like a plain one.
+    let mut bytes = fs::read(&compressed).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    fs::write(&compressed, bytes).unwrap();
+    assert!(matches!(
+        load_snapshot(&compressed),
+        Err(Error::InvalidSnapshot(_))
+    ));
+}
+
+#[test]
+fn ndjson_round_trips_through_a_buffer() {
+    let store = sample_store();
+    let mut buffer = Vec::new();
+    assert_eq!(export_ndjson(&store, &mut buffer).unwrap(), 20);
+    let text = String::from_utf8(buffer.clone()).unwrap();
+    assert_eq!(text.lines().count(), 20);
+    assert!(text.lines().next().unwrap().contains("\"m0\""));
+
+    let restored = ChronoMind::new(store.config().clone()).unwrap();
+    assert_eq!(import_ndjson(&restored, buffer.as_slice()).unwrap(), 20);
+    let sorted = |s: &ChronoMind| {
+        let mut all = s.snapshot();
+        all.sort_by(|a, b| a.vector.id.cmp(&b.vector.id));
+        all
+    };
+    assert_eq!(sorted(&restored), sorted(&store));
+}
+
+#[test]
+fn ndjson_import_validates_each_line() {
+    let mut buffer = Vec::new();
+    export_ndjson(&sample_store(), &mut buffer).unwrap();
+
+    let narrow = ChronoMind::new(Config {
+        dimensions: 3,
+        ..Config::default()
+    })
+    .unwrap();
+    assert!(matches!(
+        import_ndjson(&narrow, buffer.as_slice()),
+        Err(Error::InvalidDimensions {
+            got: 4,
+            expected: 3
+        })
+    ));
+
+    let store = ChronoMind::new(sample_store().config().clone()).unwrap();
+    let first = buffer.split(|&b| b == b'\n').next().unwrap();
+    let mut input = first.to_vec();
+    input.extend_from_slice(b"\n\n{not json\n");
+    match import_ndjson(&store, input.as_slice()) {
+        Err(Error::Serialization(message)) => assert!(message.starts_with("line 3:"), "{message}"),
+        other => panic!("expected a serialization error, got {other:?}"),
+    }
+    assert_eq!(store.len(), 1, "lines before the bad one stay imported");
+}
+
+#[test]
+fn metadata_survives_snapshots_and_ndjson() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("test.chrono");
+    let store = sample_store();
+    let metadata = serde_json::json!({"source": "https://example.com", "tags": ["a", 1, null]});
+    store
+        .insert(
+            Memory::builder("tagged", vec![1.0, 0.0, 0.0, 0.0])
+                .metadata(metadata.clone())
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+    save_snapshot(&store, &path).unwrap();
+    let loaded = load_snapshot(&path).unwrap();
+    assert_eq!(
+        loaded.get("tagged").unwrap().attributes.metadata,
+        Some(metadata.clone())
+    );
+    assert_eq!(loaded.get("m1").unwrap().attributes.metadata, None);
+
+    let mut buffer = Vec::new();
+    export_ndjson(&store, &mut buffer).unwrap();
+    let text = String::from_utf8(buffer.clone()).unwrap();
+    assert!(
+        text.contains(r#""metadata":{"source":"https://example.com""#),
+        "stored as JSON, not text"
+    );
+    let imported = ChronoMind::new(store.config().clone()).unwrap();
+    import_ndjson(&imported, buffer.as_slice()).unwrap();
+    assert_eq!(
+        imported.get("tagged").unwrap().attributes.metadata,
+        Some(metadata)
+    );
+}
+
+#[test]
+fn ndjson_file_import_reports_progress_and_optionally_skips_bad_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memories.ndjson");
+    let total = IMPORT_PROGRESS_INTERVAL + 3;
+    let mut file = std::io::BufWriter::new(fs::File::create(&path).unwrap());
+    for i in 0..total {
+        if i == 5 {
+            writeln!(file, "{{\"vector\": oops}}").unwrap();
+        }
+        let memory = Memory::from_vector(Vector::new(format!("m{i}"), vec![1.0, i as f32]));
+        writeln!(file, "{}", serde_json::to_string(&memory).unwrap()).unwrap();
+    }
+    drop(file);
+    let store = || {
+        ChronoMind::new(Config {
+            dimensions: 2,
+            ..Config::default()
+        })
+        .unwrap()
+    };
+
+    let strict = store();
+    match import_ndjson_file(&strict, &path, false, |_| panic!("no progress yet")) {
+        Err(Error::Serialization(message)) => assert!(message.starts_with("line 6:"), "{message}"),
+        other => panic!("expected a serialization error, got {other:?}"),
+    }
+    assert_eq!(strict.len(), 5, "lines before the bad one stay imported");
+
+    let lenient = store();
+    let mut reports = Vec::new();
+    let count = import_ndjson_file(&lenient, &path, true, |n| reports.push(n)).unwrap();
+    assert_eq!(count, total);
+    assert_eq!(lenient.len(), total);
+    assert_eq!(reports, [IMPORT_PROGRESS_INTERVAL]);
+}
+
+#[test]
+fn lenient_ndjson_file_import_skips_out_of_range_link_strengths() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memories.ndjson");
+    let mut file = fs::File::create(&path).unwrap();
+    let mut bad = Memory::from_vector(Vector::new("bad", vec![0.0, 1.0]));
+    bad.attributes.relationships = vec!["good".into()];
+    bad.attributes.relationship_strengths = vec![1.5];
+    for memory in [
+        Memory::from_vector(Vector::new("good", vec![1.0, 0.0])),
+        bad,
+        Memory::from_vector(Vector::new("last", vec![1.0, 1.0])),
+    ] {
+        writeln!(file, "{}", serde_json::to_string(&memory).unwrap()).unwrap();
+    }
+    drop(file);
+    let store = || {
+        ChronoMind::new(Config {
+            dimensions: 2,
+            ..Config::default()
+        })
+        .unwrap()
+    };
+
+    let strict = store();
+    assert!(matches!(
+        import_ndjson_file(&strict, &path, false, |_| {}),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert_eq!(strict.len(), 1);
+
+    let lenient = store();
+    assert_eq!(
+        import_ndjson_file(&lenient, &path, true, |_| {}).unwrap(),
+        2
+    );
+    assert!(lenient.get("bad").is_none());
+    assert!(lenient.get("last").is_some());
+}
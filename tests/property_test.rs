@@ -1,5 +1,5 @@
-//! Property tests for the index: invariants that must hold for arbitrary
-//! inputs, not just hand-picked fixtures.
+//! Property tests for the index and metrics: invariants that must hold for
+//! arbitrary inputs, not just hand-picked fixtures.
 
 use std::sync::Arc;
 
@@ -46,6 +46,34 @@ proptest! {
         }
     }
 
+    /// A seed in the params makes construction reproducible: the same
+    /// vectors in the same order give link-for-link identical graphs.
+    #[test]
+    fn seeded_params_build_identical_graphs(vectors in pvec(arb_vector(), 1..80)) {
+        let build = || {
+            let params = IndexParams {
+                seed: Some(11),
+                ..IndexParams::default()
+            };
+            let index = LockFreeHnsw::new(params, Arc::new(CosineDistance::new()));
+            for v in &vectors {
+                index.insert(v).unwrap();
+            }
+            index
+        };
+        let (a, b) = (build(), build());
+        prop_assert_eq!(a.layer_stats(), b.layer_stats());
+        for id in 0..vectors.len() as u32 {
+            for layer in 0.. {
+                let links = a.neighbors(id, layer);
+                prop_assert_eq!(&links, &b.neighbors(id, layer));
+                if links.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+
     /// Arbitrary interleaved op sequences preserve every structural graph
     /// invariant — the invariant sweep is the oracle, the op sequence is
     /// the fuzz input. (The coverage-guided version of this same harness
@@ -248,3 +276,49 @@ proptest! {
         prop_assert_eq!(ids.len(), results.len(), "duplicate ids in results");
     }
 }
+
+/// Any finite component, from subnormal to near `f32::MAX`, or zero.
+fn arb_component() -> impl Strategy<Value = f32> {
+    prop_oneof![
+        4 => -10.0f32..10.0,
+        1 => prop::num::f32::NORMAL | prop::num::f32::SUBNORMAL | prop::num::f32::ZERO,
+    ]
+}
+
+/// Two vectors of one length; the second is often a slight perturbation
+/// of the first, where rounding pushes cosine past ±1.
+fn arb_pair() -> impl Strategy<Value = (Vec<f32>, Vec<f32>)> {
+    (1usize..40).prop_flat_map(|len| {
+        prop_oneof![
+            (pvec(arb_component(), len), pvec(arb_component(), len)),
+            (pvec(arb_component(), len), pvec(-1e-6f32..1e-6, len)).prop_map(|(a, noise)| {
+                let b = a.iter().zip(&noise).map(|(x, n)| x + x * n).collect();
+                (a, b)
+            }),
+        ]
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    /// Cosine distance stays within `[0, 2]` and similarity within
+    /// `[-1, 1]` on every path, whatever the magnitudes or SIMD rounding.
+    #[test]
+    fn cosine_outputs_stay_in_bounds((a, b) in arb_pair()) {
+        let m = CosineDistance::new();
+        let distance = m.distance(&a, &b);
+        let similarity = m.similarity(&a, &b);
+        let prepared = m.distance_prepared(&m.preprocess(&a), &m.preprocess(&b));
+        prop_assert!((0.0..=2.0).contains(&distance), "distance {distance}");
+        prop_assert!((-1.0..=1.0).contains(&similarity), "similarity {similarity}");
+        prop_assert!((0.0..=2.0).contains(&prepared), "prepared distance {prepared}");
+
+        let own = m.distance(&a, &a);
+        if a.iter().all(|&x| x == 0.0) {
+            prop_assert_eq!(own, 2.0);
+        } else {
+            prop_assert!(own.abs() < 1e-5, "self-distance {own}");
+        }
+    }
+}
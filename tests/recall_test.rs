@@ -3,12 +3,13 @@
 //! These are the Phase 2 exit criteria from `docs/DESIGN.md` §4, run
 //! against the locked baseline AND the lock-free index — the latter must
 //! pass the exact gates that validate the former. Fully deterministic:
-//! seeded RNG, fixed dataset sizes, single-threaded insertion.
+//! seeded RNG, fixed dataset sizes, single-threaded insertion. The exact
+//! scan index is held to perfect recall on the same data.
 
 use std::sync::Arc;
 
 use chronomind::config::IndexParams;
-use chronomind::index::{LockFreeHnsw, RwLockHnsw, VectorIndex};
+use chronomind::index::{BruteForceIndex, LockFreeHnsw, RwLockHnsw, VectorIndex};
 use chronomind::metric::{CosineDistance, DistanceMetric};
 
 use rand::rngs::StdRng;
@@ -23,6 +24,7 @@ const GATE: f64 = 0.95;
 enum Impl {
     Baseline,
     LockFree,
+    Exact,
 }
 
 fn build_index(which: Impl, seed: u64) -> Box<dyn VectorIndex> {
@@ -31,6 +33,7 @@ fn build_index(which: Impl, seed: u64) -> Box<dyn VectorIndex> {
     match which {
         Impl::Baseline => Box::new(RwLockHnsw::with_seed(params, metric, seed)),
         Impl::LockFree => Box::new(LockFreeHnsw::with_seed(params, metric, seed)),
+        Impl::Exact => Box::new(BruteForceIndex::new(metric)),
     }
 }
 
@@ -261,6 +264,23 @@ mod lockfree {
     }
 }
 
+mod exact {
+    use super::*;
+
+    #[test]
+    fn recall_is_perfect() {
+        let (data, queries) = embedding_dataset(0xBEEF);
+        assert_eq!(measure_recall(Impl::Exact, &data, &queries, K, 0), 1.0);
+        let (data, queries) = uniform_dataset(768, 0xBEEF);
+        assert_eq!(measure_recall(Impl::Exact, &data, &queries, K, 0), 1.0);
+    }
+
+    #[test]
+    fn recall_survives_tombstones() {
+        gate_tombstones(Impl::Exact);
+    }
+}
+
 /// Diagnostic, not a gate: print the ef/recall curve for uniform 768-d data.
 #[test]
 #[ignore = "diagnostic: run with --ignored --nocapture"]
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use chronomind::{Config, Memory, SqliteStore};
+
+fn config() -> Config {
+    Config {
+        dimensions: 3,
+        ..Config::default()
+    }
+}
+
+fn memory(id: &str, data: Vec<f32>, context: &str) -> Memory {
+    Memory::builder(id, data)
+        .context(context)
+        .importance(0.4)
+        .relationships(["elsewhere"])
+        .build()
+        .unwrap()
+}
+
+fn sorted(mut memories: Vec<Memory>) -> Vec<Memory> {
+    memories.sort_by(|a, b| a.vector.id.cmp(&b.vector.id));
+    memories
+}
+
+#[test]
+fn rows_reopen_into_the_same_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memories.db");
+
+    let db = SqliteStore::create(&path, config()).unwrap();
+    db.insert(memory("a", vec![1.0, 0.0, 0.0], "work")).unwrap();
+    db.insert(memory("b", vec![0.0, 1.0, 0.0], "home")).unwrap();
+    db.insert(memory("c", vec![0.0, 0.0, 1.0], "work")).unwrap();
+    let expected = sorted(db.store().snapshot());
+    drop(db);
+
+    let reopened = SqliteStore::open(&path).unwrap();
+    assert_eq!(reopened.store().config(), &config());
+    assert_eq!(sorted(reopened.store().snapshot()), expected);
+    let results = reopened.store().search(&[0.0, 1.0, 0.0], 1).unwrap();
+    assert_eq!(results[0].0.vector.id, "b");
+}
+
+#[test]
+fn removal_deletes_the_row() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memories.db");
+    let db = SqliteStore::create(&path, config()).unwrap();
+    db.insert(memory("a", vec![1.0, 0.0, 0.0], "work")).unwrap();
+    db.insert(memory("b", vec![0.0, 1.0, 0.0], "work")).unwrap();
+
+    assert!(db.remove("a").unwrap().is_some());
+    assert!(db.remove("a").unwrap().is_none());
+    assert_eq!(db.ids().unwrap(), ["b"]);
+    drop(db);
+    assert_eq!(SqliteStore::open(&path).unwrap().store().len(), 1);
+}
+
+#[test]
+fn sql_filters_and_aggregates_by_context() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memories.db");
+    let db = SqliteStore::create(&path, config()).unwrap();
+    for (id, context) in [("a", "work"), ("b", "home"), ("c", "work"), ("d", "work")] {
+        db.insert(memory(id, vec![1.0, 0.5, 0.0], context)).unwrap();
+    }
+
+    assert_eq!(db.ids().unwrap(), ["a", "b", "c", "d"]);
+    assert_eq!(db.ids_in_context("work").unwrap(), ["a", "c", "d"]);
+    assert!(db.ids_in_context("nowhere").unwrap().is_empty());
+    let expected: HashMap<String, usize> = [("work".into(), 3), ("home".into(), 1)].into();
+    assert_eq!(db.context_counts().unwrap(), expected);
+}
+
+#[test]
+fn checkpoint_writes_state_changed_outside_the_wrapper() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("memories.db");
+    let db = SqliteStore::create(&path, config()).unwrap();
+    db.insert(memory("a", vec![1.0, 0.0, 0.0], "work")).unwrap();
+    db.store().reinforce("a", 0.5).unwrap();
+    db.store()
+        .insert(memory("direct", vec![0.0, 1.0, 0.0], "home"))
+        .unwrap();
+    assert_eq!(
+        db.ids().unwrap(),
+        ["a"],
+        "direct writes wait for a checkpoint"
+    );
+
+    db.checkpoint().unwrap();
+    let expected = sorted(db.store().snapshot());
+    drop(db);
+
+    // The queryable columns track the checkpointed state too.
+    let connection = rusqlite::Connection::open(&path).unwrap();
+    let importance: f64 = connection
+        .query_row(
+            "SELECT importance FROM memories WHERE id = 'a'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert!((importance - 0.9).abs() < 1e-6);
+    drop(connection);
+
+    assert_eq!(
+        sorted(SqliteStore::open(&path).unwrap().store().snapshot()),
+        expected
+    );
+}
@@ -1,10 +1,18 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-use chronomind::{ChronoMind, Config, Error, Memory, MemoryAttributes, Vector};
+use chronomind::{
+    Candidate, ChronoMind, Config, CosineDistance, DistanceMetric, Error, Memory, MemoryAttributes,
+    MultiQueryMode, Pca, Precision, Reranker, ScoreBands, TemporalReranker, Vector,
+};
 
+/// Stores small enough for the exhaustive scan still search the graph, so
+/// tests built on this exercise the index; the scan has its own tests.
 fn config(dimensions: usize) -> Config {
     Config {
         dimensions,
+        brute_force_threshold: 0,
         ..Config::default()
     }
 }
@@ -34,6 +42,29 @@ fn insert_and_get_roundtrip() {
     assert_eq!(store.len(), 1);
 }
 
+#[test]
+fn metadata_comes_back_from_get_and_search() {
+    let store = ChronoMind::new(config(3)).unwrap();
+    let metadata = serde_json::json!({"source": "notes.md", "line": 12});
+    store
+        .insert(
+            Memory::builder("a", vec![1.0, 0.0, 0.0])
+                .metadata(metadata.clone())
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+    store.insert(memory("b", vec![0.0, 1.0, 0.0])).unwrap();
+
+    assert_eq!(
+        store.get("a").unwrap().attributes.metadata,
+        Some(metadata.clone())
+    );
+    let results = store.search(&[1.0, 0.0, 0.0], 2).unwrap();
+    assert_eq!(results[0].0.attributes.metadata, Some(metadata));
+    assert_eq!(results[1].0.attributes.metadata, None);
+}
+
 #[test]
 fn search_returns_nearest_first() {
     let store = ChronoMind::new(config(3)).unwrap();
@@ -50,6 +81,44 @@ fn search_returns_nearest_first() {
     assert!(results[0].1 <= results[1].1 && results[1].1 <= results[2].1);
 }
 
+#[test]
+fn tied_scores_rank_by_id() {
+    let ids = |results: Vec<(Memory, f32)>| -> Vec<String> {
+        results.into_iter().map(|(m, _)| m.vector.id).collect()
+    };
+    let timestamp = SystemTime::now() - Duration::from_secs(3600);
+    // Both the scan used below the brute-force threshold and the graph.
+    for brute_force_threshold in [usize::MAX, 0] {
+        for _ in 0..5 {
+            let store = ChronoMind::new(Config {
+                dimensions: 2,
+                brute_force_threshold,
+                ..Config::default()
+            })
+            .unwrap();
+            for id in ["d", "b", "e", "a", "c"] {
+                let m = Memory::builder(id, vec![0.6, 0.8])
+                    .context("ctx")
+                    .timestamp(timestamp)
+                    .build()
+                    .unwrap();
+                store.insert(m).unwrap();
+            }
+
+            let query = [1.0, 0.0];
+            assert_eq!(ids(store.search(&query, 4).unwrap()), ["a", "b", "c", "d"]);
+            assert_eq!(
+                ids(store.search_in_context("ctx", &query, 3).unwrap()),
+                ["a", "b", "c"]
+            );
+            assert_eq!(
+                ids(store.search_with_negatives(&query, &[], 2, 0.0).unwrap()),
+                ["a", "b"]
+            );
+        }
+    }
+}
+
 #[test]
 fn search_k_truncates() {
     let store = ChronoMind::new(config(2)).unwrap();
@@ -91,6 +160,145 @@ fn temporal_weight_prefers_recent_memories() {
     assert_eq!(results[1].0.vector.id, "old");
 }
 
+#[test]
+fn rerank_overfetch_bounds_how_far_recency_can_reach() {
+    // Twenty week-old memories crowd the query; one fresh memory points
+    // the other way. Under a heavy temporal weight the fresh one is the
+    // best result, but only if the candidate pool reaches it.
+    let search_with = |overfetch: usize| {
+        let mut config = Config {
+            dimensions: 2,
+            temporal_weight: 0.9,
+            brute_force_threshold: 0,
+            rerank_overfetch: overfetch,
+            ..Config::default()
+        };
+        config.index.ef_search = 1;
+        config.index.seed = Some(7);
+        let store = ChronoMind::new(config).unwrap();
+        let week_ago = SystemTime::now() - Duration::from_secs(7 * 24 * 3600);
+        for i in 0..20 {
+            let angle = i as f32 * 0.01;
+            let old = Memory::builder(format!("old{i}"), vec![angle.cos(), angle.sin()])
+                .timestamp(week_ago)
+                .build()
+                .unwrap();
+            store.insert(old).unwrap();
+        }
+        store.insert(memory("fresh", vec![0.0, 1.0])).unwrap();
+        store.search(&[1.0, 0.0], 1).unwrap()[0].0.vector.id.clone()
+    };
+
+    assert!(search_with(1).starts_with("old"));
+    assert_eq!(search_with(30), "fresh");
+}
+
+/// Newest first, whatever the distance.
+struct PureRecency;
+
+impl Reranker for PureRecency {
+    fn rerank(
+        &self,
+        mut candidates: Vec<Candidate>,
+        _k: usize,
+        now: SystemTime,
+        _config: &Config,
+    ) -> Vec<Candidate> {
+        for c in &mut candidates {
+            c.score = now
+                .duration_since(c.timestamp)
+                .unwrap_or_default()
+                .as_secs_f32();
+        }
+        candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+        candidates
+    }
+}
+
+#[test]
+fn a_custom_reranker_replaces_temporal_scoring() {
+    let store = ChronoMind::new(config(2))
+        .unwrap()
+        .with_reranker(Arc::new(PureRecency));
+    let now = SystemTime::now();
+    for (id, data, hours_ago) in [
+        ("near-old", vec![1.0, 0.0], 48),
+        ("mid", vec![0.7, 0.7], 24),
+        ("far-new", vec![-1.0, 0.0], 1),
+    ] {
+        let memory = Memory::builder(id, data)
+            .timestamp(now - Duration::from_secs(hours_ago * 3600))
+            .build()
+            .unwrap();
+        store.insert(memory).unwrap();
+    }
+
+    let results = store.search(&[1.0, 0.0], 3).unwrap();
+    let ids: Vec<&str> = results.iter().map(|(m, _)| m.vector.id.as_str()).collect();
+    assert_eq!(ids, ["far-new", "mid", "near-old"]);
+    // Scores are the reranker's, here ages in seconds.
+    assert!((results[0].1 - 3600.0).abs() < 60.0);
+
+    // The default ranks by distance and age together.
+    let default = ChronoMind::new(config(2)).unwrap();
+    for memory in store.snapshot() {
+        default.insert(memory).unwrap();
+    }
+    assert_eq!(
+        default.search(&[1.0, 0.0], 1).unwrap()[0].0.vector.id,
+        "near-old"
+    );
+}
+
+/// The default ranking, recording the size of each pool it is handed and
+/// of each selection it returns.
+#[derive(Default)]
+struct Recording(std::sync::Mutex<Vec<(usize, usize, usize)>>);
+
+impl Reranker for Recording {
+    fn rerank(
+        &self,
+        candidates: Vec<Candidate>,
+        k: usize,
+        now: SystemTime,
+        config: &Config,
+    ) -> Vec<Candidate> {
+        let pool = candidates.len();
+        let selected = TemporalReranker.rerank(candidates, k, now, config);
+        self.0.lock().unwrap().push((pool, k, selected.len()));
+        selected
+    }
+}
+
+#[test]
+fn search_selects_the_best_k_without_ranking_the_whole_pool() {
+    let recording = Arc::new(Recording::default());
+    let store = ChronoMind::new(config(2))
+        .unwrap()
+        .with_reranker(Arc::clone(&recording) as Arc<dyn Reranker>);
+    let now = SystemTime::now();
+    for i in 0..200 {
+        let angle = i as f32 * 0.03;
+        let m = Memory::builder(format!("m{i}"), vec![angle.cos(), angle.sin()])
+            .timestamp(now - Duration::from_secs(i as u64 * 600))
+            .build()
+            .unwrap();
+        store.insert(m).unwrap();
+    }
+
+    let top = store.search(&[1.0, 0.0], 5).unwrap();
+    let (pool, k, selected) = recording.0.lock().unwrap()[0];
+    assert_eq!((k, selected), (5, 5));
+    assert!(pool > 5, "the reranker saw a pool of {pool}");
+
+    // The selection agrees with ranking everything.
+    let ids = |results: &[(Memory, f32)]| -> Vec<String> {
+        results.iter().map(|(m, _)| m.vector.id.clone()).collect()
+    };
+    let everything = store.search(&[1.0, 0.0], 200).unwrap();
+    assert_eq!(ids(&top), ids(&everything[..5]));
+}
+
 #[test]
 fn zero_temporal_weight_ranks_purely_by_distance() {
     let store = ChronoMind::new(Config {
@@ -116,6 +324,107 @@ fn zero_temporal_weight_ranks_purely_by_distance() {
     assert_eq!(results[0].0.vector.id, "old_near");
 }
 
+#[test]
+fn updated_temporal_weight_changes_the_ranking() {
+    let mut store = ChronoMind::new(Config {
+        dimensions: 2,
+        temporal_weight: 0.0,
+        ..Config::default()
+    })
+    .unwrap();
+    let old_time = SystemTime::now() - Duration::from_secs(30 * 24 * 3600);
+    store
+        .insert(Memory::new(
+            Vector::new("old_near", vec![1.0, 0.1]),
+            MemoryAttributes {
+                timestamp: old_time,
+                ..MemoryAttributes::default()
+            },
+        ))
+        .unwrap();
+    store.insert(memory("fresh_far", vec![1.0, 1.0])).unwrap();
+    let top = |store: &ChronoMind| store.search(&[1.0, 0.0], 1).unwrap()[0].0.vector.id.clone();
+    assert_eq!(top(&store), "old_near");
+
+    let mut updated = store.config().clone();
+    updated.temporal_weight = 0.8;
+    store.update_config(updated).unwrap();
+    assert_eq!(store.config().temporal_weight, 0.8);
+    assert_eq!(top(&store), "fresh_far");
+}
+
+#[test]
+fn update_config_rejects_structural_changes() {
+    let mut store = ChronoMind::new(config(2)).unwrap();
+    for i in 0..3 {
+        store
+            .insert(memory(&format!("m{i}"), vec![1.0, i as f32]))
+            .unwrap();
+    }
+    let original = store.config().clone();
+
+    for bad in [
+        Config {
+            dimensions: 3,
+            ..original.clone()
+        },
+        Config {
+            max_memories: 2,
+            ..original.clone()
+        },
+        Config {
+            temporal_weight: 2.0,
+            ..original.clone()
+        },
+    ] {
+        assert!(matches!(store.update_config(bad), Err(Error::Config(_))));
+        assert_eq!(store.config(), &original);
+    }
+
+    // Index parameters rebuild the index; the contents survive.
+    let mut rebuilt = original.clone();
+    rebuilt.index.max_connections = 4;
+    store.update_config(rebuilt).unwrap();
+    assert_eq!(store.len(), 3);
+    assert_eq!(store.search(&[1.0, 2.0], 1).unwrap()[0].0.vector.id, "m2");
+}
+
+#[test]
+fn pruning_by_importance_removes_memories_and_links() {
+    let mut store = ChronoMind::new(config(2)).unwrap();
+    // Importance 0.0, 0.1, ..., 0.9; each memory links to every other.
+    let ids: Vec<String> = (0..10).map(|i| format!("m{i}")).collect();
+    for (i, id) in ids.iter().enumerate() {
+        let angle = i as f32 * 0.3;
+        let m = Memory::builder(id.clone(), vec![angle.cos(), angle.sin()])
+            .importance(i as f32 / 10.0)
+            .relationships(ids.iter().filter(|other| *other != id).cloned())
+            .build()
+            .unwrap();
+        store.insert(m).unwrap();
+    }
+
+    assert_eq!(store.prune_below_importance(0.35).unwrap(), 4);
+    assert_eq!(store.len(), 6);
+    for i in 0..10 {
+        assert_eq!(store.get(&format!("m{i}")).is_some(), i >= 4, "m{i}");
+    }
+    let survivor = store.get("m4").unwrap();
+    assert_eq!(
+        survivor.attributes.relationships,
+        ["m5", "m6", "m7", "m8", "m9"]
+    );
+    assert_eq!(survivor.attributes.relationship_strengths.len(), 5);
+    let hits = store.search(&[1.0, 0.0], 10).unwrap();
+    assert_eq!(hits.len(), 6, "pruned memories leave the index too");
+
+    assert_eq!(store.prune_below_importance(0.0).unwrap(), 0);
+    assert!(matches!(
+        store.prune_below_importance(f32::NAN),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
 #[test]
 fn context_search_filters() {
     let store = ChronoMind::new(config(2)).unwrap();
@@ -131,6 +440,159 @@ fn context_search_filters() {
     assert_eq!(results[0].0.vector.id, "a");
 }
 
+#[test]
+fn context_search_scores_like_search() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    let week_ago = SystemTime::now() - Duration::from_secs(7 * 24 * 3600);
+    let add = |id: &str, data: Vec<f32>, context: &str, timestamp: SystemTime| {
+        let m = Memory::builder(id, data)
+            .context(context)
+            .timestamp(timestamp)
+            .importance(if id == "near-old" { 1.0 } else { 0.1 })
+            .build()
+            .unwrap();
+        store.insert(m).unwrap();
+    };
+    let now = SystemTime::now();
+    add("near-recent", vec![1.0, 0.1], "ctx", now);
+    add("near-old", vec![1.0, 0.0], "ctx", week_ago);
+    add("far-recent", vec![0.0, 1.0], "ctx", now);
+    add("elsewhere", vec![1.0, 0.0], "other", now);
+
+    let results = store.search_in_context("ctx", &[1.0, 0.0], 3).unwrap();
+    let ids: Vec<&str> = results.iter().map(|(m, _)| m.vector.id.as_str()).collect();
+    assert_eq!(ids[0], "near-recent");
+    assert!(!ids.contains(&"elsewhere"));
+
+    // Same normalized score as an unrestricted search, whatever the
+    // importance: every score is within [0, 1].
+    let everywhere: HashMap<String, f32> = store
+        .search(&[1.0, 0.0], 4)
+        .unwrap()
+        .into_iter()
+        .map(|(m, score)| (m.vector.id, score))
+        .collect();
+    for (memory, score) in &results {
+        assert!((0.0..=1.0).contains(score));
+        assert!((everywhere[&memory.vector.id] - score).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn zero_vectors_rank_finitely() {
+    let store = ChronoMind::new(config(3)).unwrap();
+    store.insert(memory("zero", vec![0.0; 3])).unwrap();
+    store.insert(memory("x", vec![1.0, 0.0, 0.0])).unwrap();
+    store.insert(memory("y", vec![0.0, 1.0, 0.0])).unwrap();
+
+    // A zero stored vector sits at the maximum distance from everything.
+    let results = store.search(&[1.0, 0.1, 0.0], 3).unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].0.vector.id, "x");
+    assert_eq!(results[2].0.vector.id, "zero");
+    assert!(results.iter().all(|(_, score)| score.is_finite()));
+
+    // So does every stored vector from a zero query.
+    let results = store.search(&[0.0; 3], 3).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|(_, score)| score.is_finite()));
+    let within = store.search_within(&[0.0; 3], 2.0).unwrap();
+    assert!(within.iter().all(|(_, distance)| *distance == 2.0));
+}
+
+#[test]
+fn warm_up_primes_without_changing_results() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    store.warm_up(&[vec![1.0, 0.0]]).unwrap();
+    assert!(matches!(
+        store.warm_up(&[vec![1.0]]),
+        Err(Error::QueryDimensionMismatch { .. })
+    ));
+
+    for i in 0..50 {
+        let angle = i as f32 * 0.1;
+        store
+            .insert(memory(&format!("m{i}"), vec![angle.cos(), angle.sin()]))
+            .unwrap();
+    }
+    let before = store.search(&[1.0, 0.0], 5).unwrap();
+    store
+        .warm_up(&[vec![1.0, 0.0], vec![0.0, 1.0], vec![-1.0, 0.2]])
+        .unwrap();
+    let after = store.search(&[1.0, 0.0], 5).unwrap();
+    let ids = |r: &[(Memory, f32)]| {
+        r.iter()
+            .map(|(m, _)| m.vector.id.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&after), ids(&before));
+    assert_eq!(after[0].0.vector.id, "m0");
+}
+
+#[test]
+fn grouped_search_keeps_the_best_per_context() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    for (c, context) in ["alpha", "beta", "gamma"].iter().enumerate() {
+        for i in 0..5 {
+            let angle = (c * 5 + i) as f32 * 0.05;
+            store
+                .insert(memory_in_context(
+                    &format!("{context}{i}"),
+                    vec![angle.cos(), angle.sin()],
+                    context,
+                ))
+                .unwrap();
+        }
+    }
+
+    let groups = store.search_grouped_by_context(&[1.0, 0.0], 2).unwrap();
+    assert_eq!(groups.len(), 3);
+    for (context, results) in &groups {
+        assert_eq!(results.len(), 2, "{context}");
+        assert!(results
+            .iter()
+            .all(|(m, _)| &m.attributes.context == context));
+        assert!(results[0].1 <= results[1].1);
+    }
+    // Angles grow with the index, so the first two of each are the best.
+    let ids: Vec<&str> = groups["beta"]
+        .iter()
+        .map(|(m, _)| m.vector.id.as_str())
+        .collect();
+    assert_eq!(ids, ["beta0", "beta1"]);
+
+    assert!(store
+        .search_grouped_by_context(&[1.0, 0.0], 0)
+        .unwrap()
+        .is_empty());
+    assert!(store.search_grouped_by_context(&[1.0], 2).is_err());
+}
+
+#[test]
+fn search_iter_yields_the_search_results_lazily() {
+    // Purely geometric scores, so repeated searches agree exactly.
+    let store = ChronoMind::new(Config {
+        temporal_weight: 0.0,
+        ..config(8)
+    })
+    .unwrap();
+    for i in 0..300 {
+        let data = (0..8).map(|j| ((i * 8 + j) as f32 * 0.37).sin()).collect();
+        store.insert(memory(&format!("m{i}"), data)).unwrap();
+    }
+    let query = [1.0, -2.0, 0.5, 3.0, 0.0, -1.0, 2.0, 1.5];
+
+    let eager = store.search(&query, 50).unwrap();
+    let lazy = store.search_iter(&query, 50).unwrap();
+    assert_eq!(lazy.len(), 50);
+    assert_eq!(lazy.collect::<Vec<_>>(), eager);
+
+    // Taking a prefix is a prefix of the full ranking.
+    let page: Vec<_> = store.search_iter(&query, 50).unwrap().take(5).collect();
+    assert_eq!(page, eager[..5]);
+    assert!(store.search_iter(&[1.0], 5).is_err());
+}
+
 #[test]
 fn invalid_inputs_are_rejected() {
     let store = ChronoMind::new(config(3)).unwrap();
@@ -142,13 +604,26 @@ fn invalid_inputs_are_rejected() {
             expected: 3
         })
     ));
+    for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        assert!(matches!(
+            store.insert(memory("non-finite", vec![1.0, bad, 0.0])),
+            Err(Error::InvalidVector(_))
+        ));
+    }
+    assert!(store.is_empty());
     assert!(matches!(
-        store.insert(memory("nan", vec![1.0, f32::NAN, 0.0])),
-        Err(Error::InvalidVector(_))
+        store.search(&[1.0], 5),
+        Err(Error::QueryDimensionMismatch {
+            query: 1,
+            expected: 3
+        })
     ));
     assert!(matches!(
-        store.search(&[1.0], 5),
-        Err(Error::InvalidDimensions { .. })
+        store.search(&[1.0, 0.0, 0.0, 0.0], 5),
+        Err(Error::QueryDimensionMismatch {
+            query: 4,
+            expected: 3
+        })
     ));
     assert!(matches!(
         store.search(&[1.0, f32::INFINITY, 0.0], 5),
@@ -183,6 +658,100 @@ fn capacity_is_enforced_but_replacement_is_allowed() {
     assert_eq!(store.len(), 2);
 }
 
+#[test]
+fn the_index_grows_to_max_memories() {
+    use chronomind::IndexParams;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Past two of the index arena's 4096-node chunks; only `max_memories`
+    // caps the store.
+    let n = 10_001;
+    let mut rng = StdRng::seed_from_u64(29);
+    let data: Vec<Vec<f32>> = (0..=n)
+        .map(|_| (0..8).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    let store = ChronoMind::new(Config {
+        dimensions: 8,
+        max_memories: n,
+        temporal_weight: 0.0,
+        index: IndexParams {
+            max_connections: 8,
+            ef_construction: 40,
+            seed: Some(3),
+            ..IndexParams::default()
+        },
+        ..Config::default()
+    })
+    .unwrap();
+    for (i, v) in data[..n].iter().enumerate() {
+        store.insert(memory(&format!("m{i}"), v.clone())).unwrap();
+    }
+    assert!(matches!(
+        store.insert(memory("over", data[n].clone())),
+        Err(Error::CapacityExceeded(10_001))
+    ));
+
+    assert_eq!(store.len(), n);
+    for i in (0..n).step_by(97).chain([4095, 4096, 8192, n - 1]) {
+        let results = store.search(&data[i], 1).unwrap();
+        assert_eq!(results[0].0.vector.id, format!("m{i}"));
+    }
+}
+
+#[test]
+fn overwriting_a_vector_moves_the_memory_in_the_index() {
+    let mut cfg = config(3);
+    cfg.temporal_weight = 0.0;
+    let store = ChronoMind::new(cfg).unwrap();
+    store.insert(memory("a", vec![1.0, 0.0, 0.0])).unwrap();
+    store.insert(memory("b", vec![0.9, 0.1, 0.0])).unwrap();
+    store.insert(memory("a", vec![0.0, 0.0, 1.0])).unwrap();
+
+    let near_new = store.search(&[0.0, 0.0, 1.0], 1).unwrap();
+    assert_eq!(near_new[0].0.vector.id, "a");
+    assert!(near_new[0].1 < 1e-6);
+    let near_old = store.search(&[1.0, 0.0, 0.0], 2).unwrap();
+    assert_eq!(near_old[0].0.vector.id, "b");
+    assert_eq!(near_old.len(), 2, "one node per memory, no stale copy");
+    assert_eq!(store.index_stats().tombstoned, 1);
+}
+
+#[test]
+fn overwriting_only_attributes_keeps_the_index_node() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    store.insert(memory("a", vec![1.0, 0.0])).unwrap();
+    store
+        .insert(memory_in_context("a", vec![1.0, 0.0], "moved"))
+        .unwrap();
+
+    let stats = store.index_stats();
+    assert_eq!(stats.tombstoned, 0);
+    assert_eq!(stats.nodes_per_layer[0], 1);
+    let hits = store.search(&[1.0, 0.0], 1).unwrap();
+    assert_eq!(hits[0].0.attributes.context, "moved");
+    assert_eq!(store.len(), 1);
+}
+
+#[test]
+fn overwriting_only_attributes_keeps_the_node_of_a_quantized_record() {
+    for precision in [Precision::F16, Precision::Int8] {
+        let store = ChronoMind::new(Config {
+            precision,
+            ..config(3)
+        })
+        .unwrap();
+        // No component survives either quantization exactly.
+        let v = vec![0.3, -0.71, 0.1];
+        store.insert(memory("a", v.clone())).unwrap();
+        assert_ne!(store.get("a").unwrap().vector.data, v, "{precision:?}");
+        store.insert(memory_in_context("a", v, "moved")).unwrap();
+
+        assert_eq!(store.index_stats().tombstoned, 0, "{precision:?}");
+        assert_eq!(store.get("a").unwrap().attributes.context, "moved");
+    }
+}
+
 #[test]
 fn reinsert_merges_relationships() {
     let store = ChronoMind::new(config(2)).unwrap();
@@ -213,6 +782,50 @@ fn access_records_retrieval() {
     assert!(store.access("missing").is_none());
 }
 
+#[test]
+fn access_many_answers_by_position() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    store.insert(memory("a", vec![1.0, 0.0])).unwrap();
+    store.insert(memory("b", vec![0.0, 1.0])).unwrap();
+
+    let got = store.access_many(&["b", "missing", "a", "b"]);
+    let ids: Vec<Option<&str>> = got
+        .iter()
+        .map(|m| m.as_ref().map(|m| m.vector.id.as_str()))
+        .collect();
+    assert_eq!(ids, [Some("b"), None, Some("a"), Some("b")]);
+    assert_eq!(store.get("a").unwrap().attributes.access_count, 1);
+    assert_eq!(store.get("b").unwrap().attributes.access_count, 2);
+    assert!(store.access_many(&[]).is_empty());
+}
+
+#[test]
+fn access_refreshes_the_instant_decay_reads() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    let week_ago = SystemTime::now() - Duration::from_secs(7 * 24 * 3600);
+    let mut stale = memory("stale", vec![1.0, 0.0]);
+    stale.attributes.timestamp = week_ago;
+    stale.attributes.last_access = week_ago;
+    stale.attributes.importance = 0.8;
+    store.insert(stale).unwrap();
+
+    let accessed = store.access("stale").unwrap();
+    let read_back = store.get("stale").unwrap();
+    // One record, one last-access instant, whichever way it is read.
+    assert_eq!(
+        accessed.attributes.last_access,
+        read_back.attributes.last_access
+    );
+    assert!(read_back.attributes.last_access > week_ago + Duration::from_secs(3600));
+    assert_eq!(read_back.attributes.timestamp, week_ago);
+
+    // Decay measures from that same instant: a week-old memory just
+    // accessed keeps its importance.
+    store.apply_decay();
+    let importance = store.get("stale").unwrap().attributes.importance;
+    assert!((importance - 0.8).abs() < 0.01, "importance {importance}");
+}
+
 #[test]
 fn decay_reduces_importance_of_stale_memories() {
     let store = ChronoMind::new(config(2)).unwrap();
@@ -284,6 +897,123 @@ fn decay_leaves_fresh_memories_nearly_intact() {
     assert!((importance - 0.8).abs() < 0.01);
 }
 
+#[test]
+fn pinned_memories_skip_decay() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    let day_ago = SystemTime::now() - Duration::from_secs(24 * 3600);
+    for id in ["pinned", "peer"] {
+        store
+            .insert(Memory::new(
+                Vector::new(id, vec![1.0, 0.0]),
+                MemoryAttributes {
+                    importance: 0.9,
+                    timestamp: day_ago,
+                    last_access: day_ago,
+                    ..MemoryAttributes::default()
+                },
+            ))
+            .unwrap();
+    }
+    store.pin_memory("pinned", true).unwrap();
+    assert!(matches!(
+        store.pin_memory("missing", true),
+        Err(Error::NotFound(_))
+    ));
+
+    store.apply_decay();
+    let pinned = store.get("pinned").unwrap().attributes;
+    assert!(pinned.pinned);
+    assert_eq!(pinned.importance, 0.9);
+    assert!(store.get("peer").unwrap().attributes.importance < 0.1);
+
+    // Unpinning does not charge the pinned day retroactively.
+    store.pin_memory("pinned", false).unwrap();
+    store.apply_decay();
+    assert!((store.get("pinned").unwrap().attributes.importance - 0.9).abs() < 0.01);
+}
+
+#[test]
+fn consolidate_judges_duplicates_by_the_store_metric() {
+    use chronomind::EuclideanDistance;
+    use std::sync::Arc;
+
+    let mut store = ChronoMind::with_metric(
+        Config {
+            dimensions: 2,
+            similarity_threshold: 0.95,
+            ..Config::default()
+        },
+        Arc::new(EuclideanDistance::new()),
+    )
+    .unwrap();
+    store.insert(memory("a", vec![1.0, 0.0])).unwrap();
+    store.insert(memory("a-copy", vec![1.001, 0.0])).unwrap();
+    // Same direction, so cosine would call this a duplicate; it is far
+    // away in Euclidean terms.
+    store.insert(memory("far", vec![10.0, 0.0])).unwrap();
+
+    assert_eq!(store.consolidate(), 1);
+    assert_eq!(store.len(), 2);
+    assert!(store.get("far").is_some());
+    assert!(store.get("a").is_some() != store.get("a-copy").is_some());
+}
+
+#[test]
+fn index_guided_consolidation_finds_every_duplicate_pair() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(17);
+    let mut store = ChronoMind::new(Config {
+        dimensions: 16,
+        similarity_threshold: 0.99,
+        ..Config::default()
+    })
+    .unwrap();
+    let mut vectors = Vec::new();
+    for i in 0..300 {
+        let v: Vec<f32> = (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        let mut original = memory(&format!("m{i}"), v.clone());
+        original.attributes.importance = 0.9;
+        store.insert(original).unwrap();
+        vectors.push((format!("m{i}"), v.clone()));
+        if i % 10 == 0 {
+            let near: Vec<f32> = v.iter().map(|x| x + rng.gen_range(-0.01..0.01)).collect();
+            let mut copy = memory(&format!("m{i}-copy"), near.clone());
+            copy.attributes.importance = 0.1;
+            store.insert(copy).unwrap();
+            vectors.push((format!("m{i}-copy"), near));
+        }
+    }
+
+    // Brute force: every pair over the threshold, by exhaustive comparison.
+    let cosine = |a: &[f32], b: &[f32]| {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (norm(a) * norm(b))
+    };
+    let mut expected = Vec::new();
+    for (i, (a_id, a)) in vectors.iter().enumerate() {
+        for (b_id, b) in &vectors[i + 1..] {
+            if cosine(a, b) > 0.99 {
+                let pair = [a_id.as_str(), b_id.as_str()];
+                expected.push(pair.into_iter().find(|id| id.ends_with("-copy")).unwrap());
+            }
+        }
+    }
+    expected.sort_unstable();
+    assert_eq!(expected.len(), 30);
+
+    assert_eq!(store.consolidate(), 30);
+    let mut absorbed: Vec<&str> = vectors
+        .iter()
+        .map(|(id, _)| id.as_str())
+        .filter(|id| store.get(id).is_none())
+        .collect();
+    absorbed.sort_unstable();
+    assert_eq!(absorbed, expected);
+}
+
 #[test]
 fn consolidate_merges_near_duplicates() {
     let mut store = ChronoMind::new(Config {
@@ -318,6 +1048,54 @@ fn consolidate_merges_near_duplicates() {
     assert!(store.get("distinct").is_some());
 }
 
+#[test]
+fn deduplicate_keeps_the_most_important_copy() {
+    let mut store = ChronoMind::new(config(2)).unwrap();
+    for (id, importance, link) in [("a", 0.3, "x"), ("b", 0.8, "y"), ("c", 0.5, "z")] {
+        let mut copy = memory(id, vec![0.6, 0.8]);
+        copy.attributes.importance = importance;
+        copy.attributes.relationships = vec![link.into()];
+        store.insert(copy).unwrap();
+    }
+    // Close, but not the same vector.
+    store.insert(memory("near", vec![0.6, 0.8001])).unwrap();
+    let mut citing = memory("citing", vec![1.0, 0.0]);
+    citing.attributes.relationships = vec!["a".into()];
+    store.insert(citing).unwrap();
+    store.access("c").unwrap();
+
+    assert_eq!(store.deduplicate(true).unwrap(), 2);
+    assert_eq!(store.len(), 3);
+    assert!(store.get("a").is_none() && store.get("c").is_none());
+    let survivor = store.get("b").unwrap().attributes;
+    assert_eq!(survivor.importance, 0.8);
+    assert_eq!(survivor.access_count, 1);
+    let mut links = survivor.relationships;
+    links.sort();
+    assert_eq!(links, ["x", "y", "z"]);
+    assert_eq!(store.get("citing").unwrap().attributes.relationships, ["b"]);
+    assert!(store.get("near").is_some());
+    assert_eq!(store.deduplicate(true).unwrap(), 0);
+}
+
+#[test]
+fn deduplicate_without_merging_only_removes() {
+    let mut store = ChronoMind::new(config(2)).unwrap();
+    for (id, link) in [("a", "x"), ("b", "y")] {
+        let mut copy = memory(id, vec![1.0, 0.0]);
+        copy.attributes.relationships = vec![link.into()];
+        store.insert(copy).unwrap();
+    }
+
+    assert_eq!(store.deduplicate(false).unwrap(), 1);
+    // Equal importance: the smallest id survives, untouched.
+    assert_eq!(store.get("a").unwrap().attributes.relationships, ["x"]);
+    assert!(store.get("b").is_none());
+
+    let mut frozen = store.into_read_only();
+    assert!(matches!(frozen.deduplicate(false), Err(Error::ReadOnly)));
+}
+
 #[test]
 fn related_walks_links_breadth_first_with_depth_cap() {
     let store = ChronoMind::new(config(2)).unwrap();
@@ -347,9 +1125,169 @@ fn related_walks_links_breadth_first_with_depth_cap() {
 }
 
 #[test]
-fn context_summary_aggregates() {
+fn related_follows_the_strongest_links_first() {
     let store = ChronoMind::new(config(2)).unwrap();
-    let mut a = memory_in_context("a", vec![1.0, 0.0], "ctx");
+    let hub = Memory::builder("hub", vec![1.0, 0.0])
+        .weighted_relationships([("weak", 0.2), ("strong", 0.9), ("plain", 1.0)])
+        .build()
+        .unwrap();
+    store.insert(hub).unwrap();
+    for id in ["weak", "strong", "plain"] {
+        store.insert(memory(id, vec![0.0, 1.0])).unwrap();
+    }
+
+    let related: Vec<String> = store
+        .related("hub", 1)
+        .into_iter()
+        .map(|m| m.vector.id)
+        .collect();
+    assert_eq!(related, ["plain", "strong", "weak"]);
+
+    // Strengths survive a round trip, one per link.
+    let hub = store.get("hub").unwrap();
+    assert_eq!(hub.attributes.relationship_strengths, [0.2, 0.9, 1.0]);
+    let leaf = store.get("weak").unwrap();
+    assert!(leaf.attributes.relationship_strengths.is_empty());
+}
+
+#[test]
+fn consolidation_weights_inherited_links_by_similarity() {
+    let mut store = ChronoMind::new(Config {
+        dimensions: 2,
+        similarity_threshold: 0.99,
+        ..Config::default()
+    })
+    .unwrap();
+    let keep = Memory::builder("keep", vec![1.0, 0.0])
+        .importance(0.9)
+        .weighted_relationships([("own", 0.7)])
+        .build()
+        .unwrap();
+    let dup = Memory::builder("dup", vec![1.0, 0.05])
+        .importance(0.2)
+        .weighted_relationships([("inherited", 0.5)])
+        .build()
+        .unwrap();
+    let similarity = CosineDistance::new().similarity(&[1.0, 0.0], &[1.0, 0.05]);
+    store.insert(keep).unwrap();
+    store.insert(dup).unwrap();
+    assert_eq!(store.consolidate(), 1);
+
+    let survivor = store.get("keep").unwrap();
+    let links: Vec<(&str, f32)> = survivor.attributes.weighted_relationships().collect();
+    assert_eq!(links[0], ("own", 0.7));
+    assert_eq!(links[1].0, "inherited");
+    assert!((links[1].1 - 0.5 * similarity).abs() < 1e-6);
+}
+
+#[test]
+fn relationship_path_finds_the_shortest_chain() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    let linked = |id: &str, links: &[&str]| {
+        let mut m = memory(id, vec![1.0, 0.0]);
+        m.attributes.relationships = links.iter().map(|l| l.to_string()).collect();
+        m
+    };
+    // a -> b -> c -> d, plus a shortcut a -> c and an island e.
+    store.insert(linked("a", &["b", "c"])).unwrap();
+    store.insert(linked("b", &["c"])).unwrap();
+    store.insert(linked("c", &["d", "ghost"])).unwrap();
+    store.insert(linked("d", &[])).unwrap();
+    store.insert(linked("e", &[])).unwrap();
+
+    let path = |from, to, depth| store.relationship_path(from, to, depth).unwrap();
+    assert_eq!(path("a", "b", 1).unwrap(), ["a", "b"]);
+    assert_eq!(path("a", "d", 2).unwrap(), ["a", "c", "d"]);
+    assert_eq!(path("b", "d", 2).unwrap(), ["b", "c", "d"]);
+    assert_eq!(path("a", "a", 0).unwrap(), ["a"]);
+
+    assert_eq!(path("b", "d", 1), None, "two hops needed");
+    assert_eq!(path("d", "a", 10), None, "links are directed");
+    assert_eq!(path("a", "e", 10), None);
+    assert!(matches!(
+        store.relationship_path("a", "ghost", 3),
+        Err(Error::NotFound(id)) if id == "ghost"
+    ));
+}
+
+#[test]
+fn relationship_cycles_are_reported() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    let linked = |id: &str, links: &[&str]| {
+        let mut m = memory(id, vec![1.0, 0.0]);
+        m.attributes.relationships = links.iter().map(|l| l.to_string()).collect();
+        m
+    };
+    // a <-> b <-> c <-> a, and an acyclic tail d -> a.
+    store.insert(linked("a", &["b", "c"])).unwrap();
+    store.insert(linked("b", &["c", "a"])).unwrap();
+    store.insert(linked("c", &["a", "b"])).unwrap();
+    store.insert(linked("d", &["a"])).unwrap();
+
+    let cycles = store.relationship_cycles();
+    assert!(cycles.contains(&vec!["a".into(), "b".into(), "c".into()]));
+    assert!(cycles.contains(&vec!["a".into(), "b".into()]));
+    assert!(cycles.iter().all(|c| !c.contains(&"d".to_string())));
+
+    let acyclic = ChronoMind::new(config(2)).unwrap();
+    acyclic.insert(linked("x", &["y"])).unwrap();
+    acyclic.insert(linked("y", &["ghost"])).unwrap();
+    assert!(acyclic.relationship_cycles().is_empty());
+    acyclic.insert(linked("z", &["z"])).unwrap();
+    assert_eq!(acyclic.relationship_cycles(), vec![vec!["z".to_string()]]);
+}
+
+#[test]
+fn relinking_consolidation_breaks_cycles_through_absorbed_memories() {
+    let build = |relinks| {
+        let mut store = ChronoMind::new(Config {
+            dimensions: 2,
+            similarity_threshold: 0.99,
+            consolidation_relinks: relinks,
+            ..Config::default()
+        })
+        .unwrap();
+        let mut keep = memory("keep", vec![1.0, 0.0]);
+        keep.attributes.importance = 0.9;
+        keep.attributes.relationships = vec!["dup".into(), "other".into()];
+        let mut dup = memory("dup", vec![1.0, 0.001]);
+        dup.attributes.importance = 0.2;
+        dup.attributes.relationships = vec!["keep".into()];
+        let mut other = memory("other", vec![0.0, 1.0]);
+        other.attributes.relationships = vec!["dup".into(), "keep".into()];
+        for m in [keep, dup, other] {
+            store.insert(m).unwrap();
+        }
+        assert_eq!(store.consolidate(), 1);
+        store
+    };
+
+    let plain = build(false);
+    assert_eq!(
+        plain.get("keep").unwrap().attributes.relationships,
+        ["dup", "other"],
+        "without relinking the absorbed id dangles"
+    );
+
+    let relinked = build(true);
+    assert_eq!(
+        relinked.get("keep").unwrap().attributes.relationships,
+        ["other"]
+    );
+    assert_eq!(
+        relinked.get("other").unwrap().attributes.relationships,
+        ["keep"]
+    );
+    assert_eq!(
+        relinked.relationship_cycles(),
+        vec![vec!["keep".to_string(), "other".to_string()]]
+    );
+}
+
+#[test]
+fn context_summary_aggregates() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    let mut a = memory_in_context("a", vec![1.0, 0.0], "ctx");
     a.attributes.importance = 0.4;
     let mut b = memory_in_context("b", vec![0.0, 1.0], "ctx");
     b.attributes.importance = 0.6;
@@ -365,10 +1303,16 @@ fn context_summary_aggregates() {
 
 #[test]
 fn stats_reflect_contents() {
-    let store = ChronoMind::new(config(2)).unwrap();
+    let store = ChronoMind::new(Config {
+        max_memories: 8,
+        ..config(2)
+    })
+    .unwrap();
     let mut a = memory_in_context("a", vec![1.0, 0.0], "ctx1");
     a.attributes.relationships = vec!["b".into()];
-    let b = memory_in_context("b", vec![0.0, 1.0], "ctx2");
+    a.attributes.importance = 0.25;
+    let mut b = memory_in_context("b", vec![0.0, 1.0], "ctx2");
+    b.attributes.importance = 0.75;
     store.insert(a).unwrap();
     store.insert(b).unwrap();
 
@@ -378,6 +1322,32 @@ fn stats_reflect_contents() {
     assert_eq!(stats.context_distribution["ctx1"], 1);
     assert_eq!(stats.context_distribution["ctx2"], 1);
     assert_eq!(stats.most_referenced, vec![("b".to_string(), 1)]);
+    assert_eq!(stats.capacity_used, 0.25);
+    assert!((stats.average_importance - 0.5).abs() < 1e-3);
+    assert!(stats.bytes_in_use > 0);
+
+    let empty = ChronoMind::new(config(2)).unwrap().stats();
+    assert_eq!(empty.total_memories, 0);
+    assert_eq!(empty.average_importance, 0.0);
+    assert_eq!(empty.capacity_used, 0.0);
+}
+
+#[test]
+fn importance_histogram_buckets_by_tenths() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    for (i, importance) in [0.05, 0.45, 0.95, 1.0].into_iter().enumerate() {
+        let mut m = memory(&format!("m{i}"), vec![1.0, i as f32]);
+        m.attributes.importance = importance;
+        store.insert(m).unwrap();
+    }
+
+    let histogram = store.stats().importance_histogram;
+    assert_eq!(histogram.len(), 10);
+    assert_eq!(histogram[0], (0.0, 1));
+    assert_eq!(histogram[4], (0.4, 1));
+    // 1.0 falls into the last bucket rather than an eleventh.
+    assert_eq!(histogram[9], (0.9, 2));
+    assert_eq!(histogram.iter().map(|&(_, n)| n).sum::<usize>(), 4);
 }
 
 #[test]
@@ -388,3 +1358,997 @@ fn remove_deletes() {
     assert!(store.remove("a").is_none());
     assert!(store.is_empty());
 }
+
+#[test]
+fn removed_memory_is_tombstoned_out_of_search() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    store.insert(memory("left", vec![1.0, 0.0])).unwrap();
+    store.insert(memory("middle", vec![1.0, 1.0])).unwrap();
+    store.insert(memory("right", vec![0.0, 1.0])).unwrap();
+    store.remove("middle").unwrap();
+
+    assert_eq!(store.len(), 2);
+    assert!(store.get("middle").is_none());
+    // The index keeps the node for routing but never returns it.
+    assert_eq!(store.index_stats().tombstoned, 1);
+    let results = store.search(&[1.0, 1.0], 3).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|(m, _)| m.vector.id != "middle"));
+}
+
+#[test]
+fn get_is_exact_on_a_large_store() {
+    let store = ChronoMind::new(config(4)).unwrap();
+    for i in 0..1000 {
+        let x = i as f32;
+        store
+            .insert(memory(&format!("m{i}"), vec![x, -x, 1.0, x * 0.5]))
+            .unwrap();
+    }
+    let last = store.get("m999").unwrap();
+    assert_eq!(last.vector.data, [999.0, -999.0, 1.0, 499.5]);
+    assert_eq!(store.get("m0").unwrap().vector.data, [0.0, 0.0, 1.0, 0.0]);
+    assert!(store.get("m1000").is_none());
+}
+
+#[test]
+fn read_only_store_serves_reads_and_refuses_mutations() {
+    let mut a = memory_in_context("a", vec![1.0, 0.0], "ctx");
+    a.attributes.importance = 0.8;
+    let built = ChronoMind::new(config(2)).unwrap();
+    built.insert(a).unwrap();
+    built.insert(memory("b", vec![1.0, 0.001])).unwrap();
+    let mut store = built.into_read_only();
+    assert!(store.is_read_only());
+
+    assert_eq!(store.search(&[1.0, 0.0], 2).unwrap().len(), 2);
+    assert_eq!(
+        store
+            .search_in_context("ctx", &[1.0, 0.0], 2)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(store.context_summary("ctx").is_some());
+    assert_eq!(store.stats().total_memories, 2);
+
+    assert!(matches!(
+        store.insert(memory("c", vec![0.0, 1.0])),
+        Err(Error::ReadOnly)
+    ));
+    assert!(store.remove("a").is_none());
+    store.apply_decay();
+    assert_eq!(store.consolidate(), 0);
+    assert!(matches!(store.try_remove("a"), Err(Error::ReadOnly)));
+    assert!(matches!(store.try_apply_decay(), Err(Error::ReadOnly)));
+    assert!(matches!(store.try_consolidate(), Err(Error::ReadOnly)));
+    assert_eq!(store.access("a").unwrap().attributes.access_count, 0);
+
+    let a = store.get("a").unwrap();
+    assert_eq!(a.attributes.importance, 0.8);
+    assert_eq!(a.attributes.access_count, 0);
+    assert_eq!(store.len(), 2);
+}
+
+#[test]
+fn search_within_returns_everything_inside_the_radius() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    // More memories than ef_search, so the candidate pool must grow.
+    for i in 0..200 {
+        let angle = i as f32 * 0.01;
+        store
+            .insert(memory(&format!("m{i}"), vec![angle.cos(), angle.sin()]))
+            .unwrap();
+    }
+
+    let all = store.search_within(&[1.0, 0.0], 2.0).unwrap();
+    assert_eq!(all.len(), 200);
+    assert!(all.windows(2).all(|w| w[0].1 <= w[1].1));
+
+    assert!(store.search_within(&[-1.0, 0.0], 0.01).unwrap().is_empty());
+
+    // Cosine distance 1 - cos(0.01 * i) <= 0.02 holds for i <= 20.
+    let subset: Vec<String> = store
+        .search_within(&[1.0, 0.0], 0.02)
+        .unwrap()
+        .into_iter()
+        .map(|(m, _)| m.vector.id)
+        .collect();
+    let expected: Vec<String> = (0..=20).map(|i| format!("m{i}")).collect();
+    assert_eq!(subset, expected);
+
+    assert!(matches!(
+        store.search_within(&[1.0, 0.0], f32::NAN),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn whitening_recovers_structure_hidden_by_anisotropy() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Cluster membership lives in `y` (±1); `z` is a huge-variance nuisance
+    // axis and `x` a large constant offset. Raw cosine is dominated by the
+    // nuisance; after whitening every axis has unit variance and the
+    // clusters separate cleanly.
+    let mut store = ChronoMind::new(Config {
+        dimensions: 3,
+        temporal_weight: 0.0,
+        ..Config::default()
+    })
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(7);
+    for i in 0..200 {
+        let label = if i % 2 == 0 { 1.0 } else { -1.0 };
+        let data = vec![
+            200.0 + rng.gen_range(-1.0..1.0),
+            label,
+            rng.gen_range(-500.0..500.0),
+        ];
+        let id = format!("{}{i}", if label > 0.0 { "a" } else { "b" });
+        store.insert(memory(&id, data)).unwrap();
+    }
+
+    let query = [200.0, 1.0, 0.0];
+    let purity = |store: &ChronoMind| {
+        store
+            .search(&query, 10)
+            .unwrap()
+            .iter()
+            .filter(|(m, _)| m.vector.id.starts_with('a'))
+            .count()
+    };
+
+    let raw = purity(&store);
+    store.fit_whitening().unwrap();
+    assert!(store.whitening().is_some());
+    let whitened = purity(&store);
+    assert_eq!(whitened, 10, "whitened top-10 should be all cluster a");
+    assert!(raw <= 8, "raw cosine should mix clusters, got {raw}/10");
+
+    // Stored embeddings are untouched, and clearing restores raw ranking.
+    assert_eq!(store.get("a0").unwrap().vector.data[1], 1.0);
+    store.clear_whitening().unwrap();
+    assert!(store.whitening().is_none());
+    assert_eq!(purity(&store), raw);
+}
+
+#[test]
+fn diverse_search_covers_both_clusters() {
+    let store = ChronoMind::new(Config {
+        dimensions: 3,
+        temporal_weight: 0.0,
+        ..Config::default()
+    })
+    .unwrap();
+    for i in 0..5 {
+        let jitter = i as f32 * 0.002;
+        // Cluster a near angle 0, cluster b near angle 0.64, in the xy plane.
+        store
+            .insert(memory(&format!("a{i}"), vec![1.0, jitter, 0.0]))
+            .unwrap();
+        let b = 0.6435 + jitter;
+        store
+            .insert(memory(&format!("b{i}"), vec![b.cos(), b.sin(), 0.0]))
+            .unwrap();
+    }
+    let query = [1.0, 0.2, 0.0];
+    let clusters = |results: Vec<(Memory, f32)>| -> Vec<char> {
+        results
+            .iter()
+            .map(|(m, _)| m.vector.id.chars().next().unwrap())
+            .collect()
+    };
+
+    let plain = clusters(store.search(&query, 4).unwrap());
+    assert!(plain.iter().all(|&c| c == 'a'), "plain: {plain:?}");
+
+    let diverse = clusters(store.search_diverse(&query, 4, 0.5).unwrap());
+    assert_eq!(diverse.len(), 4);
+    assert_eq!(diverse[0], 'a', "the most relevant memory is picked first");
+    assert!(
+        diverse.contains(&'a') && diverse.contains(&'b'),
+        "diverse: {diverse:?}"
+    );
+
+    // lambda = 1 is pure relevance.
+    let relevance = clusters(store.search_diverse(&query, 4, 1.0).unwrap());
+    assert_eq!(relevance, plain);
+
+    assert!(matches!(
+        store.search_diverse(&query, 4, 1.5),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn visit_budget_bounds_search_work() {
+    use chronomind::IndexParams;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(11);
+    let data: Vec<Vec<f32>> = (0..1000)
+        .map(|_| (0..8).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    let build = |max_nodes_visited| {
+        let store = ChronoMind::new(Config {
+            dimensions: 8,
+            index: IndexParams {
+                max_nodes_visited,
+                ..IndexParams::default()
+            },
+            ..Config::default()
+        })
+        .unwrap();
+        for (i, v) in data.iter().enumerate() {
+            store.insert(memory(&format!("m{i}"), v.clone())).unwrap();
+        }
+        store
+    };
+    let query = &data[0];
+
+    let (results, stats) = build(None).search_with_stats(query, 10).unwrap();
+    assert_eq!(results.len(), 10);
+    assert!(stats.nodes_visited > 100, "unbounded: {stats:?}");
+    assert!(!stats.budget_exhausted);
+
+    let (results, stats) = build(Some(100)).search_with_stats(query, 10).unwrap();
+    assert!(stats.budget_exhausted);
+    // Entry points (one per layer descended) are admitted past the budget.
+    assert!(stats.nodes_visited <= 100 + 32, "bounded: {stats:?}");
+    assert!(!results.is_empty());
+}
+
+#[test]
+fn exact_mode_finds_what_a_starved_index_misses() {
+    use chronomind::{IndexParams, SearchMode};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(23);
+    let random =
+        |rng: &mut StdRng| -> Vec<f32> { (0..8).map(|_| rng.gen_range(-1.0..1.0)).collect() };
+    let data: Vec<Vec<f32>> = (0..1000).map(|_| random(&mut rng)).collect();
+    let queries: Vec<Vec<f32>> = (0..20).map(|_| random(&mut rng)).collect();
+    // A visit budget this small leaves the traversal well short of
+    // convergence, so approximate search is bound to miss.
+    let store = ChronoMind::new(Config {
+        dimensions: 8,
+        temporal_weight: 0.0,
+        index: IndexParams {
+            ef_search: 1,
+            max_nodes_visited: Some(10),
+            seed: Some(5),
+            ..IndexParams::default()
+        },
+        ..Config::default()
+    })
+    .unwrap();
+    for (i, v) in data.iter().enumerate() {
+        store.insert(memory(&format!("m{i}"), v.clone())).unwrap();
+    }
+
+    let cosine = |a: &[f32], b: &[f32]| -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (norm(a) * norm(b))
+    };
+    let top = |query: &[f32], mode| {
+        store.search_with_mode(query, 1, mode).unwrap()[0]
+            .0
+            .vector
+            .id
+            .clone()
+    };
+    let mut approximate_misses = 0;
+    for query in &queries {
+        let (truth, _) = data
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| cosine(query, a).total_cmp(&cosine(query, b)))
+            .unwrap();
+        let truth = format!("m{truth}");
+        assert_eq!(top(query, SearchMode::Exact), truth);
+        if top(query, SearchMode::Approximate) != truth {
+            approximate_misses += 1;
+        }
+    }
+    assert!(approximate_misses > 0, "the budget should cost recall");
+    assert_eq!(
+        store
+            .search_with_mode(&queries[0], 5, SearchMode::Approximate)
+            .unwrap(),
+        store.search(&queries[0], 5).unwrap()
+    );
+}
+
+#[test]
+fn multi_query_modes_diverge() {
+    let store = ChronoMind::new(config(3)).unwrap();
+    store.insert(memory("x", vec![1.0, 0.0, 0.0])).unwrap();
+    store.insert(memory("y", vec![0.0, 1.0, 0.0])).unwrap();
+    store.insert(memory("mid", vec![1.0, 1.0, 0.0])).unwrap();
+    store.insert(memory("far", vec![0.0, 0.0, 1.0])).unwrap();
+    let queries = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+
+    // The mean of the two queries points straight at "mid".
+    let mean = store
+        .search_multi(&queries, 2, MultiQueryMode::Mean)
+        .unwrap();
+    assert_eq!(mean[0].0.vector.id, "mid");
+
+    // Each query matches its own axis exactly; "mid" is only close-ish.
+    let min = store
+        .search_multi(&queries, 2, MultiQueryMode::MinDistance)
+        .unwrap();
+    let mut ids: Vec<&str> = min.iter().map(|(m, _)| m.vector.id.as_str()).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, ["x", "y"]);
+
+    assert!(matches!(
+        store.search_multi(
+            &[vec![1.0, 0.0, 0.0], vec![1.0, 0.0]],
+            2,
+            MultiQueryMode::Mean
+        ),
+        Err(Error::QueryDimensionMismatch { .. })
+    ));
+    assert!(matches!(
+        store.search_multi(&[], 2, MultiQueryMode::MinDistance),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn index_stats_reflect_the_graph() {
+    let store = ChronoMind::new(config(4)).unwrap();
+    assert_eq!(store.index_stats().total_connections, 0);
+
+    for i in 0..300 {
+        let t = i as f32 * 0.1;
+        store
+            .insert(memory(
+                &format!("m{i}"),
+                vec![t.sin(), t.cos(), (t * 0.3).sin(), 1.0],
+            ))
+            .unwrap();
+    }
+    store.remove("m0");
+
+    let stats = store.index_stats();
+    assert_eq!(stats.nodes_per_layer[0], 300);
+    assert!(stats.nodes_per_layer.windows(2).all(|w| w[0] >= w[1]));
+    assert_eq!(stats.tombstoned, 1);
+    assert_eq!(
+        stats.total_connections,
+        stats.connections_per_layer.iter().sum::<usize>()
+    );
+    // Every node links somewhere, and never past the base-layer cap (2M).
+    let cap = 2 * store.config().index.max_connections;
+    assert!(stats.average_degree >= 1.0 && stats.average_degree <= cap as f64);
+}
+
+#[test]
+fn negatives_push_a_cluster_down() {
+    let store = ChronoMind::new(config(3)).unwrap();
+    // Two clusters equally close to the query, one on each side of it.
+    for i in 0..5 {
+        let jitter = i as f32 * 0.01;
+        store
+            .insert(memory(&format!("a{i}"), vec![1.0, 0.5 + jitter, 0.0]))
+            .unwrap();
+        store
+            .insert(memory(&format!("b{i}"), vec![1.0, -0.5 - jitter, 0.0]))
+            .unwrap();
+    }
+    let query = [1.0, 0.0, 0.0];
+    let top = |results: Vec<(Memory, f32)>| -> Vec<char> {
+        results
+            .iter()
+            .map(|(m, _)| m.vector.id.chars().next().unwrap())
+            .collect()
+    };
+
+    let plain = top(store.search_with_negatives(&query, &[], 4, 1.0).unwrap());
+    assert!(plain.contains(&'a') && plain.contains(&'b'), "{plain:?}");
+
+    let negative = vec![vec![0.0, 1.0, 0.0]];
+    let steered = top(store
+        .search_with_negatives(&query, &negative, 4, 1.0)
+        .unwrap());
+    assert_eq!(steered, ['b', 'b', 'b', 'b']);
+
+    assert!(matches!(
+        store.search_with_negatives(&query, &negative, 4, -1.0),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn banded_search_labels_results_by_score() {
+    let store = ChronoMind::new(Config {
+        dimensions: 3,
+        // Pure geometry: score = cosine distance / 2.
+        temporal_weight: 0.0,
+        score_bands: ScoreBands {
+            excellent: 0.1,
+            good: 0.2,
+            fair: 0.3,
+        },
+        ..Config::default()
+    })
+    .unwrap();
+    store.insert(memory("same", vec![1.0, 0.0, 0.0])).unwrap(); // 0.0
+    store.insert(memory("near", vec![1.0, 1.0, 0.0])).unwrap(); // ~0.146
+    store.insert(memory("off", vec![1.0, 1.5, 0.0])).unwrap(); // ~0.223
+    store.insert(memory("ortho", vec![0.0, 1.0, 0.0])).unwrap(); // 0.5
+
+    let bands: Vec<(String, &str)> = store
+        .search_banded(&[1.0, 0.0, 0.0], 4)
+        .unwrap()
+        .into_iter()
+        .map(|(m, _, band)| (m.vector.id, band))
+        .collect();
+    assert_eq!(
+        bands,
+        [
+            ("same".to_string(), "excellent"),
+            ("near".to_string(), "good"),
+            ("off".to_string(), "fair"),
+            ("ortho".to_string(), "weak"),
+        ]
+    );
+}
+
+#[test]
+fn int8_store_keeps_approximate_records_and_exact_ranking() {
+    let store = ChronoMind::new(Config {
+        dimensions: 8,
+        precision: Precision::Int8,
+        ..Config::default()
+    })
+    .unwrap();
+    for i in 0..100 {
+        let t = i as f32 * 0.21;
+        let data = (0..8).map(|j| (t + j as f32).sin()).collect();
+        store.insert(memory(&format!("m{i}"), data)).unwrap();
+    }
+
+    let original: Vec<f32> = (0..8).map(|j| (42.0 * 0.21 + j as f32).sin()).collect();
+    let stored = store.get("m42").unwrap().vector.data;
+    // Range [-1, 1] over 255 steps: at most half a step off.
+    for (x, y) in original.iter().zip(&stored) {
+        assert!((x - y).abs() <= 1.0 / 255.0 + 1e-6, "{x} vs {y}");
+    }
+    // The index keeps full precision, so ranking is unaffected.
+    assert_eq!(store.search(&original, 1).unwrap()[0].0.vector.id, "m42");
+    assert_eq!(
+        store.search_in_context("", &original, 1).unwrap()[0]
+            .0
+            .vector
+            .id,
+        "m42"
+    );
+}
+
+#[test]
+fn f16_store_halves_record_bytes_and_keeps_recall() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let (n, dim) = (500, 64);
+    let mut rng = StdRng::seed_from_u64(3);
+    let data: Vec<Vec<f32>> = (0..n)
+        .map(|_| (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    let build = |precision| {
+        let store = ChronoMind::new(Config {
+            dimensions: dim,
+            precision,
+            index: chronomind::IndexParams {
+                seed: Some(9),
+                ..Default::default()
+            },
+            ..Config::default()
+        })
+        .unwrap();
+        for (i, v) in data.iter().enumerate() {
+            store.insert(memory(&format!("m{i}"), v.clone())).unwrap();
+        }
+        store
+    };
+    let (full, half) = (build(Precision::F32), build(Precision::F16));
+
+    // Records: 4 vs 2 bytes per component. Everything else — the f32 index
+    // copy, the (identically seeded) graph, ids — is the same.
+    assert_eq!(full.estimated_bytes() - half.estimated_bytes(), n * dim * 2);
+
+    // Context scans read the f16 records; rankings barely move.
+    let mut overlap = 0;
+    for query in &data[..20] {
+        let ids = |store: &ChronoMind| -> Vec<String> {
+            store
+                .search_in_context("", query, 10)
+                .unwrap()
+                .into_iter()
+                .map(|(m, _)| m.vector.id)
+                .collect()
+        };
+        let exact = ids(&full);
+        overlap += ids(&half).iter().filter(|id| exact.contains(id)).count();
+    }
+    assert!(overlap >= 190, "overlap {overlap}/200");
+}
+
+#[test]
+fn estimated_bytes_scale_linearly() {
+    let build = |n: usize| {
+        let store = ChronoMind::new(config(32)).unwrap();
+        for i in 0..n {
+            store
+                .insert(memory_in_context(
+                    &format!("m{i:05}"),
+                    vec![0.25; 32],
+                    "context",
+                ))
+                .unwrap();
+        }
+        store
+    };
+    let (single, double) = (build(500), build(1000));
+    let ratio = double.estimated_bytes() as f64 / single.estimated_bytes() as f64;
+    assert!((1.9..=2.1).contains(&ratio), "ratio {ratio}");
+
+    // Vectors alone: 1000 records and 1000 index copies of 32 f32s.
+    assert!(double.estimated_bytes() > 2 * 1000 * 32 * 4);
+    assert_eq!(double.stats().bytes_in_use, double.estimated_bytes());
+}
+
+#[test]
+fn detailed_hits_break_the_score_into_its_components() {
+    let mut cfg = config(2);
+    cfg.temporal_weight = 0.4;
+    let store = ChronoMind::new(cfg.clone()).unwrap();
+    let mut old = memory("old", vec![1.0, 0.0]);
+    old.attributes.timestamp = SystemTime::now() - Duration::from_secs(48 * 3600);
+    store.insert(old).unwrap();
+    store.insert(memory("new", vec![0.6, 0.8])).unwrap();
+
+    let hits = store.search_detailed(&[1.0, 0.0], 2).unwrap();
+    let plain = store.search(&[1.0, 0.0], 2).unwrap();
+    assert_eq!(hits.len(), 2);
+    for (hit, (memory, _)) in hits.iter().zip(&plain) {
+        assert_eq!(hit.memory.vector.id, memory.vector.id);
+        let w = cfg.temporal_weight;
+        let expected = (1.0 - w) * hit.distance / 2.0 + w * hit.temporal_penalty;
+        assert!((hit.score - expected).abs() < 1e-6);
+    }
+    let old = hits.iter().find(|h| h.memory.vector.id == "old").unwrap();
+    assert!(old.distance < 1e-6);
+    assert!(
+        old.temporal_penalty > 0.5,
+        "48h old: {}",
+        old.temporal_penalty
+    );
+    let new = hits.iter().find(|h| h.memory.vector.id == "new").unwrap();
+    assert!((new.distance - 0.4).abs() < 1e-5);
+    assert!(new.temporal_penalty < 1e-3);
+}
+
+#[test]
+fn similarity_search_reports_clamped_cosine() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    store.insert(memory("same", vec![2.0, 0.0])).unwrap();
+    store.insert(memory("diagonal", vec![1.0, 1.0])).unwrap();
+    store.insert(memory("orthogonal", vec![0.0, 3.0])).unwrap();
+    store.insert(memory("opposite", vec![-1.0, 0.0])).unwrap();
+
+    let hits = store.search_similarity(&[1.0, 0.0], 4).unwrap();
+    let ids: Vec<&str> = hits.iter().map(|(m, _)| m.vector.id.as_str()).collect();
+    assert_eq!(ids, ["same", "diagonal", "orthogonal", "opposite"]);
+    assert!((hits[0].1 - 1.0).abs() < 1e-6);
+    assert!((hits[1].1 - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-5);
+    assert!(hits[2].1.abs() < 1e-6);
+    assert_eq!(hits[3].1, 0.0, "negative cosine clamps to zero");
+}
+
+#[test]
+fn small_stores_are_scanned_exactly() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(3);
+    let data: Vec<Vec<f32>> = (0..300)
+        .map(|_| (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    let build = |brute_force_threshold| {
+        let store = ChronoMind::new(Config {
+            dimensions: 16,
+            temporal_weight: 0.0,
+            brute_force_threshold,
+            ..Config::default()
+        })
+        .unwrap();
+        for (i, v) in data.iter().enumerate() {
+            store.insert(memory(&format!("m{i}"), v.clone())).unwrap();
+        }
+        store
+    };
+    let query: Vec<f32> = (0..16).map(|_| rng.gen_range(-1.0..1.0)).collect();
+
+    // Manual scan: cosine distance to every vector.
+    let cosine = |v: &[f32]| {
+        let dot: f32 = v.iter().zip(&query).map(|(a, b)| a * b).sum();
+        let norm = |x: &[f32]| x.iter().map(|c| c * c).sum::<f32>().sqrt();
+        1.0 - dot / (norm(v) * norm(&query))
+    };
+    let mut expected: Vec<(usize, f32)> = data.iter().map(|v| cosine(v)).enumerate().collect();
+    expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let expected: Vec<String> = expected[..20]
+        .iter()
+        .map(|(i, _)| format!("m{i}"))
+        .collect();
+
+    let (results, stats) = build(1_000).search_with_stats(&query, 20).unwrap();
+    assert!(stats.exhaustive);
+    assert_eq!(stats.nodes_visited, 300);
+    assert_eq!(stats.distance_computations, 300);
+    assert_eq!(stats.layers_traversed, 0);
+    let ids: Vec<String> = results.into_iter().map(|(m, _)| m.vector.id).collect();
+    assert_eq!(ids, expected);
+
+    let (results, stats) = build(300).search_with_stats(&query, 20).unwrap();
+    assert!(!stats.exhaustive, "a store at the threshold uses the graph");
+    assert!(stats.layers_traversed >= 1 && stats.distance_computations > 0);
+    assert_eq!(results.len(), 20);
+}
+
+#[test]
+fn remove_many_counts_only_present_ids() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    for (i, id) in ["a", "b", "c"].into_iter().enumerate() {
+        store.insert(memory(id, vec![1.0, i as f32])).unwrap();
+    }
+
+    assert_eq!(store.remove_many(&["a", "missing", "c", "a"]), 2);
+    assert_eq!(store.len(), 1);
+    assert!(store.get("b").is_some());
+    assert_eq!(store.remove_many(&[]), 0);
+}
+
+#[test]
+fn clear_leaves_an_empty_searchable_store() {
+    let mut store = ChronoMind::new(config(2)).unwrap();
+    store.insert(memory("a", vec![1.0, 0.0])).unwrap();
+    store.insert(memory("b", vec![0.0, 1.0])).unwrap();
+    store.remove("b");
+
+    store.clear().unwrap();
+    assert!(store.is_empty());
+    assert!(store.search(&[1.0, 0.0], 5).unwrap().is_empty());
+    assert_eq!(store.index_stats().tombstoned, 0);
+    assert!(store.index_stats().nodes_per_layer.is_empty());
+
+    store.insert(memory("c", vec![1.0, 0.0])).unwrap();
+    assert_eq!(store.search(&[1.0, 0.0], 5).unwrap()[0].0.vector.id, "c");
+
+    let mut frozen = store.into_read_only();
+    assert!(matches!(frozen.clear(), Err(Error::ReadOnly)));
+    assert_eq!(frozen.remove_many(&["c"]), 0);
+    assert_eq!(frozen.len(), 1);
+}
+
+#[test]
+fn renamed_contexts_move_their_memories() {
+    let mut store = ChronoMind::new(config(2)).unwrap();
+    store
+        .insert(memory_in_context("a", vec![1.0, 0.0], "drafts"))
+        .unwrap();
+    store
+        .insert(memory_in_context("b", vec![0.9, 0.1], "drafts"))
+        .unwrap();
+    store
+        .insert(memory_in_context("c", vec![0.0, 1.0], "other"))
+        .unwrap();
+
+    assert_eq!(store.rename_context("drafts", "final").unwrap(), 2);
+    assert!(store
+        .search_in_context("drafts", &[1.0, 0.0], 5)
+        .unwrap()
+        .is_empty());
+    let mut moved: Vec<String> = store
+        .search_in_context("final", &[1.0, 0.0], 5)
+        .unwrap()
+        .into_iter()
+        .map(|(m, _)| m.vector.id)
+        .collect();
+    moved.sort();
+    assert_eq!(moved, ["a", "b"]);
+    // The index still serves them, now under the new label.
+    let hit = &store.search(&[1.0, 0.0], 1).unwrap()[0].0;
+    assert_eq!(hit.attributes.context, "final");
+
+    assert_eq!(store.rename_context("missing", "x").unwrap(), 0);
+    assert!(matches!(
+        store.rename_context("other", ""),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert_eq!(store.get("c").unwrap().attributes.context, "other");
+}
+
+#[test]
+fn context_centroids_follow_membership_changes() {
+    let mut store = ChronoMind::new(config(2)).unwrap();
+    assert_eq!(store.context_centroid("work"), None);
+    store
+        .insert(memory_in_context("a", vec![1.0, 0.0], "work"))
+        .unwrap();
+    assert_eq!(store.context_centroid("work"), Some(vec![1.0, 0.0]));
+
+    store
+        .insert(memory_in_context("b", vec![0.0, 1.0], "work"))
+        .unwrap();
+    assert_eq!(store.context_centroid("work"), Some(vec![0.5, 0.5]));
+    assert_eq!(
+        store.context_summary("work").unwrap().centroid,
+        vec![0.5, 0.5]
+    );
+
+    // Re-labeling a memory by re-inserting it moves it between contexts.
+    store
+        .insert(memory_in_context("b", vec![0.0, 1.0], "home"))
+        .unwrap();
+    assert_eq!(store.context_centroid("work"), Some(vec![1.0, 0.0]));
+    assert_eq!(store.context_centroid("home"), Some(vec![0.0, 1.0]));
+
+    store.rename_context("home", "work").unwrap();
+    assert_eq!(store.context_centroid("home"), None);
+    assert_eq!(store.context_centroid("work"), Some(vec![0.5, 0.5]));
+
+    store.remove("a");
+    assert_eq!(store.context_centroid("work"), Some(vec![0.0, 1.0]));
+    store.clear().unwrap();
+    assert_eq!(store.context_centroid("work"), None);
+}
+
+#[test]
+fn classify_context_ranks_the_nearest_cluster_first() {
+    let store = ChronoMind::new(config(3)).unwrap();
+    let clusters = [
+        ("code", [1.0, 0.0, 0.0]),
+        ("cooking", [0.0, 1.0, 0.0]),
+        ("travel", [0.0, 0.0, 1.0]),
+    ];
+    for (context, center) in clusters {
+        for i in 0..5 {
+            let jitter = i as f32 * 0.05;
+            let v = center.iter().map(|c| c + jitter).collect();
+            store
+                .insert(memory_in_context(&format!("{context}{i}"), v, context))
+                .unwrap();
+        }
+    }
+    store
+        .insert(memory("unlabeled", vec![0.1, 0.9, 0.0]))
+        .unwrap();
+
+    let ranked = store.classify_context(&[0.1, 0.9, 0.0], 2).unwrap();
+    assert_eq!(ranked.len(), 2);
+    assert_eq!(ranked[0].0, "cooking");
+    assert!(ranked[0].1 > ranked[1].1);
+    assert!(ranked.iter().all(|(c, _)| !c.is_empty()));
+    assert_eq!(
+        store.classify_context(&[0.0, 0.0, 1.0], 10).unwrap().len(),
+        3
+    );
+    assert!(matches!(
+        store.classify_context(&[1.0], 1),
+        Err(Error::QueryDimensionMismatch { .. })
+    ));
+}
+
+#[test]
+fn reinforced_memories_outlast_their_peers() {
+    let store = ChronoMind::new(config(2)).unwrap();
+    let stale = SystemTime::now() - Duration::from_secs(24 * 3600);
+    for id in ["used", "ignored"] {
+        let mut m = memory(id, vec![1.0, 0.0]);
+        m.attributes.importance = 0.5;
+        m.attributes.last_access = stale;
+        store.insert(m).unwrap();
+    }
+
+    for _ in 0..3 {
+        store.reinforce("used", 0.1).unwrap();
+    }
+    let used = store.get("used").unwrap();
+    assert!((used.attributes.importance - 0.8).abs() < 1e-6);
+    assert_eq!(used.attributes.access_count, 3);
+
+    store.apply_decay();
+    let used = store.get("used").unwrap().attributes.importance;
+    let ignored = store.get("ignored").unwrap().attributes.importance;
+    assert!(used > 0.79, "a fresh access shields it from decay: {used}");
+    assert!(
+        ignored < 0.1,
+        "a day unaccessed at the base rate: {ignored}"
+    );
+
+    store.reinforce("used", 5.0).unwrap();
+    assert_eq!(store.get("used").unwrap().attributes.importance, 1.0);
+    assert!(matches!(
+        store.reinforce("missing", 0.1),
+        Err(Error::NotFound(_))
+    ));
+    assert!(matches!(
+        store.reinforce("used", -0.1),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn search_where_filters_on_nested_metadata() {
+    let build = |brute_force_threshold| {
+        let store = ChronoMind::new(Config {
+            dimensions: 2,
+            brute_force_threshold,
+            ..Config::default()
+        })
+        .unwrap();
+        for i in 0..200 {
+            let angle = i as f32 * 0.01;
+            let builder = Memory::builder(format!("m{i}"), vec![angle.cos(), angle.sin()]);
+            // Every tenth memory has no metadata at all.
+            let builder = match i % 10 {
+                0 => builder,
+                _ if i % 20 == 7 => builder.metadata(serde_json::json!({"doc": {"lang": "fr"}})),
+                _ => builder.metadata(serde_json::json!({"doc": {"lang": "en"}})),
+            };
+            store.insert(builder.build().unwrap()).unwrap();
+        }
+        store
+    };
+    let french = |m: &serde_json::Value| m["doc"]["lang"] == "fr";
+
+    for store in [build(0), build(1_000)] {
+        let results = store.search_where(&[1.0, 0.0], 5, french).unwrap();
+        let ids: Vec<&str> = results.iter().map(|(m, _)| m.vector.id.as_str()).collect();
+        assert_eq!(ids, ["m7", "m27", "m47", "m67", "m87"]);
+        assert!(results.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        // A predicate that accepts anything still skips memories without
+        // metadata.
+        let any = store.search_where(&[1.0, 0.0], 200, |_| true).unwrap();
+        assert_eq!(any.len(), 180);
+        assert!(store
+            .search_where(&[1.0, 0.0], 5, |_| false)
+            .unwrap()
+            .is_empty());
+    }
+}
+
+#[test]
+fn pca_reduced_search_keeps_recall() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // 64-dimensional embeddings generated from 8 latent factors plus noise.
+    let mut rng = StdRng::seed_from_u64(11);
+    let basis: Vec<Vec<f32>> = (0..8)
+        .map(|_| (0..64).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    let sample = |rng: &mut StdRng| -> Vec<f32> {
+        let latent: Vec<f32> = (0..8).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        (0..64)
+            .map(|d| {
+                let signal: f32 = latent.iter().zip(&basis).map(|(l, b)| l * b[d]).sum();
+                signal + rng.gen_range(-0.02..0.02)
+            })
+            .collect()
+    };
+    let data: Vec<Vec<f32>> = (0..500).map(|_| sample(&mut rng)).collect();
+    let queries: Vec<Vec<f32>> = (0..20).map(|_| sample(&mut rng)).collect();
+
+    let settings = Config {
+        dimensions: 64,
+        temporal_weight: 0.0,
+        ..Config::default()
+    };
+    let refs: Vec<&[f32]> = data.iter().map(Vec::as_slice).collect();
+    let pca = Pca::fit(&refs, 8).unwrap();
+    assert!(
+        pca.explained_variance() > 0.95,
+        "{}",
+        pca.explained_variance()
+    );
+
+    let full = ChronoMind::new(settings.clone()).unwrap();
+    let reduced = ChronoMind::with_pca(settings, pca).unwrap();
+    for (i, v) in data.iter().enumerate() {
+        full.insert(memory(&format!("m{i}"), v.clone())).unwrap();
+        reduced.insert(memory(&format!("m{i}"), v.clone())).unwrap();
+    }
+    assert_eq!(reduced.get("m0").unwrap().vector.data, data[0]);
+
+    let mut hits = 0;
+    for q in &queries {
+        let expected: HashSet<String> = full
+            .search(q, 10)
+            .unwrap()
+            .into_iter()
+            .map(|(m, _)| m.vector.id)
+            .collect();
+        hits += reduced
+            .search(q, 10)
+            .unwrap()
+            .into_iter()
+            .filter(|(m, _)| expected.contains(&m.vector.id))
+            .count();
+    }
+    let recall = hits as f32 / (10 * queries.len()) as f32;
+    assert!(recall >= 0.8, "recall {recall}");
+
+    // The graph holds 8 components per memory instead of 64; only the
+    // links differ otherwise.
+    let without_links =
+        |store: &ChronoMind| store.estimated_bytes() - store.index_stats().total_connections * 4;
+    assert_eq!(
+        without_links(&full) - without_links(&reduced),
+        data.len() * (64 - 8) * 4
+    );
+
+    let wrong = Pca::fit(&[&[1.0, 0.0], &[0.0, 1.0]], 1).unwrap();
+    assert!(matches!(
+        ChronoMind::with_pca(config(64), wrong),
+        Err(Error::InvalidDimensions {
+            got: 2,
+            expected: 64
+        })
+    ));
+}
+
+#[test]
+fn feedback_pulls_in_more_of_the_cluster() {
+    let store = ChronoMind::new(Config {
+        dimensions: 2,
+        temporal_weight: 0.0,
+        ..Config::default()
+    })
+    .unwrap();
+    let at = |angle: f32| vec![angle.cos(), angle.sin()];
+    for i in 0..=10 {
+        store
+            .insert(memory(&format!("cluster{i}"), at(i as f32 * 0.02)))
+            .unwrap();
+    }
+    store.insert(memory("stray-a", at(0.45))).unwrap();
+    store.insert(memory("stray-b", at(0.50))).unwrap();
+    let query = at(0.30);
+    let ids = |results: Vec<(Memory, f32)>| -> Vec<String> {
+        results.into_iter().map(|(m, _)| m.vector.id).collect()
+    };
+
+    let plain = ids(store.search(&query, 4).unwrap());
+    assert!(plain.contains(&"stray-a".to_string()), "{plain:?}");
+    let expanded = ids(store.search_with_feedback(&query, 4, 3, 0.8).unwrap());
+    assert!(
+        expanded.iter().all(|id| id.starts_with("cluster")),
+        "{expanded:?}"
+    );
+    assert!(expanded.iter().any(|id| !plain.contains(id)));
+
+    assert_eq!(
+        ids(store.search_with_feedback(&query, 4, 3, 0.0).unwrap()),
+        plain
+    );
+    assert_eq!(
+        ids(store.search_with_feedback(&query, 4, 0, 0.8).unwrap()),
+        plain
+    );
+    assert!(matches!(
+        store.search_with_feedback(&query, 4, 3, 1.5),
+        Err(Error::InvalidArgument(_))
+    ));
+}
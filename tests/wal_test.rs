@@ -0,0 +1,167 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use chronomind::{Config, Memory, MemoryAttributes, Vector, WalStore};
+
+fn config() -> Config {
+    Config {
+        dimensions: 3,
+        ..Config::default()
+    }
+}
+
+fn memory(id: &str, data: Vec<f32>) -> Memory {
+    Memory::new(
+        Vector::new(id, data),
+        MemoryAttributes {
+            context: "wal".into(),
+            relationships: vec!["m0".into()],
+            ..MemoryAttributes::default()
+        },
+    )
+}
+
+fn populate(store: &WalStore) {
+    for i in 0..20 {
+        let x = i as f32;
+        store
+            .insert(memory(&format!("m{i}"), vec![x, 1.0, -x]))
+            .unwrap();
+    }
+    store.remove("m3").unwrap();
+    store.remove("missing").unwrap();
+    store.insert(memory("m4", vec![9.0, 9.0, 9.0])).unwrap();
+}
+
+fn contents(store: &WalStore) -> Vec<Memory> {
+    let mut memories = store.store().snapshot();
+    memories.sort_by(|a, b| a.vector.id.cmp(&b.vector.id));
+    memories
+}
+
+fn log_len(path: &Path) -> u64 {
+    fs::metadata(path).unwrap().len()
+}
+
+#[test]
+fn reopening_without_compaction_replays_the_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store.chrono");
+
+    let store = WalStore::create(&path, config()).unwrap();
+    populate(&store);
+    let before = contents(&store);
+    drop(store); // "crash": no compaction
+
+    let reopened = WalStore::open(&path).unwrap();
+    assert_eq!(contents(&reopened), before);
+    assert!(reopened.store().get("m3").is_none());
+    assert_eq!(reopened.store().get("m4").unwrap().vector.data, [9.0; 3]);
+
+    // Writes after a replay extend the same log.
+    reopened
+        .insert(memory("late", vec![1.0, 2.0, 3.0]))
+        .unwrap();
+    drop(reopened);
+    assert!(WalStore::open(&path).unwrap().store().get("late").is_some());
+}
+
+#[test]
+fn compaction_folds_the_log_into_the_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store.chrono");
+
+    let mut store = WalStore::create(&path, config()).unwrap();
+    let empty_log = log_len(store.log_path());
+    populate(&store);
+    assert!(log_len(store.log_path()) > empty_log);
+
+    store.compact().unwrap();
+    assert_eq!(log_len(store.log_path()), empty_log);
+    let before = contents(&store);
+    drop(store);
+
+    assert_eq!(contents(&WalStore::open(&path).unwrap()), before);
+    // The snapshot alone is a regular snapshot.
+    assert_eq!(
+        chronomind::load_snapshot(&path).unwrap().len(),
+        before.len()
+    );
+}
+
+#[test]
+fn torn_tail_is_truncated_on_open() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store.chrono");
+
+    let store = WalStore::create(&path, config()).unwrap();
+    populate(&store);
+    let before = contents(&store);
+    let log_path = store.log_path().to_path_buf();
+    let intact = log_len(&log_path);
+    drop(store);
+
+    // A crash mid-append: a frame header promising more than was written.
+    let mut log = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+    log.write_all(&[200, 0, 0, 0, 1, 2, 3, 4, 5]).unwrap();
+    drop(log);
+
+    let reopened = WalStore::open(&path).unwrap();
+    assert_eq!(contents(&reopened), before);
+    assert_eq!(log_len(&log_path), intact);
+}
+
+#[test]
+fn log_from_before_a_compaction_is_discarded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store.chrono");
+
+    let mut store = WalStore::create(&path, config()).unwrap();
+    populate(&store);
+    let stale = fs::read(store.log_path()).unwrap();
+    store.remove("m5").unwrap();
+    store.compact().unwrap();
+    let log_path = store.log_path().to_path_buf();
+    drop(store);
+
+    // Crash between the snapshot rename and the log rename: the old log
+    // survives next to the new snapshot. Replaying it would resurrect m5.
+    fs::write(&log_path, stale).unwrap();
+    let reopened = WalStore::open(&path).unwrap();
+    assert!(reopened.store().get("m5").is_none());
+    assert_eq!(reopened.store().len(), 18);
+}
+
+#[test]
+fn bit_flipped_record_stops_replay_cleanly() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store.chrono");
+
+    let store = WalStore::create(&path, config()).unwrap();
+    let log_path = store.log_path().to_path_buf();
+    store.insert(memory("first", vec![1.0, 2.0, 3.0])).unwrap();
+    let first_end = log_len(&log_path);
+    store.insert(memory("second", vec![4.0, 5.0, 6.0])).unwrap();
+    drop(store);
+
+    // Flip a bit in the second record's vector data: 5.0 becomes 5.0000005.
+    let mut bytes = fs::read(&log_path).unwrap();
+    let five = 5.0f32.to_le_bytes();
+    let at = first_end as usize
+        + bytes[first_end as usize..]
+            .windows(4)
+            .position(|w| w == five)
+            .unwrap();
+    bytes[at] ^= 0x01;
+    fs::write(&log_path, bytes).unwrap();
+
+    // The record's CRC no longer matches: it is dropped, not misread.
+    let reopened = WalStore::open(&path).unwrap();
+    assert_eq!(
+        reopened.store().get("first").unwrap().vector.data,
+        [1.0, 2.0, 3.0]
+    );
+    assert!(reopened.store().get("second").is_none());
+    assert_eq!(log_len(&log_path), first_end);
+}